@@ -8,6 +8,7 @@ import "@openzeppelin/contracts/access/Ownable.sol";
 import "@openzeppelin/contracts/utils/ReentrancyGuard.sol";
 import {ICoreWriter} from "./interfaces/ICore.sol";
 import {IHyperSwapRouter, IProjectXRouter} from "./interfaces/ISwapRouter.sol";
+import {IUniswapV3Pool} from "./interfaces/IUniswapV3Pool.sol";
 
 /**
  * @title Arbitrage
@@ -26,6 +27,14 @@ contract Arbitrage is Ownable, ReentrancyGuard {
     address public projectXRouter;
     bool public paused;
 
+    // Pools allowed to call the swap callback, set only for the duration of flashArb
+    address private flashBuyPool;
+    address private flashSellPool;
+
+    // TickMath bounds, used as "no price limit" on direct pool swaps
+    uint160 internal constant MIN_SQRT_RATIO = 4295128739;
+    uint160 internal constant MAX_SQRT_RATIO = 1461446703485210103287273052203988822378723970342;
+
     // Structs for arbitrage parameters
     struct PoolSwapParams {
         string dex;
@@ -39,6 +48,15 @@ contract Arbitrage is Ownable, ReentrancyGuard {
         address recipient;
     }
 
+    // Callback data of flashArb, ABI-encoded off-chain
+    struct FlashArbData {
+        address buyPool;
+        address sellPool;
+        address quoteToken;
+        address baseToken;
+        uint256 minProfit;
+    }
+
     struct SpotOrderParams {
         string baseToken;
         string quoteToken;
@@ -600,9 +618,126 @@ contract Arbitrage is Ownable, ReentrancyGuard {
         require(amountIn > 0, "Amount must be greater than 0");
         require(recipient != address(0), "Invalid recipient");
 
-        // Transfer tokens from sender and approve router
+        // Transfer tokens from sender, the router is approved per swap
         IERC20(tokenIn).safeTransferFrom(msg.sender, address(this), amountIn);
 
+        amountOut = _routerSwap(dex, tokenIn, tokenOut, fee, amountIn, amountOutMinimum, recipient, sqrtPriceLimitX96);
+
+        emit TokenSwap(tokenIn, tokenOut, amountIn, amountOut, recipient);
+        return amountOut;
+    }
+
+    /**
+     * @dev Execute pool-to-pool arbitrage atomically: buy on the cheaper pool, sell everything
+     *      received on the richer pool, revert unless the quote returned covers amountIn + minProfit
+     * @param buy Swap of quote for base on the cheaper pool (amountIn pulled from the caller)
+     * @param sell Swap of base for quote on the richer pool (amountIn ignored, the buy output is sold)
+     * @param minProfit Minimum quote profit in quote token units
+     */
+    function crossPoolArb(PoolSwapParams calldata buy, PoolSwapParams calldata sell, uint256 minProfit)
+        external
+        onlyOwner
+        nonReentrant
+        whenNotPaused
+        returns (uint256 profit)
+    {
+        require(buy.amountIn > 0, "Invalid buy amount");
+        require(buy.tokenIn == sell.tokenOut && buy.tokenOut == sell.tokenIn, "Legs must round-trip");
+
+        IERC20(buy.tokenIn).safeTransferFrom(msg.sender, address(this), buy.amountIn);
+
+        uint256 baseReceived = _routerSwap(
+            buy.dex, buy.tokenIn, buy.tokenOut, buy.poolFeeTier, buy.amountIn, buy.amountOutMin, address(this), 0
+        );
+        uint256 quoteReceived = _routerSwap(
+            sell.dex, sell.tokenIn, sell.tokenOut, sell.poolFeeTier, baseReceived, sell.amountOutMin, address(this), 0
+        );
+        require(quoteReceived >= buy.amountIn + minProfit, "Insufficient profit");
+
+        profit = quoteReceived - buy.amountIn;
+        IERC20(buy.tokenIn).safeTransfer(msg.sender, quoteReceived);
+
+        emit ArbitrageExecuted(sell.dex, buy.tokenIn, sell.tokenOut, buy.amountIn, quoteReceived, profit);
+    }
+
+    /**
+     * @dev Flash-swap arbitrage without inventory: swap quote for base on the cheaper pool, which sends the
+     *      base first; the callback sells it on the richer pool, repays the quote owed and keeps the rest.
+     *      Reverts unless the quote left covers data.minProfit. The profit is sent to the caller
+     * @param zeroForOne Direction of the quote -> base swap on the buy pool (quote is token0)
+     * @param quoteIn Quote owed to the buy pool (exact input)
+     * @param data ABI-encoded FlashArbData
+     */
+    function flashArb(bool zeroForOne, uint256 quoteIn, bytes calldata data)
+        external
+        onlyOwner
+        nonReentrant
+        whenNotPaused
+        returns (uint256 profit)
+    {
+        FlashArbData memory arb = abi.decode(data, (FlashArbData));
+        require(quoteIn > 0 && quoteIn <= uint256(type(int256).max), "Invalid quote amount");
+        require(arb.buyPool != arb.sellPool, "Pools must differ");
+
+        uint256 quoteBefore = IERC20(arb.quoteToken).balanceOf(address(this));
+        flashBuyPool = arb.buyPool;
+        flashSellPool = arb.sellPool;
+
+        IUniswapV3Pool(arb.buyPool).swap(
+            address(this), zeroForOne, int256(quoteIn), zeroForOne ? MIN_SQRT_RATIO + 1 : MAX_SQRT_RATIO - 1, data
+        );
+
+        flashBuyPool = address(0);
+        flashSellPool = address(0);
+
+        profit = IERC20(arb.quoteToken).balanceOf(address(this)) - quoteBefore;
+        require(profit >= arb.minProfit, "Insufficient profit");
+        IERC20(arb.quoteToken).safeTransfer(msg.sender, profit);
+
+        emit ArbitrageExecuted("flash", arb.quoteToken, arb.baseToken, quoteIn, quoteIn + profit, profit);
+    }
+
+    /**
+     * @dev Pool swap callback. From the buy pool: sell the base received on the sell pool and repay the quote
+     *      owed. From the sell pool: pay the base owed. Any other caller reverts
+     */
+    function uniswapV3SwapCallback(int256 amount0Delta, int256 amount1Delta, bytes calldata data) external {
+        require(msg.sender == flashBuyPool || msg.sender == flashSellPool, "Unauthorized callback");
+        FlashArbData memory arb = abi.decode(data, (FlashArbData));
+
+        // Positive delta = owed to the calling pool, negative = received from it
+        (uint256 owed, uint256 received) = amount0Delta > 0
+            ? (uint256(amount0Delta), uint256(-amount1Delta))
+            : (uint256(amount1Delta), uint256(-amount0Delta));
+
+        if (msg.sender == flashSellPool) {
+            IERC20(arb.baseToken).safeTransfer(msg.sender, owed);
+            return;
+        }
+
+        bool baseIsToken0 = arb.baseToken < arb.quoteToken;
+        (int256 sold0, int256 sold1) = IUniswapV3Pool(arb.sellPool).swap(
+            address(this), baseIsToken0, int256(received), baseIsToken0 ? MIN_SQRT_RATIO + 1 : MAX_SQRT_RATIO - 1, data
+        );
+        uint256 quoteOut = uint256(-(baseIsToken0 ? sold1 : sold0));
+        require(quoteOut >= owed + arb.minProfit, "Insufficient profit");
+
+        IERC20(arb.quoteToken).safeTransfer(msg.sender, owed);
+    }
+
+    /**
+     * @dev Swap tokens held by this contract through the DEX router
+     */
+    function _routerSwap(
+        string memory dex,
+        address tokenIn,
+        address tokenOut,
+        uint24 fee,
+        uint256 amountIn,
+        uint256 amountOutMinimum,
+        address recipient,
+        uint160 sqrtPriceLimitX96
+    ) internal returns (uint256 amountOut) {
         if (keccak256(bytes(dex)) == keccak256(bytes("hyperswap"))) {
             require(hyperSwapRouter != address(0), "HyperSwap router not set");
             IERC20(tokenIn).approve(hyperSwapRouter, amountIn);
@@ -637,9 +772,6 @@ contract Arbitrage is Ownable, ReentrancyGuard {
         } else {
             revert("Invalid DEX: must be 'hyperswap' or 'projectx'");
         }
-
-        emit TokenSwap(tokenIn, tokenOut, amountIn, amountOut, recipient);
-        return amountOut;
     }
 
     /**
@@ -0,0 +1,111 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.19;
+
+import {Test} from "forge-std/Test.sol";
+import {IERC20} from "@openzeppelin/contracts/token/ERC20/IERC20.sol";
+import {Arbitrage} from "../src/HyperArb.sol";
+import {IHyperSwapRouter} from "../src/interfaces/ISwapRouter.sol";
+
+/**
+ * @title HyperArb flash arbitrage fork test
+ * @notice Runs flashArb against the live wHYPE/USDT0 pools on a HyperEVM fork:
+ *         MAINNET_RPC_URL=<rpc> forge test --match-contract HyperArbForkTest
+ *         (skipped when MAINNET_RPC_URL is not set)
+ */
+contract HyperArbForkTest is Test {
+    address constant WHYPE = 0x5555555555555555555555555555555555555555;
+    address constant USDT0 = 0xB8CE59FC3717ada4C02eaDF9682A9e934F625ebb;
+    address constant HYPERSWAP_ROUTER = 0x6D99e7f6747AF2cDbB5164b6DD50e40D4fDe1e77;
+    // wHYPE/USDT0 0.05% pools (wHYPE is token0)
+    address constant HYPERSWAP_POOL = 0x337b56d87A6185cD46AF3Ac2cDF03CBC37070C30;
+    address constant PRJX_POOL = 0xBd19E19E4b70eB7F248695a42208bc1EdBBFb57D;
+    uint24 constant POOL_FEE = 500;
+
+    // Quote -> base on the buy pool is token1 -> token0
+    bool constant ZERO_FOR_ONE = false;
+    uint256 constant QUOTE_IN = 1_000e6;
+
+    Arbitrage public arbitrage;
+
+    function setUp() public {
+        string memory rpc = vm.envOr("MAINNET_RPC_URL", string(""));
+        if (bytes(rpc).length == 0) {
+            vm.skip(true);
+            return;
+        }
+        vm.createSelectFork(rpc);
+        arbitrage = new Arbitrage();
+    }
+
+    function flashData(address buyPool, address sellPool, uint256 minProfit) internal pure returns (bytes memory) {
+        return abi.encode(
+            Arbitrage.FlashArbData({
+                buyPool: buyPool,
+                sellPool: sellPool,
+                quoteToken: USDT0,
+                baseToken: WHYPE,
+                minProfit: minProfit
+            })
+        );
+    }
+
+    // Dump wHYPE into the Hyperswap pool so it trades well below the ProjectX pool
+    function openGap() internal {
+        uint256 amount = 20_000 ether;
+        deal(WHYPE, address(this), amount);
+        IERC20(WHYPE).approve(HYPERSWAP_ROUTER, amount);
+        IHyperSwapRouter(HYPERSWAP_ROUTER).exactInputSingle(
+            IHyperSwapRouter.ExactInputSingleParams({
+                tokenIn: WHYPE,
+                tokenOut: USDT0,
+                fee: POOL_FEE,
+                recipient: address(this),
+                amountIn: amount,
+                amountOutMinimum: 0,
+                sqrtPriceLimitX96: 0
+            })
+        );
+    }
+
+    function test_FlashArbProfitableRoundTrip() public {
+        openGap();
+        uint256 quoteBefore = IERC20(USDT0).balanceOf(address(this));
+
+        uint256 profit = arbitrage.flashArb(ZERO_FOR_ONE, QUOTE_IN, flashData(HYPERSWAP_POOL, PRJX_POOL, 1));
+
+        assertGt(profit, 0);
+        assertEq(IERC20(USDT0).balanceOf(address(this)) - quoteBefore, profit);
+        // Nothing is left behind in the contract
+        assertEq(IERC20(USDT0).balanceOf(address(arbitrage)), 0);
+        assertEq(IERC20(WHYPE).balanceOf(address(arbitrage)), 0);
+    }
+
+    function test_FlashArbRevertsBelowMinProfit() public {
+        openGap();
+        vm.expectRevert(bytes("Insufficient profit"));
+        arbitrage.flashArb(ZERO_FOR_ONE, QUOTE_IN, flashData(HYPERSWAP_POOL, PRJX_POOL, QUOTE_IN));
+    }
+
+    function test_FlashArbRevertsOnLosingRoundTrip() public {
+        // Buying on the rich pool and selling on the cheap one loses the gap and both fees
+        openGap();
+        vm.expectRevert(bytes("Insufficient profit"));
+        arbitrage.flashArb(ZERO_FOR_ONE, QUOTE_IN, flashData(PRJX_POOL, HYPERSWAP_POOL, 1));
+    }
+
+    function test_CallbackRejectsUnknownCaller() public {
+        vm.expectRevert(bytes("Unauthorized callback"));
+        arbitrage.uniswapV3SwapCallback(1, -1, flashData(HYPERSWAP_POOL, PRJX_POOL, 0));
+
+        // Pools are only authorized during flashArb
+        vm.prank(HYPERSWAP_POOL);
+        vm.expectRevert(bytes("Unauthorized callback"));
+        arbitrage.uniswapV3SwapCallback(1, -1, flashData(HYPERSWAP_POOL, PRJX_POOL, 0));
+    }
+
+    function test_FlashArbOnlyOwner() public {
+        vm.prank(address(0xBEEF));
+        vm.expectRevert();
+        arbitrage.flashArb(ZERO_FOR_ONE, QUOTE_IN, flashData(HYPERSWAP_POOL, PRJX_POOL, 0));
+    }
+}
@@ -3,20 +3,87 @@ use alloy::{
     primitives::Address,
     providers::{Provider, RootProvider},
 };
+use clap::{Parser, Subcommand};
 use eyre::Result;
 use shd::{
-    dex::pool_data::{calculate_pool_prices, get_pool_info},
-    types::{BotConfig, EnvConfig, PriceReference, load_bot_config_with_env},
+    cache::ReadCache,
+    control::{ControlHandle, InventorySnapshot, OpportunitySnapshot, PoolSnapshot},
+    data::{BalanceSnapshotRecord, DB_CHANNEL_CAPACITY, DbRecord, DbWriter, ExecutionRecord, OpportunityRecord, TradeRecord, instance_name},
+    detect::Candidates,
+    dex::discovery::PoolRegistry,
+    dex::pool_data::{PoolInfo, calculate_pool_tvl, get_pool_info},
+    dex::swap::BestOpportunity,
+    events::{ArbEvent, EVENT_CHANNEL_CAPACITY, EventBus},
+    metrics::METRICS,
+    portfolio::Portfolio,
+    recorder::{RECORDER_CHANNEL_CAPACITY, SpreadRecord, SpreadRecorder},
+    reload::SharedConfig,
+    stats::{MeanReversionState, ReversionAction, Signal, ThresholdState},
+    tx::TX_TRACKER,
+    types::{ArbTarget, BotConfig, EnvConfig, PriceReference, load_bot_config_with_env},
     utils::{evm::init_allowance, misc::log_gas_prices},
 };
-use std::str::FromStr;
-use tokio::{task, time};
+use std::{
+    collections::HashMap,
+    str::FromStr,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use tokio::{sync::watch, task, time};
 use tracing::Level;
 use tracing_subscriber::{EnvFilter, fmt};
 
 // Constants
 const BASIS_POINT_DENO: f64 = 10000.0; // Basis points denominator (1% = 100 bps)
 const INVENTORY_CHECK_INTERVAL_BLOCKS: u64 = 10; // Check inventory every N blocks
+const PORTFOLIO_SUMMARY_INTERVAL_BLOCKS: u64 = 100; // Mark portfolio and log summary every N blocks
+const HEAD_POLL_INTERVAL_MS: u64 = 250; // Chain head polling, target tasks run on their own poll_interval_ms
+const REPORT_DEFAULT_DAYS: i64 = 30; // Default history window of the report subcommand
+const MEAN_REVERSION_REPLAY_DAYS: i64 = 365; // Recorded fills replayed to restore open mean-reversion positions
+
+/// HyperEVM arbitrage bot
+#[derive(Debug, Parser)]
+#[command(name = "arbitrager", version, about)]
+struct Cli {
+    /// Bot configuration file
+    #[arg(long, default_value = "config/main.toml")]
+    config: String,
+    /// Environment file (wallets, database)
+    #[arg(long, default_value = "config/.env")]
+    env_file: String,
+    /// Simulate trades and approvals without broadcasting (overrides TESTING)
+    #[arg(long, conflicts_with = "live")]
+    dry_run: bool,
+    /// Broadcast trades (overrides TESTING)
+    #[arg(long)]
+    live: bool,
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Start the trading loop (default)
+    Run,
+    /// Validate the configuration, then check pools, tokens and DEX contracts on-chain
+    CheckConfig,
+    /// Log EVM and HyperCore balances of every target wallet
+    Balances,
+    /// Log reference prices and gas prices
+    Prices,
+    /// Check and set router allowances for every target (only reported with --dry-run)
+    Approve,
+    /// Run one detection pass for a target without broadcasting
+    Simulate {
+        /// Target vault name
+        target: String,
+    },
+    /// Daily and weekly PnL from the persisted trade history
+    Report {
+        #[arg(default_value_t = REPORT_DEFAULT_DAYS)]
+        days: i64,
+    },
+}
 
 // Inventory status for double leg mode
 #[derive(Debug)]
@@ -31,14 +98,51 @@ struct InventoryStatus {
     is_balanced: bool,
 }
 
+impl InventoryStatus {
+    // Inventory skew in [-1, 1]: -1 = all quote, 0 = 50/50, 1 = all base
+    fn skew(&self) -> f64 {
+        if self.total_value_usd > 0.0 { (self.base_percentage - 50.0) / 50.0 } else { 0.0 }
+    }
+}
+
+// Shared services and caches used by every target task (cheap to clone)
+#[derive(Clone)]
+struct BotContext {
+    db: DbWriter,
+    events: EventBus,
+    recorder: SpreadRecorder,
+    control: ControlHandle,
+    pools: PoolRegistry,
+    cache: ReadCache,
+    portfolio: Arc<Mutex<Portfolio>>,
+}
+
+// Per-target progress kept by its task between passes
+#[derive(Debug, Default)]
+struct TargetState {
+    last_block: Option<u64>,
+    last_inventory_check: Option<u64>,
+    last_portfolio_mark: Option<u64>,
+    mean_reversion: MeanReversionState,
+    thresholds: ThresholdState,
+}
+
+impl TargetState {
+    // True when `interval` blocks elapsed since `last` (or never done)
+    fn due(last: Option<u64>, current: u64, interval: u64) -> bool {
+        last.is_none_or(|last| current >= last + interval)
+    }
+}
+
 // Fetch price based on configured oracle reference
-async fn fetch_price_by_reference(reference: &PriceReference, symbol: &str, config: &BotConfig) -> Result<f64> {
-    match reference {
+async fn fetch_price_by_reference(reference: &PriceReference, symbol: &str, config: &BotConfig) -> shd::error::Result<f64> {
+    let start = std::time::Instant::now();
+    let result = match reference {
         PriceReference::Pyth => match symbol.to_uppercase().as_str() {
             "BTC" => shd::oracles::pyth::fetch_btc_usd_price().await,
             "ETH" => shd::oracles::pyth::fetch_eth_usd_price().await,
             "HYPE" | "WHYPE" => shd::oracles::fetch_hype_usd_price().await,
-            _ => Err(eyre::eyre!("Pyth oracle doesn't support {} price", symbol)),
+            _ => Err(shd::error::Error::Oracle(format!("Pyth oracle doesn't support {} price", symbol))),
         },
         PriceReference::Redstone => match symbol.to_uppercase().as_str() {
             "BTC" => shd::oracles::fetch_btc_usd_price().await,
@@ -53,11 +157,69 @@ async fn fetch_price_by_reference(reference: &PriceReference, symbol: &str, conf
             let hypercore = shd::oracles::Hypercore::new(config);
             hypercore.get_price(symbol).await
         }
+    };
+    METRICS.observe_oracle(&reference.to_string(), start.elapsed(), result.is_ok());
+    result
+}
+
+// Reference price shared by targets on the same pair within the cache window
+async fn cached_price(ctx: &BotContext, reference: &PriceReference, symbol: &str, config: &BotConfig) -> shd::error::Result<f64> {
+    ctx.cache
+        .prices
+        .get_or_fetch((reference.to_string(), symbol.to_uppercase()), fetch_price_by_reference(reference, symbol, config))
+        .await
+}
+
+// Pool state fetched once per block across targets
+async fn cached_pool_info<T: Network>(provider: RootProvider<T>, ctx: &BotContext, pool: Address, block: u64) -> shd::error::Result<PoolInfo>
+where
+    RootProvider<T>: Provider + Clone,
+{
+    let fetch = async {
+        let rpc_start = std::time::Instant::now();
+        let result = get_pool_info(provider, pool).await;
+        METRICS.observe_rpc("pool_info", rpc_start.elapsed(), result.is_ok());
+        result
+    };
+    ctx.cache.pools.get_or_fetch((pool, block), fetch).await
+}
+
+// USD price of the target's quote token, 1.0 for dollar-pegged quotes
+async fn cached_quote_price(ctx: &BotContext, target: &shd::types::ArbTarget, config: &BotConfig) -> shd::error::Result<f64> {
+    if target.quote_is_usd() {
+        return Ok(1.0);
+    }
+    cached_price(ctx, &target.reference, &target.quote_token, config).await
+}
+
+/// Pool TVL in USD, base valued at the reference price and quote at its USD price, cached for a minute
+async fn fetch_pool_tvl_usd<T: Network>(provider: RootProvider<T>, ctx: &BotContext, pool_info: &PoolInfo, target: &shd::types::ArbTarget, config: &BotConfig, reference_price: f64) -> Option<f64>
+where
+    RootProvider<T>: Provider + Clone,
+{
+    let fetch = async {
+        let quote_price = cached_quote_price(ctx, target, config).await.map_err(|e| format!("no {} price: {}", target.quote_token, e))?;
+        let (price0, price1) = if pool_info.token0.to_string().eq_ignore_ascii_case(&target.base_token_address) {
+            (reference_price, quote_price)
+        } else {
+            (quote_price, reference_price)
+        };
+        let rpc_start = std::time::Instant::now();
+        let tvl = calculate_pool_tvl(provider, pool_info, Some(price0), Some(price1)).await;
+        METRICS.observe_rpc("pool_tvl", rpc_start.elapsed(), tvl.is_ok());
+        tvl.map_err(|e| e.to_string())?.total_value_usd.ok_or_else(|| "token prices missing".to_string())
+    };
+    match ctx.cache.tvl.get_or_fetch(pool_info.address, fetch).await {
+        Ok(tvl) => Some(tvl),
+        Err(e) => {
+            tracing::debug!("Failed to fetch TVL of {}: {}", pool_info.address, e);
+            None
+        }
     }
 }
 
 // Helper function to fetch and log current balances
-async fn log_current_balances<T: Network>(_provider: RootProvider<T>, target: &shd::types::ArbTarget, env: &EnvConfig, config: &BotConfig, prefix: &str) -> Result<()>
+async fn log_current_balances<T: Network>(provider: RootProvider<T>, target: &shd::types::ArbTarget, env: &EnvConfig, config: &BotConfig, db: &DbWriter, prefix: &str) -> Result<()>
 where
     RootProvider<T>: Provider + Clone,
 {
@@ -72,7 +234,7 @@ where
 
     // Fetch token balances
     let (base_decimals, quote_decimals, base_balance_raw, quote_balance_raw) =
-        shd::utils::evm::get_token_info_and_balances(&config.global.rpc_endpoint, &format!("{:?}", wallet_address), &target.base_token_address, &target.quote_token_address)
+        shd::utils::evm::get_token_info_and_balances(&provider, &format!("{:?}", wallet_address), &target.base_token_address, &target.quote_token_address)
             .await
             .map_err(|e| eyre::eyre!(e))?;
 
@@ -104,11 +266,23 @@ where
         total_value_usd
     );
 
+    db.record(DbRecord::BalanceSnapshot(BalanceSnapshotRecord {
+        instance_name: instance_name(&target.vault_name),
+        label: prefix.to_string(),
+        base_token: target.base_token.clone(),
+        quote_token: target.quote_token.clone(),
+        base_balance,
+        quote_balance,
+        base_value_usd,
+        quote_value_usd,
+        total_value_usd,
+    }));
+
     Ok(())
 }
 
 // Check inventory balance for double leg mode
-async fn check_inventory_balance<T: Network>(_provider: RootProvider<T>, target: &shd::types::ArbTarget, env: &EnvConfig, config: &BotConfig) -> Result<InventoryStatus>
+async fn check_inventory_balance<T: Network>(provider: RootProvider<T>, target: &shd::types::ArbTarget, env: &EnvConfig, config: &BotConfig) -> Result<InventoryStatus>
 where
     RootProvider<T>: Provider + Clone,
 {
@@ -123,7 +297,7 @@ where
 
     // Fetch token balances
     let (base_decimals, quote_decimals, base_balance_raw, quote_balance_raw) =
-        shd::utils::evm::get_token_info_and_balances(&config.global.rpc_endpoint, &format!("{:?}", wallet_address), &target.base_token_address, &target.quote_token_address)
+        shd::utils::evm::get_token_info_and_balances(&provider, &format!("{:?}", wallet_address), &target.base_token_address, &target.quote_token_address)
             .await
             .map_err(|e| eyre::eyre!(e))?;
 
@@ -163,389 +337,838 @@ where
 }
 
 // --- Main logic ---
-async fn run<T: Network>(config: BotConfig, env: &EnvConfig, provider: RootProvider<T>, current_block: u64)
+/// One detection and execution pass over every target (sequential, used by `simulate`)
+async fn run<T: Network>(mut config: BotConfig, env: &EnvConfig, provider: RootProvider<T>, ctx: &BotContext, current_block: u64)
 where
     RootProvider<T>: Provider + Clone,
 {
-    // For each vault
+    ctx.control.record_block(current_block);
+    // Runtime threshold overrides from the control API
+    for target in config.targets.iter_mut() {
+        ctx.control.apply_overrides(target);
+        ctx.pools.apply(target);
+    }
     for target in &config.targets {
-        // Check inventory balance for double leg mode targets (every N blocks)
-        // Do this BEFORE looking for opportunities to prevent execution if imbalanced
-        if !target.statistical_arb && current_block % INVENTORY_CHECK_INTERVAL_BLOCKS == 0 {
-            match check_inventory_balance(provider.clone(), &target, &env, &config).await {
-                Ok(status) => {
-                    if !status.is_balanced {
-                        tracing::warn!(
-                            "⚠️ INVENTORY IMBALANCE DETECTED in double-leg mode for {} (Block #{}):\n  \
-                            {} balance: {:.6} ({:.1}% of total)\n  \
-                            {} balance: {:.6} ({:.1}% of total)\n  \
-                            Total value: ${:.2} USD\n  \
-                            Skipping arbitrage - waiting for rebalancer algo to operate...",
-                            target.vault_name,
-                            current_block,
-                            status.base_token,
-                            status.base_balance,
-                            status.base_percentage,
-                            status.quote_token,
-                            status.quote_balance,
-                            status.quote_percentage,
-                            status.total_value_usd
-                        );
-                        // Skip this target if inventory is imbalanced
-                        continue;
-                    } else {
-                        tracing::info!(
-                            "✅ Inventory balanced for {} (Block #{}): {} {:.1}% / {} {:.1}%",
-                            target.vault_name,
-                            current_block,
-                            status.base_token,
-                            status.base_percentage,
-                            status.quote_token,
-                            status.quote_percentage
-                        );
-                    }
+        run_target(target, &config, env, provider.clone(), ctx, &mut TargetState::default(), current_block).await;
+    }
+}
+
+/// Detection and execution pass of one target at a block
+async fn run_target<T: Network>(target: &ArbTarget, config: &BotConfig, env: &EnvConfig, provider: RootProvider<T>, ctx: &BotContext, state: &mut TargetState, current_block: u64)
+where
+    RootProvider<T>: Provider + Clone,
+{
+    if ctx.control.is_paused(&target.vault_name) {
+        tracing::info!("⏸️ Target {} paused, skipping", target.vault_name);
+        return;
+    }
+
+    // Check inventory balance for double leg mode targets (every N blocks)
+    // Do this BEFORE looking for opportunities to prevent execution if imbalanced
+    if !target.statistical_arb && TargetState::due(state.last_inventory_check, current_block, INVENTORY_CHECK_INTERVAL_BLOCKS) {
+        state.last_inventory_check = Some(current_block);
+        match check_inventory_balance(provider.clone(), &target, &env, config).await {
+            Ok(status) => {
+                METRICS.inventory_ratio.with_label_values(&[target.vault_name.as_str()]).set(status.base_percentage / 100.0);
+                ctx.control.record_inventory(
+                    &target.vault_name,
+                    InventorySnapshot {
+                        base_token: status.base_token.clone(),
+                        quote_token: status.quote_token.clone(),
+                        base_balance: status.base_balance,
+                        quote_balance: status.quote_balance,
+                        base_percentage: status.base_percentage,
+                        quote_percentage: status.quote_percentage,
+                        total_value_usd: status.total_value_usd,
+                        is_balanced: status.is_balanced,
+                        timestamp: String::new(),
+                    },
+                );
+                if !status.is_balanced {
+                    tracing::warn!(
+                        "⚠️ INVENTORY IMBALANCE DETECTED in double-leg mode for {} (Block #{}):\n  \
+                        {} balance: {:.6} ({:.1}% of total)\n  \
+                        {} balance: {:.6} ({:.1}% of total)\n  \
+                        Total value: ${:.2} USD\n  \
+                        Skipping arbitrage - waiting for rebalancer algo to operate...",
+                        target.vault_name,
+                        current_block,
+                        status.base_token,
+                        status.base_balance,
+                        status.base_percentage,
+                        status.quote_token,
+                        status.quote_balance,
+                        status.quote_percentage,
+                        status.total_value_usd
+                    );
+                    ctx.events.publish(
+                        target,
+                        ArbEvent::InventoryImbalance {
+                            vault: target.vault_name.clone(),
+                            base_token: status.base_token.clone(),
+                            base_percentage: status.base_percentage,
+                            quote_token: status.quote_token.clone(),
+                            quote_percentage: status.quote_percentage,
+                            total_value_usd: status.total_value_usd,
+                        },
+                    );
+                    // Skip this target if inventory is imbalanced
+                    return;
+                } else {
+                    tracing::info!(
+                        "✅ Inventory balanced for {} (Block #{}): {} {:.1}% / {} {:.1}%",
+                        target.vault_name,
+                        current_block,
+                        status.base_token,
+                        status.base_percentage,
+                        status.quote_token,
+                        status.quote_percentage
+                    );
                 }
-                Err(e) => {
-                    tracing::error!("Failed to check inventory balance for {}: {}. Skipping target.", target.vault_name, e);
-                    continue;
+            }
+            Err(e) => {
+                tracing::error!("Failed to check inventory balance for {}: {}. Skipping target.", target.vault_name, e);
+                return;
+            }
+        }
+    }
+
+    tracing::info!("Monitoring target: {}", target.format_log_info());
+    // Get the wallet signer for this vault address
+    let wallet_signer = match env.get_signer_for_address(&target.address) {
+        Some(signer) => signer,
+        None => {
+            tracing::error!("No matching wallet found for vault: {}. Skipping vault.", target.format_log_info());
+            return;
+        }
+    };
+    let wallet_address = wallet_signer.address();
+    // Verify the vault address matches the wallet address (lowercase comparison)
+    if target.address.to_lowercase() != format!("0x{:x}", wallet_address).to_lowercase() {
+        tracing::error!("Target address mismatch for {}: expected 0x{:x} | Skipping target ...", target.format_log_info(), wallet_address);
+        return;
+    }
+
+    // Fetch reference price for base in quote token
+    let reference_price = match cached_price(ctx, &target.reference, &target.base_token, config).await {
+        Ok(price) => {
+            tracing::info!("{}/{} Reference price from {:?}: ${:.2}", target.base_token, target.quote_token, target.reference, price);
+            ctx.control.record_oracle(target, price);
+            price
+        }
+        Err(e) => {
+            tracing::warn!("Failed to fetch {} price from {:?}: {}", target.base_token, target.reference, e);
+            return; // Skip this target if we can't get the base price
+        }
+    };
+
+    // Adaptive mode: the executable threshold follows realised volatility and recent fill slippage
+    let adapted;
+    let target = match &target.adaptive_threshold {
+        Some(at) => {
+            state.thresholds.observe_price(current_block, reference_price, at.vol_window);
+            match state.thresholds.threshold(at, target.min_executable_spread_bps) {
+                Some(adaptive) => {
+                    tracing::info!(
+                        "🎚️ Adaptive threshold for {}: {:.2} bps (static {:.2} bps, vol {:.2} bps/block, fill slippage {:+.2} bps)",
+                        target.vault_name,
+                        adaptive.threshold_bps,
+                        target.min_executable_spread_bps,
+                        adaptive.realised_vol_bps,
+                        adaptive.mean_slippage_bps
+                    );
+                    let mut updated = target.clone();
+                    updated.min_executable_spread_bps = adaptive.threshold_bps;
+                    adapted = updated;
+                    &adapted
+                }
+                None => {
+                    tracing::debug!("Adaptive threshold for {} warming up, using static {:.2} bps", target.vault_name, target.min_executable_spread_bps);
+                    target
                 }
             }
         }
+        None => target,
+    };
+    METRICS.exec_threshold_bps.with_label_values(&[target.vault_name.as_str()]).set(target.min_executable_spread_bps);
+
+    // Cross-check the reference against HyperCore mid when events are published
+    if target.publish_events
+        && ctx.events.is_enabled()
+        && target.reference != PriceReference::Hypercore
+        && let Some(events_config) = &config.events
+    {
+        match cached_price(ctx, &PriceReference::Hypercore, &target.base_token, config).await {
+            Ok(hypercore_price) if hypercore_price > 0.0 => {
+                let divergence_bps = ((reference_price - hypercore_price) / hypercore_price) * BASIS_POINT_DENO;
+                if divergence_bps.abs() >= events_config.oracle_divergence_bps {
+                    tracing::warn!("🔀 {} {} price diverges from HyperCore by {:.2} bps", target.base_token, target.reference, divergence_bps);
+                    ctx.events.publish(
+                        target,
+                        ArbEvent::OracleDivergence {
+                            vault: target.vault_name.clone(),
+                            token: target.base_token.clone(),
+                            reference: target.reference.to_string(),
+                            reference_price,
+                            hypercore_price,
+                            divergence_bps,
+                        },
+                    );
+                }
+            }
+            Ok(_) => {}
+            Err(e) => tracing::debug!("Failed to fetch HyperCore {} price for divergence check: {}", target.base_token, e),
+        }
+    }
 
-        tracing::info!("Monitoring target: {}", target.format_log_info());
-        // Get the wallet signer for this vault address
-        let wallet_signer = match env.get_signer_for_address(&target.address) {
-            Some(signer) => signer,
-            None => {
-                tracing::error!("No matching wallet found for vault: {}. Skipping vault.", target.format_log_info());
-                continue;
+    // Mark-to-market portfolio (the first mark sets the starting inventory)
+    let marked = ctx.portfolio.lock().unwrap_or_else(|e| e.into_inner()).vaults.contains_key(&target.vault_name);
+    if TargetState::due(state.last_portfolio_mark, current_block, PORTFOLIO_SUMMARY_INTERVAL_BLOCKS) || !marked {
+        state.last_portfolio_mark = Some(current_block);
+        match shd::utils::evm::get_token_info_and_balances(&provider, &target.address, &target.base_token_address, &target.quote_token_address).await {
+            Ok((base_decimals, quote_decimals, base_balance_raw, quote_balance_raw)) => {
+                let base_balance = base_balance_raw as f64 / 10f64.powi(base_decimals as i32);
+                let quote_balance = quote_balance_raw as f64 / 10f64.powi(quote_decimals as i32);
+                let mut portfolio = ctx.portfolio.lock().unwrap_or_else(|e| e.into_inner());
+                let vault = portfolio.mark(target, base_balance, quote_balance, reference_price);
+                if vault.current_value_usd() > 0.0 {
+                    METRICS
+                        .inventory_ratio
+                        .with_label_values(&[target.vault_name.as_str()])
+                        .set(vault.current_base * reference_price / vault.current_value_usd());
+                }
+                vault.log_summary();
             }
-        };
-        let wallet_address = wallet_signer.address();
-        // Verify the vault address matches the wallet address (lowercase comparison)
-        if target.address.to_lowercase() != format!("0x{:x}", wallet_address).to_lowercase() {
-            tracing::error!("Target address mismatch for {}: expected 0x{:x} | Skipping target ...", target.format_log_info(), wallet_address);
-            continue;
+            Err(e) => tracing::warn!("Failed to mark portfolio for {}: {}", target.vault_name, e),
         }
+    }
 
-        // Fetch reference price for base in quote token
-        let reference_price = match fetch_price_by_reference(&target.reference, &target.base_token, &config).await {
-            Ok(price) => {
-                tracing::info!("{}/{} Reference price from {:?}: ${:.2}", target.base_token, target.quote_token, target.reference, price);
-                price
+    // Inventory skew drives asymmetric buy/sell thresholds in statistical-arb mode
+    let inventory_skew = if target.statistical_arb && target.inventory_skew_coefficient > 0.0 {
+        match check_inventory_balance(provider.clone(), target, env, config).await {
+            Ok(status) => {
+                let skew = status.skew();
+                tracing::info!(
+                    "Inventory skew for {}: {:+.2} ({} {:.1}% / {} {:.1}%) | Exec threshold: buy {:.2} bps, sell {:.2} bps",
+                    target.vault_name,
+                    skew,
+                    status.base_token,
+                    status.base_percentage,
+                    status.quote_token,
+                    status.quote_percentage,
+                    target.executable_threshold_bps(true, skew),
+                    target.executable_threshold_bps(false, skew)
+                );
+                skew
             }
             Err(e) => {
-                tracing::warn!("Failed to fetch {} price from {:?}: {}", target.base_token, target.reference, e);
-                continue; // Skip this target if we can't get the base price
+                tracing::warn!("Failed to fetch inventory for {}: {}. Using symmetric threshold.", target.vault_name, e);
+                0.0
             }
-        };
-
-        // Track the single best opportunity across all pools
-        // (dex, pool, price, spread_bps, fee_bps, net_profit_bps, pool_fee_tier)
-        let mut best_opportunity: Option<(String, String, f64, f64, f64, f64, u32)> = None;
-
-        // For double-leg arb: track all opportunities
-        let mut all_opportunities: Vec<shd::dex::swap::BestOpportunity> = Vec::new();
+        }
+    } else {
+        0.0
+    };
 
-        // >>>>> Hyperswap pools <<<<<
-        // tracing::info!("Hyperswap Pools:");
-        for pool_addr_str in &target.hyperswap_pools {
-            if pool_addr_str.is_empty() {
-                continue;
+    // Best net spread across all pools (exported even when below thresholds)
+    let mut best_net_bps = f64::NEG_INFINITY;
+
+    // Tradable pools for the mode of the target (best single pool, mean reversion, cross-pool, double leg)
+    let mut candidates = Candidates::default();
+
+    // For the spread recorder: one row per pool, sharing the block and oracle prices of this pass
+    let record_template = if ctx.recorder.is_enabled() {
+        let mut template = SpreadRecord {
+            block: current_block,
+            timestamp: chrono::Utc::now().timestamp(),
+            vault: target.vault_name.clone(),
+            reference: target.reference.to_string(),
+            reference_price,
+            ..Default::default()
+        };
+        template.set_oracle_price(&target.reference, reference_price);
+        for oracle in config.recorder.iter().flat_map(|r| &r.oracles).filter(|oracle| **oracle != target.reference) {
+            match cached_price(ctx, oracle, &target.base_token, config).await {
+                Ok(price) => template.set_oracle_price(oracle, price),
+                Err(e) => tracing::debug!("Failed to fetch {} price from {} for the recorder: {}", target.base_token, oracle, e),
             }
+        }
+        Some(template)
+    } else {
+        None
+    };
+    let mut spread_records: Vec<SpreadRecord> = Vec::new();
+
+    // >>>>> Hyperswap and ProjectX pools <<<<<
+    let listed = target
+        .hyperswap_pools
+        .iter()
+        .map(|pool| ("Hyperswap", pool))
+        .chain(target.prjx_pools.iter().map(|pool| ("ProjectX", pool)));
+    for (dex, pool_addr_str) in listed {
+        if pool_addr_str.is_empty() {
+            continue;
+        }
 
-            if let Ok(pool_addr) = Address::from_str(pool_addr_str) {
-                match get_pool_info(provider.clone(), pool_addr).await {
-                    Ok(pool_info) => {
-                        let price = calculate_pool_prices(&pool_info);
+        if let Ok(pool_addr) = Address::from_str(pool_addr_str) {
+            let pool_info_result = cached_pool_info(provider.clone(), ctx, pool_addr, current_block).await;
+            match pool_info_result {
+                Ok(pool_info) => {
+                    // Price of base_token in terms of quote_token, whatever the token order
+                    let Some(spread) = shd::detect::pool_spread(target, dex, pool_addr_str, &pool_info, reference_price, inventory_skew) else {
+                        tracing::warn!("Pool {} doesn't contain base token {}", &pool_addr_str[..10], target.base_token);
+                        continue;
+                    };
+                    let BestOpportunity {
+                        pool_price,
+                        spread_bps,
+                        fee_bps,
+                        net_profit_bps,
+                        ..
+                    } = spread.opportunity;
+                    METRICS.spread_bps.with_label_values(&[target.vault_name.as_str(), dex, pool_addr_str.as_str()]).set(spread_bps);
+                    best_net_bps = best_net_bps.max(net_profit_bps);
+
+                    tracing::debug!(
+                        " - {} | Pool: ${:.2} | Ref: ${:.2} | Spread: {:.1} bps | Net of pool fees: {:.1} bps",
+                        &pool_addr_str[..10],
+                        pool_price,
+                        reference_price,
+                        spread_bps,
+                        net_profit_bps
+                    );
 
-                        // Determine which price to use based on token order
-                        // We need the price of base_token in terms of quote_token
-                        let pool_price = if pool_info.token0.to_string().to_lowercase() == target.base_token_address.to_lowercase() {
-                            // base_token is token0, so we want token0/token1 price
-                            price.token0_price
-                        } else if pool_info.token1.to_string().to_lowercase() == target.base_token_address.to_lowercase() {
-                            // base_token is token1, so we want token1/token0 price
-                            price.token1_price
-                        } else {
-                            tracing::warn!("Pool {} doesn't contain base token {}", &pool_addr_str[..10], target.base_token);
-                            continue;
-                        };
+                    ctx.control.record_pool(PoolSnapshot {
+                        target: target.vault_name.clone(),
+                        dex: dex.to_string(),
+                        pool: pool_addr_str.clone(),
+                        pool_price,
+                        spread_bps,
+                        net_bps: net_profit_bps,
+                        block: current_block,
+                        timestamp: String::new(),
+                    });
+
+                    // Spread recorder: every pool, tradable or not
+                    if let Some(template) = &record_template {
+                        spread_records.push(SpreadRecord::for_pool(template, dex, pool_addr_str, &pool_info, pool_price, spread.base_is_token0));
+                    }
 
-                        let spread_bps = ((pool_price - reference_price) / reference_price) * BASIS_POINT_DENO;
-                        let fee_bps = (price.fee as f64) / 100.0; // Convert fee to basis points
-                        let net_profit_bps = spread_bps.abs() - fee_bps; // Single fee for one-way trade
+                    // Pool depth: in-range liquidity from the pool state, TVL when filtered or worth watching
+                    let active_liquidity = pool_info.liquidity.to::<u128>() as f64;
+                    let tvl_usd = if target.min_pool_tvl_usd > 0.0 || spread.is_watched(target) {
+                        fetch_pool_tvl_usd(provider.clone(), ctx, &pool_info, target, config, reference_price).await
+                    } else {
+                        None
+                    };
+                    if let Some(reason) = target.thin_pool_reason(active_liquidity, tvl_usd) {
+                        tracing::debug!(" - {} skipped, thin pool: {}", &pool_addr_str[..10], reason);
+                        continue;
+                    }
 
-                        tracing::debug!(
-                            " - {} | Pool: ${:.2} | Ref: ${:.2} | Spread: {:.1} bps | Net of pool fees: {:.1} bps",
+                    // Persist every pool above the watch threshold
+                    if spread.is_watched(target) {
+                        tracing::info!(
+                            "👀 {} {} | Spread: {:.1} bps | Net: {:.1} bps | TVL: {} | In-range L: {:.3e}",
+                            dex,
                             &pool_addr_str[..10],
+                            spread_bps,
+                            net_profit_bps,
+                            tvl_usd.map(|tvl| format!("${:.0}", tvl)).unwrap_or_else(|| "n/a".to_string()),
+                            active_liquidity
+                        );
+                        ctx.control.record_opportunity(OpportunitySnapshot {
+                            target: target.vault_name.clone(),
+                            dex: dex.to_string(),
+                            pool: pool_addr_str.clone(),
                             pool_price,
                             reference_price,
                             spread_bps,
-                            net_profit_bps
-                        );
-
-                        // Update best opportunity if this pool is better
-                        // Use min_executable_spread_bps as threshold (can be negative for lossy trades)
-                        if net_profit_bps >= target.min_executable_spread_bps && spread_bps.abs() >= target.min_watch_spread_bps {
-                            if best_opportunity.is_none() || net_profit_bps > best_opportunity.as_ref().unwrap().5 {
-                                best_opportunity = Some(("Hyperswap".to_string(), pool_addr_str.clone(), pool_price, spread_bps, fee_bps, net_profit_bps, price.fee));
-                            }
-                        }
-
-                        // For double-leg: collect all opportunities
-                        if !target.statistical_arb && target.reference == PriceReference::Hypercore {
-                            all_opportunities.push(shd::dex::swap::BestOpportunity {
-                                dex: "Hyperswap".to_string(),
-                                pool_address: pool_addr_str.clone(),
-                                pool_price,
-                                spread_bps,
-                                fee_bps,
-                                net_profit_bps,
-                                pool_fee_tier: price.fee,
-                            });
-                        }
-                    }
-                    Err(e) => {
-                        tracing::debug!("    Failed to fetch {}: {}", &pool_addr_str[..10], e);
+                            net_bps: net_profit_bps,
+                            executable: spread.is_executable(),
+                            tvl_usd,
+                            active_liquidity,
+                            block: current_block,
+                            timestamp: String::new(),
+                        });
+                        ctx.db.record(DbRecord::Opportunity(OpportunityRecord {
+                            instance_name: instance_name(&target.vault_name),
+                            block: current_block,
+                            dex: dex.to_string(),
+                            pool: pool_addr_str.clone(),
+                            pool_price,
+                            reference: target.reference.to_string(),
+                            reference_price,
+                            spread_bps,
+                            fee_bps,
+                            net_bps: net_profit_bps,
+                            executable: spread.is_executable(),
+                        }));
                     }
+
+                    // Candidates of every mode (feeds the spread window in mean-reversion mode)
+                    candidates.add(target, &spread, &pool_info, &mut state.mean_reversion);
+                }
+                Err(e) => {
+                    tracing::debug!("   - Failed to fetch {}: {}", &pool_addr_str[..10], e);
                 }
             }
         }
+    }
 
-        // >>>>> ProjectX pools <<<<<
-        // tracing::info!("ProjectX Pools:");
-        for pool_addr_str in &target.prjx_pools {
-            if pool_addr_str.is_empty() {
-                continue;
-            }
-
-            if let Ok(pool_addr) = Address::from_str(pool_addr_str) {
-                match get_pool_info(provider.clone(), pool_addr).await {
-                    Ok(pool_info) => {
-                        let price = calculate_pool_prices(&pool_info);
-
-                        // Determine which price to use based on token order
-                        // We need the price of base_token in terms of quote_token
-                        let pool_price = if pool_info.token0.to_string().to_lowercase() == target.base_token_address.to_lowercase() {
-                            // base_token is token0, so we want token0/token1 price
-                            price.token0_price
-                        } else if pool_info.token1.to_string().to_lowercase() == target.base_token_address.to_lowercase() {
-                            // base_token is token1, so we want token1/token0 price
-                            price.token1_price
-                        } else {
-                            tracing::warn!("Pool {} doesn't contain base token {}", &pool_addr_str[..10], target.base_token);
-                            continue;
-                        };
-
-                        let spread_bps = ((pool_price - reference_price) / reference_price) * BASIS_POINT_DENO;
-                        let fee_bps = (price.fee as f64) / 100.0; // Convert fee to basis points
-                        let net_profit_bps = spread_bps.abs() - fee_bps; // Single fee for one-way trade
+    if best_net_bps.is_finite() {
+        METRICS.best_net_bps.with_label_values(&[target.vault_name.as_str()]).set(best_net_bps);
+    }
+    ctx.recorder.record(spread_records);
 
-                        tracing::debug!(
-                            " - {} | Pool: ${:.2} | Ref: ${:.2} | Spread: {:.1} bps | Net of pool fees: {:.1} bps",
-                            &pool_addr_str[..10],
-                            pool_price,
-                            reference_price,
-                            spread_bps,
-                            net_profit_bps
-                        );
+    // Mean-reversion mode: trade the z-score of each pool spread instead of the fixed thresholds
+    if let Some(mr) = &target.mean_reversion {
+        for (opportunity, pool_info, stats) in candidates.reversion {
+            let (pool, Some(stats)) = (pool_info.address, stats) else {
+                continue;
+            };
+            let (position, action) = state.mean_reversion.action(mr, pool, &stats, &opportunity, reference_price, target.min_trade_value_usd);
+            tracing::info!(
+                "📐 {} {} | Spread: {:.1} bps | Mean: {:.1} bps | Stdev: {:.1} bps | z: {:+.2} | Half-life: {} | Samples: {}/{} | Position: {:+.6} {}",
+                opportunity.dex,
+                &opportunity.pool_address[..10],
+                opportunity.spread_bps,
+                stats.mean_bps,
+                stats.stdev_bps,
+                stats.z_score(opportunity.spread_bps),
+                stats.half_life.map(|h| format!("{:.1}", h)).unwrap_or_else(|| "n/a".to_string()),
+                stats.samples,
+                mr.min_samples,
+                position,
+                target.base_token
+            );
 
-                        // Update best opportunity if this pool is better
-                        // Use min_executable_spread_bps as threshold (can be negative for lossy trades)
-                        if net_profit_bps >= target.min_executable_spread_bps && spread_bps.abs() >= target.min_watch_spread_bps {
-                            if best_opportunity.is_none() || net_profit_bps > best_opportunity.as_ref().unwrap().5 {
-                                best_opportunity = Some(("ProjectX".to_string(), pool_addr_str.clone(), pool_price, spread_bps, fee_bps, net_profit_bps, price.fee));
-                            }
-                        }
+            let order = match action {
+                ReversionAction::Hold => continue,
+                ReversionAction::PositionLimit { exposure_usd } => {
+                    tracing::info!(
+                        "📐 Position limit reached for {}: ${:.2} open + ${:.2} > ${:.2}",
+                        target.vault_name,
+                        exposure_usd,
+                        mr.trade_size_usd,
+                        mr.max_position_usd
+                    );
+                    continue;
+                }
+                ReversionAction::Trade {
+                    signal: Signal::Enter { is_buy },
+                    order,
+                } => {
+                    tracing::info!("📐 Spread {} stdev from its mean - {} ${:.2}", mr.entry_z, if is_buy { "buying" } else { "selling" }, mr.trade_size_usd);
+                    order
+                }
+                ReversionAction::Trade { order, .. } => {
+                    tracing::info!("📐 Spread back within {} stdev - closing {:+.6} {}", mr.exit_z, position, target.base_token);
+                    order
+                }
+            };
 
-                        // For double-leg: collect all opportunities
-                        if !target.statistical_arb && target.reference == PriceReference::Hypercore {
-                            all_opportunities.push(shd::dex::swap::BestOpportunity {
-                                dex: "ProjectX".to_string(),
-                                pool_address: pool_addr_str.clone(),
-                                pool_price,
-                                spread_bps,
-                                fee_bps,
-                                net_profit_bps,
-                                pool_fee_tier: price.fee,
-                            });
+            // Targets sharing this wallet wait until the trade and its balance reads are done
+            let _wallet_lock = TX_TRACKER.lock_wallet(wallet_address).await;
+            if let Err(e) = log_current_balances(provider.clone(), target, env, config, &ctx.db, "Pre-Trade").await {
+                tracing::error!("Failed to log pre-trade balances: {}", e);
+            }
+            ctx.events.publish(
+                target,
+                ArbEvent::TradeSubmitted {
+                    vault: target.vault_name.clone(),
+                    dex: opportunity.dex.clone(),
+                    pool: opportunity.pool_address.clone(),
+                    is_buy: order.is_buy,
+                    spread_bps: opportunity.spread_bps,
+                },
+            );
+            // Tagged so the recorded fills restore the position after a restart
+            let result = shd::dex::swap::execute_pool_trade(provider.clone(), opportunity.clone(), order, target, env, config, reference_price)
+                .await
+                .map(|execution| {
+                    execution.map(|mut execution| {
+                        if let Some(pnl) = &mut execution.pnl {
+                            pnl.mean_reversion = true;
                         }
+                        execution
+                    })
+                });
+            if let Some(execution) = record_execution(ctx, target, current_block, &opportunity, result) {
+                if execution.success
+                    && let Some(pnl) = &execution.pnl
+                {
+                    state.mean_reversion.fill(pool, execution.is_buy, pnl.base_amount);
+                    if let Some(at) = &target.adaptive_threshold {
+                        state.thresholds.observe_slippage(pnl.slippage_bps, at.slippage_window);
                     }
-                    Err(e) => {
-                        tracing::debug!("   - Failed to fetch {}: {}", &pool_addr_str[..10], e);
-                    }
+                }
+                if let Err(e) = log_current_balances(provider.clone(), target, env, config, &ctx.db, "Post-Trade").await {
+                    tracing::error!("Failed to log post-trade balances: {}", e);
                 }
             }
         }
+        tracing::info!("Completed checks for target: {}", target.format_log_info());
+        return;
+    }
 
-        // Process the best opportunity if we found one
-        if let Some((dex, pool, price, spread, fee, net_profit, pool_fee_tier)) = best_opportunity {
-            tracing::info!("Best opportunity found:");
-            tracing::info!(
-                "{} {} | ${:.4} | Spread: {:.2} bps | Fee: {:.2} bps | Net of pool fees: {:.2} bps",
-                dex,
-                &pool[..10],
-                price,
-                spread,
-                fee,
-                net_profit
-            );
+    // Cross-pool mode: buy the cheapest pool and sell the richest when the round trip pays for both
+    // fees, price impact and gas (falls through to the reference checks otherwise)
+    if target.cross_pool.is_some() {
+        // Sized on the wallet balances: targets sharing this wallet wait until both legs and their balance reads are done
+        let _wallet_lock = TX_TRACKER.lock_wallet(wallet_address).await;
+        match shd::dex::swap_cross_pool::find_cross_pool_opportunity(provider.clone(), &candidates.cross_pool, target, env, config).await {
+            Ok(Some(opportunity)) => {
+                if let Err(e) = log_current_balances(provider.clone(), target, env, config, &ctx.db, "Pre-Cross-Pool").await {
+                    tracing::error!("Failed to log pre-trade balances: {}", e);
+                }
+                for (leg, is_buy) in [(&opportunity.buy, true), (&opportunity.sell, false)] {
+                    ctx.events.publish(
+                        target,
+                        ArbEvent::TradeSubmitted {
+                            vault: target.vault_name.clone(),
+                            dex: leg.dex.clone(),
+                            pool: leg.pool_address.clone(),
+                            is_buy,
+                            spread_bps: leg.spread_bps,
+                        },
+                    );
+                }
+                let legs = shd::dex::swap_cross_pool::execute_cross_pool(provider.clone(), &opportunity, target, env, config, reference_price).await;
+                let mut traded = false;
+                for (leg, result) in legs {
+                    traded |= record_execution(ctx, target, current_block, &leg, result).is_some();
+                }
+                if traded && let Err(e) = log_current_balances(provider.clone(), target, env, config, &ctx.db, "Post-Cross-Pool").await {
+                    tracing::error!("Failed to log post-trade balances: {}", e);
+                }
+                tracing::info!("Completed checks for target: {}", target.format_log_info());
+                return;
+            }
+            Ok(None) => {}
+            Err(e) => tracing::warn!("Cross-pool check failed for {}: {}", target.vault_name, e),
+        }
+    }
 
-            // Check if net profit exceeds executable threshold
-            if net_profit >= target.min_executable_spread_bps {
-                tracing::info!("Exceeds executable threshold ({} bps) - Ready to execute", target.min_executable_spread_bps);
-                // If statistical_arb is true : just buy/sell accordingly
-                if target.statistical_arb {
-                    tracing::info!("📈 Statistical arbitrage mode - executing trade");
+    // Process the best opportunity if we found one
+    if let Some((opportunity, _)) = candidates.best {
+        let BestOpportunity {
+            dex,
+            pool_address: pool,
+            pool_price: price,
+            spread_bps: spread,
+            fee_bps: fee,
+            net_profit_bps: net_profit,
+            ..
+        } = opportunity.clone();
+        tracing::info!("Best opportunity found:");
+        tracing::info!(
+            "{} {} | ${:.4} | Spread: {:.2} bps | Fee: {:.2} bps | Net of pool fees: {:.2} bps",
+            dex,
+            &pool[..10],
+            price,
+            spread,
+            fee,
+            net_profit
+        );
 
-                    // Log current balances before trade
-                    if let Err(e) = log_current_balances(provider.clone(), &target, &env, &config, "Pre-Trade").await {
-                        tracing::error!("Failed to log pre-trade balances: {}", e);
-                    }
+        // Check if net profit exceeds executable threshold
+        let exec_threshold = target.executable_threshold_bps(spread < 0.0, inventory_skew);
+        if net_profit >= exec_threshold {
+            tracing::info!("Exceeds executable threshold ({:.2} bps) - Ready to execute", exec_threshold);
+            ctx.events.publish(
+                target,
+                ArbEvent::OpportunityDetected {
+                    vault: target.vault_name.clone(),
+                    dex: dex.clone(),
+                    pool: pool.clone(),
+                    pool_price: price,
+                    reference_price,
+                    spread_bps: spread,
+                    net_bps: net_profit,
+                },
+            );
+            // If statistical_arb is true : just buy/sell accordingly
+            if target.statistical_arb {
+                tracing::info!("📈 Statistical arbitrage mode - executing trade");
+                // Targets sharing this wallet wait until the trade and its balance reads are done
+                let _wallet_lock = TX_TRACKER.lock_wallet(wallet_address).await;
+
+                // Log current balances before trade
+                if let Err(e) = log_current_balances(provider.clone(), &target, &env, config, &ctx.db, "Pre-Trade").await {
+                    tracing::error!("Failed to log pre-trade balances: {}", e);
+                }
 
-                    // Create BestOpportunity struct
-                    let opportunity = shd::dex::swap::BestOpportunity {
+                // Execute the swap
+                ctx.events.publish(
+                    target,
+                    ArbEvent::TradeSubmitted {
+                        vault: target.vault_name.clone(),
                         dex: dex.clone(),
-                        pool_address: pool.clone(),
-                        pool_price: price,
+                        pool: pool.clone(),
+                        is_buy: spread < 0.0,
                         spread_bps: spread,
-                        fee_bps: fee,
-                        net_profit_bps: net_profit,
-                        pool_fee_tier,
-                    };
-
-                    // Execute the swap
-                    match shd::dex::swap::execute_statistical_arbitrage(provider.clone(), opportunity, &target, &env, &config, reference_price).await {
-                        Ok(_) => {
-                            tracing::info!("Trade executed successfully");
-                            // Log new balances after trade
-                            if let Err(e) = log_current_balances(provider.clone(), &target, &env, &config, "Post-Trade").await {
-                                tracing::error!("Failed to log post-trade balances: {}", e);
-                            }
-                        }
-                        Err(e) => tracing::error!("Trade execution failed: {}", e),
+                    },
+                );
+                let result = shd::dex::swap::execute_statistical_arbitrage(provider.clone(), opportunity.clone(), &target, &env, config, reference_price).await;
+                if let Some(execution) = record_execution(ctx, target, current_block, &opportunity, result) {
+                    if let (Some(at), Some(pnl)) = (&target.adaptive_threshold, &execution.pnl)
+                        && execution.success
+                    {
+                        state.thresholds.observe_slippage(pnl.slippage_bps, at.slippage_window);
                     }
-                } else if target.reference == PriceReference::Hypercore {
-                    // Double-leg arbitrage mode (only for Hypercore reference)
-                    tracing::info!("🔄 Double-leg arbitrage mode - preparing parameters");
-
-                    // Find best buy opportunity (lowest price) and sell opportunity (highest price)
-                    let buy_opp = all_opportunities.iter().min_by(|a, b| a.pool_price.partial_cmp(&b.pool_price).unwrap());
-                    let sell_opp = all_opportunities.iter().max_by(|a, b| a.pool_price.partial_cmp(&b.pool_price).unwrap());
-
-                    if let (Some(buy), Some(sell)) = (buy_opp, sell_opp) {
-                        // Only proceed if there's a profitable spread
-                        if sell.pool_price > buy.pool_price {
-                            let spread_profit = ((sell.pool_price - buy.pool_price) / buy.pool_price) * BASIS_POINT_DENO;
-                            let total_fees = buy.fee_bps + sell.fee_bps;
-                            let net_profit = spread_profit - total_fees;
-
-                            if net_profit >= target.min_executable_spread_bps {
-                                tracing::info!("Found profitable double-leg opportunity:");
-                                tracing::info!("  Buy on {} at ${:.4}", buy.dex, buy.pool_price);
-                                tracing::info!("  Sell on {} at ${:.4}", sell.dex, sell.pool_price);
-                                tracing::info!("  Spread: {:.2} bps | Fees: {:.2} bps | Net of pool fees: {:.2} bps", spread_profit, total_fees, net_profit);
-
-                                // Prepare double-leg arbitrage
-                                match shd::dex::swap_double_leg::prepare_double_leg_arbitrage(provider.clone(), buy.clone(), sell.clone(), &target, &env, &config, reference_price).await {
-                                    Ok((pool_swap, spot_order, double_leg)) => {
-                                        tracing::info!("✅ Double-leg arbitrage prepared successfully");
-                                        tracing::info!("Pool swap params: {:?}", pool_swap);
-                                        tracing::info!("Spot order params: {:?}", spot_order);
-                                        tracing::info!("Expected profit: ${:.2}", double_leg.expected_profit_usd);
-
-                                        // Log current balances before execution
-                                        if let Err(e) = log_current_balances(provider.clone(), &target, &env, &config, "Pre-Double-Leg").await {
-                                            tracing::error!("Failed to log pre-trade balances: {}", e);
-                                        }
-
-                                        // Contract will use these params to execute atomically
-                                        // NOTE: When contract execution is implemented, add post-trade balance logging here
-                                    }
-                                    Err(e) => {
-                                        tracing::error!("Failed to prepare double-leg arbitrage: {}", e);
+                    // Log new balances after trade
+                    if let Err(e) = log_current_balances(provider.clone(), &target, &env, config, &ctx.db, "Post-Trade").await {
+                        tracing::error!("Failed to log post-trade balances: {}", e);
+                    }
+                }
+            } else if target.reference == PriceReference::Hypercore {
+                // Double-leg arbitrage mode (only for Hypercore reference)
+                tracing::info!("🔄 Double-leg arbitrage mode - preparing parameters");
+
+                // Find best buy opportunity (lowest price) and sell opportunity (highest price)
+                let buy_opp = candidates.double_leg.iter().min_by(|a, b| a.pool_price.partial_cmp(&b.pool_price).unwrap());
+                let sell_opp = candidates.double_leg.iter().max_by(|a, b| a.pool_price.partial_cmp(&b.pool_price).unwrap());
+
+                if let (Some(buy), Some(sell)) = (buy_opp, sell_opp) {
+                    // Only proceed if there's a profitable spread
+                    if sell.pool_price > buy.pool_price {
+                        let spread_profit = ((sell.pool_price - buy.pool_price) / buy.pool_price) * BASIS_POINT_DENO;
+                        let total_fees = buy.fee_bps + sell.fee_bps;
+                        let net_profit = spread_profit - total_fees;
+
+                        if net_profit >= target.min_executable_spread_bps {
+                            tracing::info!("Found profitable double-leg opportunity:");
+                            tracing::info!("  Buy on {} at ${:.4}", buy.dex, buy.pool_price);
+                            tracing::info!("  Sell on {} at ${:.4}", sell.dex, sell.pool_price);
+                            tracing::info!("  Spread: {:.2} bps | Fees: {:.2} bps | Net of pool fees: {:.2} bps", spread_profit, total_fees, net_profit);
+
+                            // Prepare double-leg arbitrage
+                            match shd::dex::swap_double_leg::prepare_double_leg_arbitrage(provider.clone(), buy.clone(), sell.clone(), &target, &env, config, reference_price).await {
+                                Ok((pool_swap, spot_order, double_leg)) => {
+                                    tracing::info!("✅ Double-leg arbitrage prepared successfully");
+                                    tracing::info!("Pool swap params: {:?}", pool_swap);
+                                    tracing::info!("Spot order params: {:?}", spot_order);
+                                    tracing::info!("Expected profit: ${:.2}", double_leg.expected_profit_usd);
+
+                                    // Log current balances before execution
+                                    if let Err(e) = log_current_balances(provider.clone(), &target, &env, config, &ctx.db, "Pre-Double-Leg").await {
+                                        tracing::error!("Failed to log pre-trade balances: {}", e);
                                     }
+
+                                    // Contract will use these params to execute atomically
+                                    // NOTE: When contract execution is implemented, add post-trade balance logging here
+                                }
+                                Err(e) => {
+                                    tracing::error!("Failed to prepare double-leg arbitrage: {}", e);
                                 }
-                            } else {
-                                tracing::info!("Double-leg net profit ({:.2} bps) below threshold", net_profit);
                             }
+                        } else {
+                            tracing::info!("Double-leg net profit ({:.2} bps) below threshold", net_profit);
                         }
-                    } else {
-                        tracing::info!("No double-leg opportunities found");
                     }
                 } else {
-                    tracing::info!("Double-leg arbitrage only supported with Hypercore reference");
+                    tracing::info!("No double-leg opportunities found");
                 }
             } else {
-                tracing::info!("Net profit ({:.2} bps) below executable threshold ({} bps)", net_profit, target.min_executable_spread_bps);
+                tracing::info!("Double-leg arbitrage only supported with Hypercore reference");
             }
         } else {
-            tracing::info!(
-                "No pools found meeting criteria (net profit >= {} bps and spread >= {} bps)",
-                target.min_executable_spread_bps,
-                target.min_watch_spread_bps
+            tracing::info!("Net profit ({:.2} bps) below executable threshold ({:.2} bps)", net_profit, exec_threshold);
+        }
+    } else {
+        tracing::info!(
+            "No pools found meeting criteria (net profit >= {} bps and spread >= {} bps)",
+            target.min_executable_spread_bps,
+            target.min_watch_spread_bps
+        );
+    }
+    tracing::info!("Completed checks for target: {}", target.format_log_info());
+}
+
+// Count, publish and persist the outcome of a pool trade, returning the swap when it reached the chain
+fn record_execution(
+    ctx: &BotContext, target: &ArbTarget, current_block: u64, opportunity: &shd::dex::swap::BestOpportunity, result: shd::error::Result<Option<shd::dex::swap::SwapExecution>>,
+) -> Option<shd::dex::swap::SwapExecution> {
+    let dex = &opportunity.dex;
+    let pool = &opportunity.pool_address;
+    match result {
+        Ok(Some(execution)) => {
+            tracing::info!("Trade executed successfully");
+            METRICS.inc_trade(&target.vault_name, "attempted");
+            METRICS.inc_trade(&target.vault_name, if execution.success { "succeeded" } else { "failed" });
+            METRICS.gas_spent_hype.with_label_values(&[target.vault_name.as_str()]).inc_by(execution.gas_paid_wei as f64 / 1e18);
+            let tx_hash = format!("0x{:x}", execution.tx_hash);
+            let event = if execution.success {
+                ArbEvent::TradeConfirmed {
+                    vault: target.vault_name.clone(),
+                    dex: dex.clone(),
+                    pool: pool.clone(),
+                    is_buy: execution.is_buy,
+                    tx_hash,
+                    net_pnl_usd: execution.pnl.as_ref().map(|p| p.net_pnl_usd),
+                }
+            } else {
+                ArbEvent::TradeFailed {
+                    vault: target.vault_name.clone(),
+                    dex: dex.clone(),
+                    pool: pool.clone(),
+                    tx_hash: Some(tx_hash),
+                    error: "reverted".to_string(),
+                }
+            };
+            ctx.events.publish(target, event);
+            ctx.db.record(DbRecord::Execution(ExecutionRecord::from_execution(target, current_block, &execution)));
+            if let Some(pnl) = &execution.pnl {
+                ctx.portfolio.lock().unwrap_or_else(|e| e.into_inner()).apply_trade(&target.vault_name, pnl);
+                ctx.control.record_trade(&target.vault_name, pnl.clone());
+                ctx.db.record(DbRecord::Trade(TradeRecord {
+                    instance_name: instance_name(&target.vault_name),
+                    pnl: pnl.clone(),
+                }));
+            }
+            Some(execution)
+        }
+        Ok(None) => {
+            tracing::info!("Trade skipped");
+            None
+        }
+        Err(e) => {
+            tracing::error!("Trade execution failed: {}", e);
+            METRICS.inc_trade(&target.vault_name, "attempted");
+            METRICS.inc_trade(&target.vault_name, "failed");
+            ctx.events.publish(
+                target,
+                ArbEvent::TradeFailed {
+                    vault: target.vault_name.clone(),
+                    dex: dex.clone(),
+                    pool: pool.clone(),
+                    tx_hash: None,
+                    error: e.to_string(),
+                },
             );
+            ctx.db.record(DbRecord::Execution(ExecutionRecord::failed(target, current_block, opportunity, e.to_string())));
+            None
         }
-        tracing::info!("Completed checks for target: {}", target.format_log_info());
     }
 }
 
-/// Main monitoring function that checks for new events and updates reserves
-async fn moni<T: Network>(config: BotConfig, env: EnvConfig, provider: RootProvider<T>)
-where
-    RootProvider<T>: Provider + Clone,
-{
-    let mut last: Option<u64> = None;
-    let mut time = std::time::SystemTime::now();
-    let interval = 250;
-    let mut _loop_count = 0u64; // Track number of loops for testing (currently unused)
-    tracing::info!("Starting monitoring with interval: {} ms", interval);
+/// Supervisor: polls the chain head, applies config reloads and keeps one task per target running
+async fn moni(config: SharedConfig, env: EnvConfig, provider: RootProvider<Ethereum>, ctx: BotContext) {
+    let mut active = config.current();
+    let (head_sender, head) = watch::channel(0u64);
+    let mut tasks: HashMap<String, task::JoinHandle<()>> = HashMap::new();
+    tracing::info!("Starting monitoring, chain head polled every {} ms", HEAD_POLL_INTERVAL_MS);
     loop {
-        match provider.get_block_number().await {
-            Ok(current) => match last {
-                Some(prev) => {
-                    // --- Fetch new logs ---
-                    let elapsed = time.elapsed().unwrap().as_millis() as u64;
-                    if elapsed > interval && current > prev {
-                        let delta = current - prev;
-                        tracing::info!("💎 New block range: [{}, {}] with a delta of {} blocks", prev, current, delta);
-                        // --- Main logic ---
-                        let _res = run(config.clone(), &env, provider.clone(), current).await;
-                        // --- End Main logic ---
-                        last = Some(current);
-                        time = std::time::SystemTime::now();
+        // --- Pick up a reloaded config ---
+        let latest = config.current();
+        if !Arc::ptr_eq(&active, &latest) {
+            ctx.control.set_config(&latest);
+            init_allowance(&latest, &env).await;
+            active = latest;
+        }
+
+        // --- One task per target, removed targets stop their own task ---
+        let finished: Vec<String> = tasks.iter().filter(|(_, handle)| handle.is_finished()).map(|(name, _)| name.clone()).collect();
+        for vault_name in finished {
+            let Some(handle) = tasks.remove(&vault_name) else { continue };
+            match handle.await {
+                Ok(()) => tracing::debug!("🧵 Task for {} stopped", vault_name),
+                Err(e) if e.is_panic() => tracing::error!("🧵 Task for {} panicked: {}, restarting it", vault_name, e),
+                Err(e) => tracing::error!("🧵 Task for {} failed: {}, restarting it", vault_name, e),
+            }
+        }
+        for target in &active.targets {
+            if !tasks.contains_key(&target.vault_name) {
+                tracing::info!("🧵 Starting task for {} (every {} ms)", target.vault_name, target.poll_interval_ms);
+                let handle = task::spawn(target_task(target.vault_name.clone(), config.clone(), env.clone(), provider.clone(), ctx.clone(), head.clone()));
+                tasks.insert(target.vault_name.clone(), handle);
+            }
+        }
+
+        // --- Chain head, shared with the target tasks ---
+        let rpc_start = std::time::Instant::now();
+        let block_number = provider.get_block_number().await;
+        METRICS.observe_rpc("block_number", rpc_start.elapsed(), block_number.is_ok());
+        match block_number {
+            Ok(current) => {
+                let previous = *head_sender.borrow();
+                if current > previous {
+                    if previous == 0 {
+                        tracing::info!("Starting block number: {}", current);
+                    } else {
+                        tracing::debug!("💎 New block #{} (+{})", current, current - previous);
                     }
+                    ctx.control.record_block(current);
+                    head_sender.send_replace(current);
                 }
-                None => {
-                    tracing::info!("Starting block number: {}", current);
-                    last = Some(current);
-                }
-            },
+            }
             Err(e) => {
                 tracing::error!("Error fetching block number: {}", e);
             }
         }
-        time::sleep(std::time::Duration::from_millis(interval)).await;
+        time::sleep(Duration::from_millis(HEAD_POLL_INTERVAL_MS)).await;
     }
 }
 
-#[tokio::main]
-async fn main() -> Result<()> {
-    // Initialize tracing subscriber and load configurations
-    let filter = EnvFilter::from_default_env();
-    fmt().with_max_level(Level::TRACE).with_env_filter(filter).init();
-    dotenv::from_filename("config/.env").ok();
-    let env = EnvConfig::new();
-    // let path = "config/main.demo.toml";
-    let path = "config/main.toml"; // ! @PROD
-    tracing::info!("Loading bot configuration from: {}", path);
-    let config = load_bot_config_with_env(path, &env);
+/// Open mean-reversion positions of a target, replayed from its recorded fills (flat without a database)
+async fn restore_mean_reversion(ctx: &BotContext, config: &SharedConfig, vault_name: &str) -> MeanReversionState {
+    let enabled = config.current().targets.iter().any(|t| t.vault_name == vault_name && t.mean_reversion.is_some());
+    let Some(db) = ctx.db.connection().filter(|_| enabled) else {
+        return MeanReversionState::default();
+    };
+    match shd::portfolio::load_trades(db, vault_name, MEAN_REVERSION_REPLAY_DAYS).await {
+        Ok(trades) => {
+            let state = MeanReversionState::replay(trades.iter().map(|(_, pnl)| pnl));
+            for (pool, position) in state.positions.iter().filter(|(_, p)| **p != 0.0) {
+                tracing::info!("📐 Restored {} position on {:?}: {:+.6}", vault_name, pool, position);
+            }
+            state
+        }
+        Err(e) => {
+            tracing::warn!("📐 Failed to restore mean-reversion positions of {}, starting flat: {}", vault_name, e);
+            MeanReversionState::default()
+        }
+    }
+}
 
-    // Log the initialization
-    tracing::info!("🔑 Multi-wallet system initialized with {} wallets", env.wallet_pub_keys.len());
+/// Run a target on its own poll interval, at most once per new block, until it leaves the config
+async fn target_task(vault_name: String, config: SharedConfig, env: EnvConfig, provider: RootProvider<Ethereum>, ctx: BotContext, head: watch::Receiver<u64>) {
+    let mut state = TargetState {
+        mean_reversion: restore_mean_reversion(&ctx, &config, &vault_name).await,
+        ..TargetState::default()
+    };
+    loop {
+        let active = config.current();
+        let Some(mut target) = active.targets.iter().find(|t| t.vault_name == vault_name).cloned() else {
+            tracing::info!("🧵 {} removed from config, stopping its task", vault_name);
+            let _ = METRICS.block_lag.remove_label_values(&[&vault_name]);
+            return;
+        };
+        let current = *head.borrow();
+        if current > state.last_block.unwrap_or_default() {
+            // Runtime threshold overrides from the control API
+            ctx.control.apply_overrides(&mut target);
+            ctx.pools.apply(&mut target);
+            let started = std::time::Instant::now();
+            run_target(&target, &active, &env, provider.clone(), &ctx, &mut state, current).await;
+            if started.elapsed().as_millis() as u64 > target.poll_interval_ms {
+                tracing::debug!("🐢 {} pass took {} ms (poll interval {} ms)", vault_name, started.elapsed().as_millis(), target.poll_interval_ms);
+            }
+            state.last_block = Some(current);
+        }
+        // Lag behind the chain head, the pass may have outlived several blocks
+        let chain_head = *head.borrow();
+        METRICS
+            .block_lag
+            .with_label_values(&[&vault_name])
+            .set(chain_head.saturating_sub(state.last_block.unwrap_or(chain_head)) as i64);
+        time::sleep(Duration::from_millis(target.poll_interval_ms)).await;
+    }
+}
 
-    // Log target configurations
+/// Log the configured targets and their thresholds
+fn log_targets(config: &BotConfig) {
     tracing::info!("📊 Configured {} arbitrage targets:", config.targets.len());
     for target in &config.targets {
         let hyperswap_count = target.hyperswap_pools.iter().filter(|p| !p.is_empty()).count();
@@ -567,23 +1190,14 @@ async fn main() -> Result<()> {
             target.poll_interval_ms
         );
         tracing::info!("Reference: {} | Statistical Arb: {}", target.reference, if target.statistical_arb { "Yes (EVM-only)" } else { "No" });
-    }
-
-    // Build HTTP provider using network's RPC
-    let provider = match config.global.rpc_endpoint.parse() {
-        Ok(parsed) => RootProvider::<Ethereum>::new_http(parsed),
-        Err(e) => {
-            tracing::error!("Failed to parse RPC URL: {}", e);
-            return Ok(());
+        if target.inventory_skew_coefficient > 0.0 {
+            tracing::info!("Inventory skew coefficient: {} bps", target.inventory_skew_coefficient);
         }
-    };
-    let current = shd::utils::misc::block(provider.clone()).await.unwrap();
-    tracing::info!("🚀 Launching monitoring, starting at block #{}", current);
-
-    // Fetch prices for all configured targets based on their oracle reference
-    tracing::info!("📊 Fetching prices for all configured targets...");
-    let mut hype_price = 0.0;
+    }
+}
 
+/// Log EVM and HyperCore spot balances of every target wallet
+async fn log_startup_balances(provider: &RootProvider<Ethereum>, config: &BotConfig, env: &EnvConfig) {
     // Initialize spot balance fetcher
     let spot_fetcher = match shd::core::spot::HyperliquidSpotBalances::new() {
         Ok(fetcher) => Some(fetcher),
@@ -608,7 +1222,7 @@ async fn main() -> Result<()> {
 
         // Fetch EVM balances
         if !target.base_token_address.is_empty() && !target.quote_token_address.is_empty() {
-            match shd::utils::evm::get_token_info_and_balances(&config.global.rpc_endpoint, &format!("{:?}", wallet_address), &target.base_token_address, &target.quote_token_address).await {
+            match shd::utils::evm::get_token_info_and_balances(provider, &format!("{:?}", wallet_address), &target.base_token_address, &target.quote_token_address).await {
                 Ok((base_decimals, quote_decimals, base_balance, quote_balance)) => {
                     let base_balance_formatted = base_balance as f64 / 10f64.powi(base_decimals as i32);
                     let quote_balance_formatted = quote_balance as f64 / 10f64.powi(quote_decimals as i32);
@@ -650,12 +1264,16 @@ async fn main() -> Result<()> {
             }
         }
     }
+}
 
+/// Log reference prices of every target, returns the HYPE price (0.0 if unavailable)
+async fn log_reference_prices(config: &BotConfig) -> f64 {
+    let mut hype_price = 0.0;
     for target in &config.targets {
         tracing::info!("Fetching prices for {} using {:?} oracle", target.vault_name, target.reference);
 
         // Fetch base token price
-        match fetch_price_by_reference(&target.reference, &target.base_token, &config).await {
+        match fetch_price_by_reference(&target.reference, &target.base_token, config).await {
             Ok(price) => {
                 tracing::info!("💰 {}/{} Price from {:?}: ${:.2}", target.base_token, "USD", target.reference, price);
                 if target.base_token.to_uppercase() == "HYPE" || target.base_token.to_uppercase() == "WHYPE" {
@@ -673,7 +1291,7 @@ async fn main() -> Result<()> {
 
         // Fetch quote token price if different from base
         if target.quote_token != target.base_token {
-            match fetch_price_by_reference(&target.reference, &target.quote_token, &config).await {
+            match fetch_price_by_reference(&target.reference, &target.quote_token, config).await {
                 Ok(price) => {
                     tracing::info!("💰 {}/{} Price from {:?}: ${:.2}", target.quote_token, "USD", target.reference, price);
                     if target.quote_token.to_uppercase() == "HYPE" || target.quote_token.to_uppercase() == "WHYPE" {
@@ -691,26 +1309,269 @@ async fn main() -> Result<()> {
         }
     }
 
-    // Log gas prices if we have HYPE price
+    hype_price
+}
+
+/// Discover pools of every target once ([discovery] section only)
+async fn refresh_discovered_pools(provider: &RootProvider<Ethereum>, config: &BotConfig, registry: &PoolRegistry) {
+    let Some(discovery) = &config.discovery else {
+        return;
+    };
+    for target in &config.targets {
+        let base_price = match fetch_price_by_reference(&target.reference, &target.base_token, config).await {
+            Ok(price) => price,
+            Err(e) => {
+                tracing::warn!("🔎 Skipping discovery for {}, no {} price: {}", target.vault_name, target.base_token, e);
+                continue;
+            }
+        };
+        let quote_price = if target.quote_is_usd() {
+            1.0
+        } else {
+            match fetch_price_by_reference(&target.reference, &target.quote_token, config).await {
+                Ok(price) => price,
+                Err(e) => {
+                    tracing::warn!("🔎 Skipping discovery for {}, no {} price: {}", target.vault_name, target.quote_token, e);
+                    continue;
+                }
+            }
+        };
+        match shd::dex::discovery::discover_target_pools(provider.clone(), config, discovery, target, base_price, quote_price).await {
+            Ok(pools) => registry.set(&target.vault_name, pools),
+            Err(e) => tracing::warn!("🔎 Pool discovery failed for {}: {}", target.vault_name, e),
+        }
+    }
+}
+
+/// Re-run pool discovery on the configured interval, following config reloads
+async fn discovery_task(config: SharedConfig, provider: RootProvider<Ethereum>, registry: PoolRegistry) {
+    loop {
+        let current = config.current();
+        let Some(interval) = current.discovery.as_ref().map(|d| d.interval_secs) else {
+            // Discovery disabled, check again after a reload
+            time::sleep(std::time::Duration::from_secs(60)).await;
+            continue;
+        };
+        refresh_discovered_pools(&provider, &current, &registry).await;
+        time::sleep(std::time::Duration::from_secs(interval)).await;
+    }
+}
+
+/// Build the provider of the configured RPC endpoints (failover on health and latency)
+fn build_provider(config: &BotConfig) -> Result<RootProvider<Ethereum>> {
+    Ok(shd::rpc::provider(&config.global)?)
+}
+
+/// Log gas prices valued at the HYPE price
+async fn log_gas(provider: &RootProvider<Ethereum>, hype_price: f64) {
     if hype_price > 0.0 {
-        let (_, _gas_gwei, _) = log_gas_prices(provider.clone(), hype_price).await.unwrap();
+        if let Err(e) = log_gas_prices(provider.clone(), hype_price).await {
+            tracing::warn!("Failed to fetch gas prices: {}", e);
+        }
     } else {
         tracing::warn!("HYPE price not available, skipping gas price calculation");
     }
+}
+
+/// Run a single detection pass for one target at the current block, never broadcasting
+async fn simulate(mut config: BotConfig, mut env: EnvConfig, vault_name: &str) -> Result<()> {
+    config.targets.retain(|t| t.vault_name == vault_name);
+    if config.targets.is_empty() {
+        return Err(eyre::eyre!("Unknown target '{}'", vault_name));
+    }
+    env.testing = true;
+    let provider = build_provider(&config)?;
+    let current = shd::utils::misc::block(provider.clone()).await?;
+    tracing::info!("🧪 Simulating {} at block #{} (dry run)", vault_name, current);
+    let ctx = BotContext {
+        db: DbWriter::disabled(),
+        events: EventBus::disabled(),
+        recorder: SpreadRecorder::disabled(),
+        control: ControlHandle::new(&config),
+        pools: PoolRegistry::default(),
+        cache: ReadCache::default(),
+        portfolio: Arc::default(),
+    };
+    refresh_discovered_pools(&provider, &config, &ctx.pools).await;
+    run(config, &env, provider, &ctx, current).await;
+    Ok(())
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    // Initialize tracing subscriber and load configurations
+    let filter = EnvFilter::from_default_env();
+    fmt().with_max_level(Level::TRACE).with_env_filter(filter).init();
+    let cli = Cli::parse();
+    dotenv::from_filename(&cli.env_file).ok();
+    let mut env = EnvConfig::new()?;
+    if cli.dry_run {
+        env.testing = true;
+        tracing::info!("🧪 Dry run: trades are simulated and never broadcast");
+    } else if cli.live {
+        env.testing = false;
+        tracing::info!("🔴 Live: trades are broadcast");
+    }
+    let path = cli.config.as_str();
+    tracing::info!("Loading bot configuration from: {}", path);
+    let config = load_bot_config_with_env(path, &env)?;
+
+    match cli.command.unwrap_or(Command::Run) {
+        Command::Run => {}
+        Command::CheckConfig => {
+            let report = shd::dex::preflight::check_chain(build_provider(&config)?, &config).await?;
+            if !report.is_ok() {
+                return Err(shd::error::Error::Config(format!("{} pre-flight check(s) failed", report.failures.len())).into());
+            }
+            tracing::info!("✅ Configuration {} is valid", path);
+            return Ok(());
+        }
+        Command::Balances => {
+            log_targets(&config);
+            log_startup_balances(&build_provider(&config)?, &config, &env).await;
+            return Ok(());
+        }
+        Command::Prices => {
+            let hype_price = log_reference_prices(&config).await;
+            log_gas(&build_provider(&config)?, hype_price).await;
+            return Ok(());
+        }
+        Command::Approve => {
+            init_allowance(&config, &env).await;
+            return Ok(());
+        }
+        Command::Simulate { target } => return simulate(config, env, &target).await,
+        Command::Report { days } => return Ok(shd::portfolio::report(&env.database_url, &config.targets, days).await?),
+    }
+
+    // Log the initialization
+    tracing::info!("🔑 Multi-wallet system initialized with {} wallets", env.wallet_pub_keys.len());
+    log_targets(&config);
+
+    let provider = build_provider(&config)?;
+    let current = shd::utils::misc::block(provider.clone()).await?;
+    tracing::info!("🚀 Launching monitoring, starting at block #{}", current);
+
+    // Log balances, reference prices and gas
+    log_startup_balances(&provider, &config, &env).await;
+    tracing::info!("📊 Fetching prices for all configured targets...");
+    let hype_price = log_reference_prices(&config).await;
+    log_gas(&provider, hype_price).await;
 
     init_allowance(&config, &env).await;
 
+    // Background DB writer (opportunities, executions, balance snapshots)
+    let db = match shd::data::spawn_writer(&env.database_url, DB_CHANNEL_CAPACITY).await {
+        Ok(writer) => {
+            tracing::info!("🗄️ DB writer started");
+            writer
+        }
+        Err(e) => {
+            tracing::warn!("🗄️ Failed to connect to database, records will not be persisted: {}", e);
+            DbWriter::disabled()
+        }
+    };
+
+    // Prometheus /metrics endpoint
+    if let Some(address) = config.global.metrics_listen_address.clone() {
+        task::spawn(async move {
+            if let Err(e) = shd::metrics::serve(&address).await {
+                tracing::error!("Metrics server stopped: {}", e);
+            }
+        });
+    }
+
+    // Event bus for targets with publish_events = true
+    let events = match &config.events {
+        Some(events_config) => shd::events::spawn_event_bus(events_config, EVENT_CHANNEL_CAPACITY),
+        None => EventBus::disabled(),
+    };
+    if events.is_enabled() {
+        let publishing: Vec<&str> = config.targets.iter().filter(|t| t.publish_events).map(|t| t.vault_name.as_str()).collect();
+        tracing::info!("📣 Event bus started, publishing for: {:?}", publishing);
+    }
+
+    // Spread recorder (history for threshold calibration and backtests)
+    let recorder = match &config.recorder {
+        Some(recorder_config) => match shd::recorder::spawn_spread_recorder(recorder_config, RECORDER_CHANNEL_CAPACITY) {
+            Ok(recorder) => {
+                tracing::info!("📼 Recording spreads to {} ({})", recorder_config.directory, recorder_config.format.extension());
+                recorder
+            }
+            Err(e) => {
+                tracing::error!("📼 Failed to start spread recorder, spreads will not be recorded: {}", e);
+                SpreadRecorder::disabled()
+            }
+        },
+        None => SpreadRecorder::disabled(),
+    };
+
+    // Control/status API
+    let control = ControlHandle::new(&config);
+    if let Some(address) = config.global.control_listen_address.clone() {
+        let control = control.clone();
+        let token = env.control_api_token.clone();
+        task::spawn(async move {
+            if let Err(e) = shd::control::serve(&address, control, token).await {
+                tracing::error!("Control API stopped: {}", e);
+            }
+        });
+    }
+
+    let pools = PoolRegistry::default();
+    let ctx = BotContext {
+        db,
+        events,
+        recorder: recorder.clone(),
+        control,
+        pools: pools.clone(),
+        cache: ReadCache::default(),
+        portfolio: Arc::default(),
+    };
+
+    // Hot-reload of the config file (file change or SIGHUP)
+    let shared = SharedConfig::new(config);
+    if let Err(e) = shd::reload::spawn_config_watcher(path, env.clone(), shared.clone()) {
+        tracing::error!("Failed to start config watcher, hot-reload disabled: {}", e);
+    }
+
+    // Periodic pool discovery from the DEX factories
+    task::spawn(discovery_task(shared.clone(), provider.clone(), pools));
+
     // Spawn a Tokio task that polls the block number
     let handle = task::spawn(async move {
-        let _config = config.clone();
+        let _config = shared.clone();
         let _provider = provider.clone();
         let _env = env.clone();
-        moni(_config, _env, _provider).await;
+        moni(_config, _env, _provider, ctx).await;
     });
-    // Await the polling task (never returns under normal operation)
-    match handle.await {
-        Ok(_) => tracing::info!("Polling task finished unexpectedly"),
-        Err(e) => tracing::error!("Polling task panicked: {}", e),
+    // Await the polling task (never returns under normal operation) or a shutdown signal
+    tokio::select! {
+        result = handle => match result {
+            Ok(_) => tracing::info!("Polling task finished unexpectedly"),
+            Err(e) => tracing::error!("Polling task panicked: {}", e),
+        },
+        signal = shutdown_signal() => match signal {
+            Ok(name) => tracing::info!("🛑 Received {}, shutting down", name),
+            Err(e) => tracing::error!("Failed to listen for shutdown signals: {}", e),
+        },
+    }
+
+    // Write the rows still buffered by the spread recorder
+    if recorder.is_enabled() {
+        match task::spawn_blocking(move || recorder.shutdown()).await {
+            Ok(()) => tracing::info!("📼 Spread recorder flushed"),
+            Err(e) => tracing::error!("📼 Failed to stop spread recorder: {}", e),
+        }
     }
     Ok(())
 }
+
+/// Wait for Ctrl-C or SIGTERM, returning the signal name
+async fn shutdown_signal() -> std::io::Result<&'static str> {
+    let mut terminate = tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())?;
+    tokio::select! {
+        result = tokio::signal::ctrl_c() => result.map(|_| "Ctrl-C"),
+        _ = terminate.recv() => Ok("SIGTERM"),
+    }
+}
@@ -0,0 +1,130 @@
+// Backtest of the configured targets over recorded spreads, or over history rebuilt from pool Swap logs
+use clap::Parser;
+use eyre::Result;
+use shd::{
+    backtest::{Backtest, FillModel},
+    recorder::{SpreadRecord, fetch_records, read_records},
+    types::{BotConfig, load_bot_config},
+    utils::misc::save_csv,
+};
+use tracing::Level;
+use tracing_subscriber::{EnvFilter, fmt};
+
+// Constants
+const DEFAULT_RECORDS_DIR: &str = "data/spreads"; // Without a [recorder] section
+
+/// Replay spread history through the arbitrager detection and sizing with simulated fills
+#[derive(Debug, Parser)]
+#[command(name = "backtest", version, about)]
+struct Cli {
+    /// Bot configuration file (targets, thresholds, gas limits)
+    #[arg(long, default_value = "config/main.toml")]
+    config: String,
+    /// Only replay this target (vault name)
+    #[arg(long)]
+    target: Option<String>,
+    /// Recorder files to replay (defaults to the [recorder] directory)
+    #[arg(long)]
+    records: Option<String>,
+    /// Rebuild history from the pool Swap logs from this block instead of reading recorder files
+    #[arg(long, requires = "to_block")]
+    from_block: Option<u64>,
+    /// Last block of the Swap log history
+    #[arg(long, requires = "from_block")]
+    to_block: Option<u64>,
+    /// Starting portfolio value of each target in USD
+    #[arg(long, default_value_t = 10_000.0)]
+    capital_usd: f64,
+    /// Share of the starting value held in base
+    #[arg(long, default_value_t = 0.5)]
+    base_ratio: f64,
+    /// Gas price of every transaction
+    #[arg(long, default_value_t = 1.0)]
+    gas_gwei: f64,
+    /// HYPE price valuing gas (defaults to the reference price of HYPE targets)
+    #[arg(long)]
+    hype_price: Option<f64>,
+    /// Adverse move of every fill on top of the V3 price impact
+    #[arg(long, default_value_t = 0.0)]
+    slippage_bps: f64,
+    /// Directory for the trades and inventory path CSV files of each target
+    #[arg(long)]
+    output: Option<String>,
+}
+
+// History of every target: fetched from Swap logs, or read from the recorder files
+async fn load_history(cli: &Cli, config: &BotConfig) -> Result<Vec<SpreadRecord>> {
+    if let (Some(from_block), Some(to_block)) = (cli.from_block, cli.to_block) {
+        let provider = shd::rpc::provider(&config.global)?;
+        let mut records = Vec::new();
+        for target in &config.targets {
+            tracing::info!("🔎 Rebuilding {} history from Swap logs, blocks {}..{}", target.vault_name, from_block, to_block);
+            records.extend(fetch_records(provider.clone(), target, config, from_block, to_block).await?);
+        }
+        records.sort_by_key(|r| r.block);
+        return Ok(records);
+    }
+    let directory = cli
+        .records
+        .clone()
+        .or_else(|| config.recorder.as_ref().map(|r| r.directory.clone()))
+        .unwrap_or_else(|| DEFAULT_RECORDS_DIR.to_string());
+    tracing::info!("📼 Reading spread records from {}", directory);
+    Ok(read_records(&directory)?)
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    // Initialize tracing subscriber and load configurations
+    let filter = EnvFilter::from_default_env();
+    fmt().with_max_level(Level::TRACE).with_env_filter(filter).init();
+    let cli = Cli::parse();
+    tracing::info!("Loading bot configuration from: {}", cli.config);
+    let mut config = load_bot_config(&cli.config)?;
+    if let Some(name) = &cli.target {
+        config.targets.retain(|t| &t.vault_name == name);
+        if config.targets.is_empty() {
+            return Err(eyre::eyre!("Unknown target '{}'", name));
+        }
+    }
+
+    let records = load_history(&cli, &config).await?;
+    tracing::info!(
+        "🧮 Replaying {} records | Gas: {} gwei | Extra slippage: {} bps | Capital: ${:.2}",
+        records.len(),
+        cli.gas_gwei,
+        cli.slippage_bps,
+        cli.capital_usd
+    );
+    if let Some(output) = &cli.output {
+        std::fs::create_dir_all(output)?;
+    }
+
+    let fills = FillModel {
+        gas_price_gwei: cli.gas_gwei,
+        hype_price: cli.hype_price,
+        slippage_bps: cli.slippage_bps,
+    };
+    for target in &config.targets {
+        let backtest = match Backtest::new(target, &config, fills.clone(), cli.capital_usd, cli.base_ratio) {
+            Ok(backtest) => backtest,
+            Err(e) => {
+                tracing::error!("Skipping {}: {}", target.vault_name, e);
+                continue;
+            }
+        };
+        let Some(report) = backtest.run(&records)? else {
+            tracing::warn!("No history with a {} price for {}", target.reference, target.vault_name);
+            continue;
+        };
+        report.log_summary();
+        if let Some(output) = &cli.output {
+            let trades = format!("{}/{}_trades.csv", output.trim_end_matches('/'), target.vault_name);
+            let inventory = format!("{}/{}_inventory.csv", output.trim_end_matches('/'), target.vault_name);
+            save_csv(&report.trades, &trades)?;
+            save_csv(&report.path, &inventory)?;
+            tracing::info!("🧮   Wrote {} and {}", trades, inventory);
+        }
+    }
+    Ok(())
+}
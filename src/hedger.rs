@@ -1 +1,336 @@
 // Dynamic delta neutral hedger to short the volatile inventory
+use alloy::{network::Ethereum, providers::RootProvider};
+use clap::Parser;
+use eyre::Result;
+use shd::{
+    core::{
+        api::{HyperLiquidAPI, HyperToken},
+        corewriter::{LimitOrder, TIF_IOC, place_limit_order, round_perp_price, round_size_down},
+        perps::HyperliquidPerps,
+        spot::HyperliquidSpotBalances,
+    },
+    types::{ArbTarget, BotConfig, EnvConfig, HedgeConfig, load_bot_config_with_env},
+};
+use tokio::{task, time};
+use tracing::Level;
+use tracing_subscriber::{EnvFilter, fmt};
+
+// Constants
+const BASIS_POINT_DENO: f64 = 10000.0; // Basis points denominator (1% = 100 bps)
+
+/// Perp hedger of the targets' base inventory on HyperCore
+#[derive(Debug, Parser)]
+#[command(name = "hedger", version, about)]
+struct Cli {
+    /// Bot configuration file
+    #[arg(long, default_value = "config/main.toml")]
+    config: String,
+    /// Environment file (wallets)
+    #[arg(long, default_value = "config/.env")]
+    env_file: String,
+    /// Log hedge orders without sending them (overrides TESTING)
+    #[arg(long, conflicts_with = "live")]
+    dry_run: bool,
+    /// Send hedge orders (overrides TESTING)
+    #[arg(long)]
+    live: bool,
+}
+
+// Hedge state for a single target
+struct HedgeState {
+    target: ArbTarget,
+    hedge: HedgeConfig,
+    asset: HyperToken,
+    started_at_ms: u64,
+}
+
+// Snapshot of a target's hedge after a cycle
+#[derive(Debug)]
+struct HedgeReport {
+    evm_base: f64,
+    core_base: f64,
+    perp_size: f64,
+    target_size: f64,
+    mid_price: f64,
+    unrealized_pnl: f64,
+    realized_pnl: f64,
+    funding: f64,
+}
+
+impl HedgeReport {
+    // Hedge PnL since start: realized (net of fees) + unrealized + funding received
+    fn hedge_pnl(&self) -> f64 {
+        self.realized_pnl + self.unrealized_pnl + self.funding
+    }
+}
+
+// Perp position hedging a base exposure (a short, only long base inventory is hedged)
+fn hedge_target_size(exposure: f64, hedge: &HedgeConfig) -> f64 {
+    -exposure.max(0.0) * hedge.hedge_ratio
+}
+
+// Signed perp order size needed to bring the position back to target, None when within the tolerance band
+fn hedge_order_size(target_size: f64, perp_size: f64, hedge: &HedgeConfig) -> Option<f64> {
+    let delta = target_size - perp_size;
+    let band = hedge.tolerance_ratio * target_size.abs();
+    if delta.abs() <= band { None } else { Some(delta) }
+}
+
+// Net base exposure across EVM (ERC20) and HyperCore spot balances
+async fn fetch_base_exposure(state: &HedgeState, wallet: &str, provider: &RootProvider<Ethereum>, spot: &HyperliquidSpotBalances) -> Result<(f64, f64)> {
+    let (base_decimals, _, base_balance_raw, _) = shd::utils::evm::get_token_info_and_balances(provider, wallet, &state.target.base_token_address, &state.target.quote_token_address)
+        .await
+        .map_err(|e| eyre::eyre!(e))?;
+    let evm_base = base_balance_raw as f64 / 10f64.powi(base_decimals as i32);
+
+    let core_base = spot
+        .get_specific_balances(wallet, &[state.hedge.spot_coin()])
+        .await?
+        .first()
+        .map(|b| b.total_as_f64())
+        .transpose()?
+        .unwrap_or(0.0);
+
+    Ok((evm_base, core_base))
+}
+
+// Run one hedge cycle for a target: read exposure, rebalance the perp short if needed, report PnL
+async fn hedge_target(
+    state: &HedgeState, env: &EnvConfig, config: &BotConfig, provider: &RootProvider<Ethereum>, perps: &HyperliquidPerps, spot: &HyperliquidSpotBalances, api: &HyperLiquidAPI,
+) -> Result<HedgeReport> {
+    let wallet = env
+        .get_signer_for_address(&state.target.address)
+        .ok_or_else(|| eyre::eyre!("No wallet found for target {}", state.target.vault_name))?;
+    let wallet_address = format!("{:?}", wallet.address());
+    let coin = &state.hedge.perp_coin;
+
+    let (evm_base, core_base) = fetch_base_exposure(state, &wallet_address, provider, spot).await?;
+    let exposure = evm_base + core_base;
+
+    let clearinghouse = perps.get_clearinghouse_state(&wallet_address).await?;
+    let position = clearinghouse.position(coin);
+    let perp_size = position.map(|p| p.size()).unwrap_or(0.0);
+    let unrealized_pnl = position.map(|p| p.unrealized_pnl_usd()).unwrap_or(0.0);
+
+    let mid_price = api.get_price(coin).await?;
+    let target_size = hedge_target_size(exposure, &state.hedge);
+
+    if let Some(delta) = hedge_order_size(target_size, perp_size, &state.hedge) {
+        let sz = round_size_down(delta.abs(), state.asset.sz_decimals);
+        let notional = sz * mid_price;
+
+        if notional < state.hedge.min_order_usd {
+            tracing::debug!("Hedge delta {:.6} {} (${:.2}) below minimum order ${:.2}", delta, coin, notional, state.hedge.min_order_usd);
+        } else {
+            let is_buy = delta > 0.0;
+            let offset = state.hedge.max_slippage_bps / BASIS_POINT_DENO;
+            let limit_px = round_perp_price(if is_buy { mid_price * (1.0 + offset) } else { mid_price * (1.0 - offset) }, state.asset.sz_decimals);
+            let order = LimitOrder {
+                asset: state.asset.asset_index,
+                is_buy,
+                limit_px,
+                sz,
+                // Buying back a short never flips the hedge long
+                reduce_only: is_buy && perp_size < 0.0,
+                tif: TIF_IOC,
+                cloid: 0,
+            };
+
+            tracing::info!(
+                "🛡️ Rebalancing hedge for {}: perp {:.6} -> target {:.6} {} | {} {} (${:.2})",
+                state.target.vault_name,
+                perp_size,
+                target_size,
+                coin,
+                if is_buy { "BUY" } else { "SELL" },
+                sz,
+                notional
+            );
+
+            if env.testing {
+                tracing::info!("🧪 TESTING MODE - Hedge order would be sent via CoreWriter: {:?}", order);
+            } else {
+                match place_limit_order(config, wallet.clone(), &order).await {
+                    Ok(tx_hash) => tracing::info!("✅ Hedge order sent: 0x{:x}", tx_hash),
+                    Err(e) => tracing::error!("Hedge order failed for {}: {}", state.target.vault_name, e),
+                }
+            }
+        }
+    }
+
+    let funding = perps.get_net_funding(&wallet_address, coin, state.started_at_ms).await?;
+    let realized_pnl = perps.get_realized_pnl(&wallet_address, coin, state.started_at_ms).await?;
+
+    Ok(HedgeReport {
+        evm_base,
+        core_base,
+        perp_size,
+        target_size,
+        mid_price,
+        unrealized_pnl,
+        realized_pnl,
+        funding,
+    })
+}
+
+// Hedge loop for a single target on its own cadence
+async fn hedge_loop(state: HedgeState, env: EnvConfig, config: BotConfig, provider: RootProvider<Ethereum>) {
+    let perps = HyperliquidPerps::new(&config.global.hyperliquid_api_endpoint);
+    let api = HyperLiquidAPI::new(&config.global.hyperliquid_api_endpoint);
+    let spot = match HyperliquidSpotBalances::new() {
+        Ok(fetcher) => fetcher,
+        Err(e) => {
+            tracing::error!("Failed to initialize spot balance fetcher for {}: {}", state.target.vault_name, e);
+            return;
+        }
+    };
+
+    let mut interval = time::interval(std::time::Duration::from_millis(state.hedge.poll_interval_ms));
+    loop {
+        interval.tick().await;
+        match hedge_target(&state, &env, &config, &provider, &perps, &spot, &api).await {
+            Ok(report) => {
+                tracing::info!(
+                    "🛡️ Hedge {} | Exposure: {:.6} {} (EVM {:.6} + Core {:.6}) | Perp: {:.6} (target {:.6}) @ ${:.4}",
+                    state.target.vault_name,
+                    report.evm_base + report.core_base,
+                    state.target.base_token,
+                    report.evm_base,
+                    report.core_base,
+                    report.perp_size,
+                    report.target_size,
+                    report.mid_price
+                );
+                tracing::info!(
+                    "🛡️ Hedge PnL {} | Realized: ${:.2} | Unrealized: ${:.2} | Funding: ${:.2} | Total: ${:.2}",
+                    state.target.vault_name,
+                    report.realized_pnl,
+                    report.unrealized_pnl,
+                    report.funding,
+                    report.hedge_pnl()
+                );
+            }
+            Err(e) => tracing::error!("Hedge cycle failed for {}: {}", state.target.vault_name, e),
+        }
+    }
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    // Initialize tracing subscriber and load configurations
+    let filter = EnvFilter::from_default_env();
+    fmt().with_max_level(Level::TRACE).with_env_filter(filter).init();
+    let cli = Cli::parse();
+    dotenv::from_filename(&cli.env_file).ok();
+    let mut env = EnvConfig::new()?;
+    if cli.dry_run {
+        env.testing = true;
+        tracing::info!("🧪 Dry run: hedge orders are logged and never sent");
+    } else if cli.live {
+        env.testing = false;
+        tracing::info!("🔴 Live: hedge orders are sent");
+    }
+    let path = cli.config.as_str();
+    tracing::info!("Loading bot configuration from: {}", path);
+    let config = load_bot_config_with_env(path, &env)?;
+
+    let api = HyperLiquidAPI::new(&config.global.hyperliquid_api_endpoint);
+    let provider = shd::rpc::provider(&config.global)?;
+    let started_at_ms = chrono::Utc::now().timestamp_millis() as u64;
+
+    let mut handles = Vec::new();
+    for target in &config.targets {
+        let Some(hedge) = target.hedge.clone() else {
+            tracing::info!("Target {} has no hedge configured, skipping", target.vault_name);
+            continue;
+        };
+
+        // Perp asset index and szDecimals from the meta universe
+        let asset = match api.get_token_by_symbol(&hedge.perp_coin).await {
+            Ok(Some(asset)) => asset,
+            Ok(None) => {
+                tracing::error!("Perp {} not found for target {}", hedge.perp_coin, target.vault_name);
+                continue;
+            }
+            Err(e) => {
+                tracing::error!("Failed to fetch perp metadata for {}: {}", target.vault_name, e);
+                continue;
+            }
+        };
+
+        tracing::info!(
+            "🛡️ Hedging {} ({}) with {} perp (asset {}) | Ratio: {} | Band: {}% | Poll: {}ms",
+            target.vault_name,
+            target.base_token,
+            hedge.perp_coin,
+            asset.asset_index,
+            hedge.hedge_ratio,
+            hedge.tolerance_ratio * 100.0,
+            hedge.poll_interval_ms
+        );
+
+        let state = HedgeState {
+            target: target.clone(),
+            hedge,
+            asset,
+            started_at_ms,
+        };
+        handles.push(task::spawn(hedge_loop(state, env.clone(), config.clone(), provider.clone())));
+    }
+
+    if handles.is_empty() {
+        tracing::warn!("No targets with a [targets.hedge] section, nothing to hedge");
+        return Ok(());
+    }
+
+    for handle in handles {
+        if let Err(e) = handle.await {
+            tracing::error!("Hedge task panicked: {}", e);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hedge(hedge_ratio: f64, tolerance_ratio: f64) -> HedgeConfig {
+        HedgeConfig {
+            perp_coin: "HYPE".to_string(),
+            spot_coin: None,
+            hedge_ratio,
+            tolerance_ratio,
+            min_order_usd: 10.0,
+            max_slippage_bps: 30.0,
+            poll_interval_ms: 5000,
+        }
+    }
+
+    #[test]
+    fn test_hedge_target_size() {
+        assert_eq!(hedge_target_size(10.0, &hedge(1.0, 0.1)), -10.0);
+        assert_eq!(hedge_target_size(10.0, &hedge(0.5, 0.1)), -5.0);
+        // A short base inventory is never hedged long
+        assert_eq!(hedge_target_size(-4.0, &hedge(1.0, 0.1)), 0.0);
+    }
+
+    #[test]
+    fn test_hedge_order_size_within_band() {
+        let hedge = hedge(1.0, 0.1);
+        assert_eq!(hedge_order_size(-10.0, -10.0, &hedge), None);
+        assert_eq!(hedge_order_size(-10.0, -9.0, &hedge), None);
+        assert_eq!(hedge_order_size(-10.0, -11.0, &hedge), None);
+    }
+
+    #[test]
+    fn test_hedge_order_size_outside_band() {
+        let hedge = hedge(1.0, 0.1);
+        // Under-hedged: sell more perp
+        assert_eq!(hedge_order_size(-10.0, -8.0, &hedge), Some(-2.0));
+        // Over-hedged: buy back
+        assert_eq!(hedge_order_size(-10.0, -12.5, &hedge), Some(2.5));
+        // Exposure gone: close the whole short
+        assert_eq!(hedge_order_size(0.0, -3.0, &hedge), Some(3.0));
+    }
+}
@@ -0,0 +1,557 @@
+// Offline replay of spread history (recorded, or rebuilt from Swap logs) through the detection and sizing
+// rules of the arbitrager, with V3-math fills, gas and extra slippage
+
+use alloy::primitives::{Address, TxHash, U256};
+use serde::Serialize;
+use std::{collections::HashMap, str::FromStr};
+
+use crate::{
+    detect::{Candidates, pool_spread},
+    dex::{
+        pnl::TradePnl,
+        pool_data::{PoolInfo, v3_amount_out, v3_state_after},
+        swap::{BestOpportunity, SWAP_GAS_UNITS, SwapExecution, TradeOrder, expected_fill_price, min_amount_out},
+        swap_cross_pool::{CrossPoolOpportunity, PoolCandidate, gas_units, max_quote_in, optimal_round_trip, price_gap, sell_leg_min_out},
+    },
+    error::{Error, Result},
+    portfolio::VaultPortfolio,
+    recorder::SpreadRecord,
+    stats::{MeanReversionState, ReversionAction, ThresholdState},
+    types::{ArbTarget, BotConfig, CrossPoolConfig, CrossPoolMode},
+};
+
+// Constants
+const BASIS_POINT_DENO: f64 = 10000.0;
+
+/// Execution assumptions of the replay
+#[derive(Debug, Clone)]
+pub struct FillModel {
+    pub gas_price_gwei: f64,
+    /// HYPE price valuing gas, None = the reference price (HYPE and WHYPE targets only)
+    pub hype_price: Option<f64>,
+    /// Adverse move of every fill on top of the V3 price impact (competing flow, latency)
+    pub slippage_bps: f64,
+}
+
+/// Inventory and PnL after a replayed block
+#[derive(Debug, Clone, Serialize)]
+pub struct InventoryPoint {
+    pub block: u64,
+    pub timestamp: i64,
+    pub base: f64,
+    pub quote: f64,
+    pub reference_price: f64,
+    pub value_usd: f64,
+    /// Value change since the first block, net of gas
+    pub pnl_usd: f64,
+    pub drawdown_usd: f64,
+}
+
+/// Outcome of the replay of a target
+#[derive(Debug, Clone)]
+pub struct BacktestReport {
+    pub portfolio: VaultPortfolio,
+    pub trades: Vec<TradePnl>,
+    pub path: Vec<InventoryPoint>,
+    pub blocks: u64,
+    pub reverted: u64,
+    pub max_drawdown_usd: f64,
+}
+
+impl BacktestReport {
+    pub fn pnl_usd(&self) -> f64 {
+        self.path.last().map(|point| point.pnl_usd).unwrap_or_default()
+    }
+
+    /// Largest drop from the PnL peak, in percent of the starting value
+    pub fn max_drawdown_pct(&self) -> f64 {
+        let start = self.portfolio.starting_value_usd();
+        if start > 0.0 { self.max_drawdown_usd / start * 100.0 } else { 0.0 }
+    }
+
+    pub fn log_summary(&self) {
+        let (first, last) = (self.path.first(), self.path.last());
+        tracing::info!(
+            "🧮 Backtest {} | Blocks: {} ({} -> {}) | Trades: {} ({} reverted) | Volume: ${:.2}",
+            self.portfolio.vault_name,
+            self.blocks,
+            first.map(|p| p.block).unwrap_or_default(),
+            last.map(|p| p.block).unwrap_or_default(),
+            self.portfolio.trade_count,
+            self.reverted,
+            self.portfolio.volume_usd
+        );
+        tracing::info!(
+            "🧮   PnL: ${:.2} net of gas ({:+.3}%) | Realised: ${:.2} | Fees: ${:.2} | Gas: ${:.2} | Max drawdown: ${:.2} ({:.3}%)",
+            self.pnl_usd(),
+            if self.portfolio.starting_value_usd() > 0.0 {
+                self.pnl_usd() / self.portfolio.starting_value_usd() * 100.0
+            } else {
+                0.0
+            },
+            self.portfolio.realised_pnl_usd,
+            self.portfolio.fees_paid_usd,
+            self.portfolio.gas_paid_usd,
+            self.max_drawdown_usd,
+            self.max_drawdown_pct()
+        );
+        tracing::info!(
+            "🧮   Inventory: {:.6} -> {:.6} {} | {:.2} -> {:.2} {} | Drift: {:+.1} pp",
+            self.portfolio.starting_base,
+            self.portfolio.current_base,
+            self.portfolio.base_token,
+            self.portfolio.starting_quote,
+            self.portfolio.current_quote,
+            self.portfolio.quote_token,
+            self.portfolio.inventory_drift_pct()
+        );
+    }
+}
+
+/// Replay state of one target: simulated balances and the strategy state the live task keeps between passes
+#[derive(Debug)]
+pub struct Backtest {
+    target: ArbTarget,
+    fills: FillModel,
+    max_gas_price_gwei: f64,
+    capital_usd: f64,
+    base_ratio: f64,
+    base_token: Address,
+    quote_token: Address,
+    base_decimals: u8,
+    quote_decimals: u8,
+    base: f64,
+    quote: f64,
+    reference_price: f64,
+    /// Recorded state of each pool at the current block
+    recorded: HashMap<Address, PoolInfo>,
+    /// State after our last fill on a pool, with the recorded state it was applied to
+    filled: HashMap<Address, (PoolInfo, PoolInfo)>,
+    mean_reversion: MeanReversionState,
+    thresholds: ThresholdState,
+    portfolio: Option<VaultPortfolio>,
+    trades: Vec<TradePnl>,
+    path: Vec<InventoryPoint>,
+    blocks: u64,
+    reverted: u64,
+    peak_pnl_usd: f64,
+    max_drawdown_usd: f64,
+}
+
+impl Backtest {
+    /// Start with `capital_usd`, `base_ratio` of it in base at the first replayed reference price
+    pub fn new(target: &ArbTarget, config: &BotConfig, fills: FillModel, capital_usd: f64, base_ratio: f64) -> Result<Self> {
+        if fills.hype_price.is_none() && !matches!(target.base_token.to_uppercase().as_str(), "HYPE" | "WHYPE") {
+            return Err(Error::Config(format!("Target {} trades {}, a HYPE price is needed to value gas", target.vault_name, target.base_token)));
+        }
+        if !target.statistical_arb && target.cross_pool.is_none() {
+            return Err(Error::Config(format!(
+                "Target {} trades double leg (statistical_arb = false), which backtests do not simulate",
+                target.vault_name
+            )));
+        }
+        if !target.statistical_arb {
+            tracing::warn!("{} falls back to double leg when no cross-pool round trip clears, the fallback is not simulated", target.vault_name);
+        }
+        let mut target = target.clone();
+        if target.min_pool_tvl_usd > 0.0 {
+            // Pool reserves are not part of the history, only the in-range liquidity filter applies
+            tracing::warn!("min_pool_tvl_usd of {} is not applied in backtests", target.vault_name);
+            target.min_pool_tvl_usd = 0.0;
+        }
+        Ok(Self {
+            base_token: Address::from_str(&target.base_token_address)?,
+            quote_token: Address::from_str(&target.quote_token_address)?,
+            target,
+            fills,
+            max_gas_price_gwei: config.gas.max_gas_price_gwei,
+            capital_usd,
+            base_ratio: base_ratio.clamp(0.0, 1.0),
+            base_decimals: 0,
+            quote_decimals: 0,
+            base: 0.0,
+            quote: 0.0,
+            reference_price: 0.0,
+            recorded: HashMap::new(),
+            filled: HashMap::new(),
+            mean_reversion: MeanReversionState::default(),
+            thresholds: ThresholdState::default(),
+            portfolio: None,
+            trades: Vec::new(),
+            path: Vec::new(),
+            blocks: 0,
+            reverted: 0,
+            peak_pnl_usd: 0.0,
+            max_drawdown_usd: 0.0,
+        })
+    }
+
+    /// Replay the records of the target (sorted by block), None when no block had a reference price
+    pub fn run(mut self, records: &[SpreadRecord]) -> Result<Option<BacktestReport>> {
+        let rows: Vec<SpreadRecord> = records.iter().filter(|r| r.vault == self.target.vault_name).cloned().collect();
+        for block in rows.chunk_by(|a, b| a.block == b.block) {
+            self.step(block)?;
+        }
+        Ok(self.portfolio.map(|portfolio| BacktestReport {
+            portfolio,
+            trades: self.trades,
+            path: self.path,
+            blocks: self.blocks,
+            reverted: self.reverted,
+            max_drawdown_usd: self.max_drawdown_usd,
+        }))
+    }
+
+    /// One detection pass over the pools of a block, as `run_target` does live
+    fn step(&mut self, rows: &[SpreadRecord]) -> Result<()> {
+        let Some(first) = rows.first() else {
+            return Ok(());
+        };
+        let Some(reference_price) = first.oracle_price(&self.target.reference).filter(|price| *price > 0.0) else {
+            tracing::debug!("No {} price recorded at block {}, skipped", self.target.reference, first.block);
+            return Ok(());
+        };
+        self.reference_price = reference_price;
+        self.blocks += 1;
+        if self.portfolio.is_none() {
+            (self.base_decimals, self.quote_decimals) = if first.base_is_token0 {
+                (first.token0_decimals, first.token1_decimals)
+            } else {
+                (first.token1_decimals, first.token0_decimals)
+            };
+            self.base = self.capital_usd * self.base_ratio / reference_price;
+            self.quote = self.capital_usd * (1.0 - self.base_ratio);
+            self.portfolio = Some(VaultPortfolio::new(&self.target, self.base, self.quote, reference_price));
+        }
+
+        // Adaptive mode: the executable threshold follows realised volatility and recent fill slippage
+        let mut target = self.target.clone();
+        if let Some(at) = &self.target.adaptive_threshold {
+            self.thresholds.observe_price(first.block, reference_price, at.vol_window);
+            if let Some(adaptive) = self.thresholds.threshold(at, target.min_executable_spread_bps) {
+                target.min_executable_spread_bps = adaptive.threshold_bps;
+            }
+        }
+
+        // Inventory skew in [-1, 1] drives asymmetric thresholds in statistical-arb mode
+        let value_usd = self.base * reference_price + self.quote;
+        let inventory_skew = if target.statistical_arb && target.inventory_skew_coefficient > 0.0 && value_usd > 0.0 {
+            self.base * reference_price / value_usd * 2.0 - 1.0
+        } else {
+            0.0
+        };
+
+        let mut candidates = Candidates::default();
+        for row in rows {
+            let pool_info = self.pool_state(row.pool_info(self.base_token, self.quote_token)?);
+            let Some(spread) = pool_spread(&target, &row.dex, &row.pool, &pool_info, reference_price, inventory_skew) else {
+                continue;
+            };
+            if target.thin_pool_reason(pool_info.liquidity.to::<u128>() as f64, None).is_some() {
+                continue;
+            }
+            candidates.add(&target, &spread, &pool_info, &mut self.mean_reversion);
+        }
+
+        // Same precedence as live: mean reversion, then cross-pool, then the reference threshold
+        if let Some(mr) = &target.mean_reversion {
+            for (opportunity, pool_info, stats) in candidates.reversion {
+                let Some(stats) = stats else {
+                    continue;
+                };
+                let pool = pool_info.address;
+                let (_, ReversionAction::Trade { order, .. }) = self.mean_reversion.action(mr, pool, &stats, &opportunity, reference_price, target.min_trade_value_usd) else {
+                    continue;
+                };
+                if let Some(pnl) = self.trade(&target, &opportunity, &pool_info, order)
+                    && pnl.success
+                {
+                    self.mean_reversion.fill(pool, pnl.is_buy, pnl.base_amount);
+                }
+            }
+        } else if let Some(cross_pool) = &target.cross_pool
+            && let Some(opportunity) = self.size_cross_pool(&target, cross_pool, &candidates.cross_pool)
+        {
+            self.cross_pool(cross_pool, &opportunity);
+        } else if let Some((opportunity, pool_info)) = candidates.best
+            && target.statistical_arb
+        {
+            let order = TradeOrder {
+                is_buy: opportunity.spread_bps < 0.0,
+                amount_in: None,
+                min_amount_out: None,
+            };
+            self.trade(&target, &opportunity, &pool_info, order);
+        }
+
+        self.mark(first.block, first.timestamp);
+        Ok(())
+    }
+
+    /// State of a pool for the replay: the state after our last fill on it, until the history records a
+    /// change of the pool (a swap, or a mint or burn moving the in-range liquidity)
+    fn pool_state(&mut self, recorded: PoolInfo) -> PoolInfo {
+        let address = recorded.address;
+        let state = match self.filled.get(&address) {
+            Some((at_fill, after)) if at_fill.sqrt_price_x96 == recorded.sqrt_price_x96 && at_fill.liquidity == recorded.liquidity => after.clone(),
+            _ => {
+                self.filled.remove(&address);
+                recorded.clone()
+            }
+        };
+        self.recorded.insert(address, recorded);
+        state
+    }
+
+    fn hype_price(&self) -> f64 {
+        self.fills.hype_price.unwrap_or(self.reference_price)
+    }
+
+    fn gas_price_wei(&self) -> u128 {
+        (self.fills.gas_price_gwei * 1e9) as u128
+    }
+
+    /// Size and fill a single pool trade the way `execute_pool_trade` does
+    fn trade(&mut self, target: &ArbTarget, opportunity: &BestOpportunity, pool_info: &PoolInfo, order: TradeOrder) -> Option<TradePnl> {
+        if self.fills.gas_price_gwei > self.max_gas_price_gwei {
+            return None;
+        }
+        let (balance, decimals_in, decimals_out) = if order.is_buy {
+            (self.quote, self.quote_decimals, self.base_decimals)
+        } else {
+            (self.base, self.base_decimals, self.quote_decimals)
+        };
+        let amount_in_raw = order.amount_in_raw((balance.max(0.0) * 10f64.powi(decimals_in as i32)) as u128, decimals_in, target.max_inventory_ratio);
+        let amount_in = amount_in_raw as f64 / 10f64.powi(decimals_in as i32);
+        let trade_value_usd = if order.is_buy { amount_in } else { amount_in * self.reference_price };
+        if trade_value_usd < target.min_trade_value_usd {
+            return None;
+        }
+        let amount_out_min = min_amount_out(order.is_buy, amount_in, opportunity.pool_price, decimals_out);
+        self.fill(opportunity, pool_info, order.is_buy, amount_in_raw, amount_out_min, SWAP_GAS_UNITS)
+    }
+
+    /// Cross-pool round trip sized the way `find_cross_pool_opportunity` does, when it clears the minimums
+    fn size_cross_pool(&self, target: &ArbTarget, cross_pool: &CrossPoolConfig, candidates: &[PoolCandidate]) -> Option<CrossPoolOpportunity> {
+        let ((buy, buy_pool), (sell, sell_pool), _) = price_gap(candidates)?;
+        let quote_scale = 10f64.powi(self.quote_decimals as i32);
+        // Quote valued at one dollar, as everywhere in the replay (records carry no quote price)
+        let quote_price = 1.0;
+        let max_quote_in = max_quote_in(cross_pool, target, self.quote.max(0.0) * quote_scale, self.quote_decimals, quote_price);
+        let sized = optimal_round_trip(buy_pool, sell_pool, self.base_token, self.quote_token, max_quote_in);
+        let profit_usd = sized.profit() / quote_scale * quote_price;
+        if profit_usd <= 0.0 || sized.quote_in / quote_scale * quote_price < target.min_trade_value_usd || self.fills.gas_price_gwei > self.max_gas_price_gwei {
+            return None;
+        }
+        let opportunity = CrossPoolOpportunity {
+            buy: buy.clone(),
+            sell: sell.clone(),
+            buy_pool: buy_pool.clone(),
+            sell_pool: sell_pool.clone(),
+            quote_in: U256::from(sized.quote_in as u128),
+            expected_base_out: U256::from(sized.base_out as u128),
+            expected_quote_out: U256::from(sized.quote_out as u128),
+            base_decimals: self.base_decimals,
+            quote_decimals: self.quote_decimals,
+            profit_usd,
+            quote_price,
+            gas_cost_usd: (gas_units(&cross_pool.mode) * self.gas_price_wei()) as f64 / 1e18 * self.hype_price(),
+            hype_price: self.hype_price(),
+        };
+        opportunity.is_executable(cross_pool, target).then_some(opportunity)
+    }
+
+    /// Fill both legs: two transactions (the sell leg follows a filled buy leg), or one contract call whose
+    /// profit check reverts both legs when slippage eats the minimum profit
+    fn cross_pool(&mut self, cross_pool: &CrossPoolConfig, opportunity: &CrossPoolOpportunity) {
+        let quote_in = opportunity.quote_in.to::<u128>();
+        match cross_pool.mode {
+            CrossPoolMode::Sequential => {
+                let min_base = min_amount_out(true, opportunity.quote_in_normalized(), opportunity.buy.pool_price, self.base_decimals);
+                let Some(bought) = self.fill(&opportunity.buy, &opportunity.buy_pool, true, quote_in, min_base, SWAP_GAS_UNITS) else {
+                    return;
+                };
+                if !bought.success {
+                    return;
+                }
+                let base_in = (bought.base_amount * 10f64.powi(self.base_decimals as i32)) as u128;
+                let Some(floor) = sell_leg_min_out(
+                    opportunity,
+                    self.base_token,
+                    base_in as f64,
+                    bought.quote_amount * 10f64.powi(self.quote_decimals as i32),
+                    cross_pool.min_profit_usd,
+                ) else {
+                    return;
+                };
+                let min_quote = min_amount_out(false, bought.base_amount, opportunity.sell.pool_price, self.quote_decimals).max(floor);
+                self.fill(&opportunity.sell, &opportunity.sell_pool, false, base_in, min_quote, SWAP_GAS_UNITS);
+            }
+            CrossPoolMode::Atomic | CrossPoolMode::Flash => {
+                let slippage = 1.0 - self.fills.slippage_bps / BASIS_POINT_DENO;
+                let base_out = v3_amount_out(&opportunity.buy_pool, self.quote_token, quote_in as f64) * slippage;
+                let quote_out = v3_amount_out(&opportunity.sell_pool, self.base_token, base_out) * slippage;
+                let min_profit = opportunity.min_profit_raw(cross_pool.min_profit_usd);
+                let amount_out_min = if quote_out - quote_in as f64 >= min_profit { U256::ZERO } else { U256::MAX };
+                let leg_gas = gas_units(&cross_pool.mode) / 2;
+                self.fill(&opportunity.buy, &opportunity.buy_pool, true, quote_in, amount_out_min, leg_gas);
+                self.fill(&opportunity.sell, &opportunity.sell_pool, false, base_out as u128, amount_out_min, leg_gas);
+            }
+        }
+    }
+
+    /// Swap on the pool state with V3 maths and the extra slippage, reverting below `amount_out_min`.
+    /// PnL is computed and accounted as for a live receipt, and a filled swap moves the pool price
+    fn fill(&mut self, opportunity: &BestOpportunity, pool_info: &PoolInfo, is_buy: bool, amount_in_raw: u128, amount_out_min: U256, gas_units: u128) -> Option<TradePnl> {
+        let (token_in, token_out, decimals_in, decimals_out) = if is_buy {
+            (self.quote_token, self.base_token, self.quote_decimals, self.base_decimals)
+        } else {
+            (self.base_token, self.quote_token, self.base_decimals, self.quote_decimals)
+        };
+        let amount_out = v3_amount_out(pool_info, token_in, amount_in_raw as f64) * (1.0 - self.fills.slippage_bps / BASIS_POINT_DENO);
+        let amount_out = U256::from(amount_out.max(0.0) as u128);
+        let execution = SwapExecution {
+            dex: opportunity.dex.clone(),
+            pool_address: opportunity.pool_address.clone(),
+            is_buy,
+            token_in,
+            token_out,
+            amount_in: U256::from(amount_in_raw),
+            amount_out_min,
+            amount_out: Some(amount_out),
+            tx_hash: TxHash::ZERO,
+            gas_used: gas_units as u64,
+            gas_paid_wei: gas_units * self.gas_price_wei(),
+            success: amount_out >= amount_out_min,
+            pnl: None,
+        };
+        let fee_ratio = opportunity.pool_fee_tier as f64 / 1_000_000.0;
+        let expected_price = expected_fill_price(is_buy, opportunity.pool_price, opportunity.pool_fee_tier);
+        let pnl = TradePnl::compute(&execution, decimals_in, decimals_out, fee_ratio, expected_price, self.reference_price, self.hype_price())?;
+
+        if pnl.success {
+            if let Some(recorded) = self.recorded.get(&pool_info.address) {
+                self.filled.insert(pool_info.address, (recorded.clone(), v3_state_after(pool_info, token_in, amount_in_raw as f64)));
+            }
+            if is_buy {
+                self.base += pnl.base_amount;
+                self.quote -= pnl.quote_amount;
+            } else {
+                self.base -= pnl.base_amount;
+                self.quote += pnl.quote_amount;
+            }
+            if let Some(at) = &self.target.adaptive_threshold {
+                self.thresholds.observe_slippage(pnl.slippage_bps, at.slippage_window);
+            }
+        } else {
+            self.reverted += 1;
+        }
+        if let Some(portfolio) = &mut self.portfolio {
+            portfolio.apply_trade(&pnl);
+        }
+        self.trades.push(pnl.clone());
+        Some(pnl)
+    }
+
+    /// Mark the balances at the block reference price and extend the inventory path
+    fn mark(&mut self, block: u64, timestamp: i64) {
+        let Some(portfolio) = &mut self.portfolio else {
+            return;
+        };
+        portfolio.mark(self.base, self.quote, self.reference_price);
+        let pnl_usd = portfolio.net_pnl_usd();
+        self.peak_pnl_usd = self.peak_pnl_usd.max(pnl_usd);
+        let drawdown_usd = self.peak_pnl_usd - pnl_usd;
+        self.max_drawdown_usd = self.max_drawdown_usd.max(drawdown_usd);
+        self.path.push(InventoryPoint {
+            block,
+            timestamp,
+            base: self.base,
+            quote: self.quote,
+            reference_price: self.reference_price,
+            value_usd: portfolio.current_value_usd(),
+            pnl_usd,
+            drawdown_usd,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::tests::config;
+
+    const WHYPE: &str = "0x5555555555555555555555555555555555555555";
+    const USDT0: &str = "0xB8CE59FC3717ada4C02eaDF9682A9e934F625ebb";
+    const POOL: &str = "0x337b56d87A6185cD46AF3Ac2cDF03CBC37070C30";
+
+    fn target(statistical_arb: bool) -> ArbTarget {
+        crate::types::tests::target(&format!(
+            r#"
+            base_token = "WHYPE"
+            base_token_address = "{WHYPE}"
+            quote_token_address = "{USDT0}"
+            hyperswap_pools = ["{POOL}"]
+            min_executable_spread_bps = 10.0
+            statistical_arb = {statistical_arb}
+            "#
+        ))
+    }
+
+    fn fills() -> FillModel {
+        FillModel {
+            gas_price_gwei: 0.1,
+            hype_price: None,
+            slippage_bps: 0.0,
+        }
+    }
+
+    /// WHYPE/USDT0 pool (WHYPE token0) at `pool_price` against a HyperCore price of 40
+    fn record(block: u64, pool_price: f64) -> SpreadRecord {
+        let sqrt_price_x96 = U256::try_from((pool_price * 1e-12).sqrt() * 2_f64.powi(96)).unwrap();
+        let mut record = SpreadRecord {
+            block,
+            timestamp: 1_700_000_000 + block as i64,
+            vault: "test".to_string(),
+            dex: "Hyperswap".to_string(),
+            pool: POOL.to_string(),
+            fee_bps: 5.0,
+            pool_price,
+            liquidity: "83000000000000000".to_string(),
+            sqrt_price_x96: sqrt_price_x96.to_string(),
+            base_is_token0: true,
+            token0_decimals: 18,
+            token1_decimals: 6,
+            reference: "hypercore".to_string(),
+            reference_price: 40.0,
+            spread_bps: (pool_price - 40.0) / 40.0 * BASIS_POINT_DENO,
+            ..Default::default()
+        };
+        record.set_oracle_price(&crate::types::PriceReference::Hypercore, 40.0);
+        record
+    }
+
+    #[test]
+    fn persistent_gap_is_captured_once() {
+        // The pool stays 100 bps below the reference with no other swap: the first buy closes the gap
+        let records: Vec<SpreadRecord> = (1..=5).map(|block| record(block, 39.6)).collect();
+        let report = Backtest::new(&target(true), &config(), fills(), 10_000.0, 0.5).unwrap().run(&records).unwrap().unwrap();
+        assert_eq!(report.blocks, 5);
+        assert_eq!(report.trades.len(), 1);
+        assert!(report.trades[0].is_buy && report.trades[0].success);
+    }
+
+    #[test]
+    fn recorded_swap_resets_the_filled_state() {
+        // A later swap on the pool reopens the gap, which is traded again
+        let mut records: Vec<SpreadRecord> = (1..=3).map(|block| record(block, 39.6)).collect();
+        records.push(record(4, 39.59));
+        let report = Backtest::new(&target(true), &config(), fills(), 10_000.0, 0.5).unwrap().run(&records).unwrap().unwrap();
+        assert_eq!(report.trades.len(), 2);
+    }
+
+    #[test]
+    fn double_leg_targets_are_rejected() {
+        assert!(matches!(Backtest::new(&target(false), &config(), fills(), 10_000.0, 0.5), Err(Error::Config(_))));
+    }
+}
@@ -0,0 +1,146 @@
+// Read caches shared by the concurrent target tasks
+
+use alloy::primitives::Address;
+use std::{
+    collections::HashMap,
+    hash::Hash,
+    sync::{Arc, RwLock},
+    time::{Duration, Instant},
+};
+use tokio::sync::OnceCell;
+
+use crate::dex::pool_data::PoolInfo;
+
+/// Reference prices are reused by targets polling within this window
+pub const PRICE_CACHE_TTL: Duration = Duration::from_millis(500);
+
+/// Pool states are keyed by block, entries are only kept to be evicted
+const POOL_CACHE_TTL: Duration = Duration::from_secs(10);
+
+/// Pool TVLs only feed the depth filter, a minute-old value is close enough
+const TVL_CACHE_TTL: Duration = Duration::from_secs(60);
+
+/// Value stamped with its fetch time, set once by the first caller of the key
+type Slot<V> = Arc<OnceCell<(Instant, V)>>;
+
+/// Values kept for a fixed time (cheap to clone), concurrent fetches of a key share one request
+#[derive(Debug, Clone)]
+pub struct TtlCache<K, V> {
+    ttl: Duration,
+    inner: Arc<RwLock<HashMap<K, Slot<V>>>>,
+}
+
+impl<K: Eq + Hash + Clone, V: Clone> TtlCache<K, V> {
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            inner: Arc::new(RwLock::new(HashMap::new())),
+        }
+    }
+
+    /// Value inserted less than the TTL ago
+    pub fn get(&self, key: &K) -> Option<V> {
+        let guard = self.inner.read().unwrap_or_else(|e| e.into_inner());
+        guard.get(key).and_then(|slot| slot.get()).filter(|(at, _)| at.elapsed() < self.ttl).map(|(_, value)| value.clone())
+    }
+
+    /// Insert a value, evicting expired entries
+    pub fn insert(&self, key: K, value: V) {
+        let mut guard = self.inner.write().unwrap_or_else(|e| e.into_inner());
+        self.evict(&mut guard);
+        guard.insert(key, Arc::new(OnceCell::new_with(Some((Instant::now(), value)))));
+    }
+
+    /// Cached value, or the result of `fetch` (cached only on success). Callers arriving while
+    /// a fetch of the same key is in flight wait for it instead of sending their own request.
+    pub async fn get_or_fetch<E>(&self, key: K, fetch: impl Future<Output = Result<V, E>>) -> Result<V, E> {
+        let slot = {
+            let mut guard = self.inner.write().unwrap_or_else(|e| e.into_inner());
+            match guard.get(&key) {
+                Some(slot) if slot.get().is_none_or(|(at, _)| at.elapsed() < self.ttl) => slot.clone(),
+                _ => {
+                    self.evict(&mut guard);
+                    let slot: Slot<V> = Arc::new(OnceCell::new());
+                    guard.insert(key.clone(), slot.clone());
+                    slot
+                }
+            }
+        };
+        match slot.get_or_try_init(|| async { fetch.await.map(|value| (Instant::now(), value)) }).await {
+            Ok((_, value)) => Ok(value.clone()),
+            Err(e) => {
+                // Drop the empty slot so a key that is never asked again does not linger
+                let mut guard = self.inner.write().unwrap_or_else(|e| e.into_inner());
+                if guard.get(&key).is_some_and(|current| Arc::ptr_eq(current, &slot) && current.get().is_none()) {
+                    guard.remove(&key);
+                }
+                Err(e)
+            }
+        }
+    }
+
+    // Expired values go, in-flight fetches stay
+    fn evict(&self, entries: &mut HashMap<K, Slot<V>>) {
+        entries.retain(|_, slot| slot.get().is_none_or(|(at, _)| at.elapsed() < self.ttl));
+    }
+}
+
+/// Reference prices and pool states, shared by targets reading the same pair or pool
+#[derive(Debug, Clone)]
+pub struct ReadCache {
+    /// USD price by (reference, symbol)
+    pub prices: TtlCache<(String, String), f64>,
+    /// Pool state by (pool, block)
+    pub pools: TtlCache<(Address, u64), PoolInfo>,
+    /// Pool TVL in USD by pool
+    pub tvl: TtlCache<Address, f64>,
+}
+
+impl Default for ReadCache {
+    fn default() -> Self {
+        Self {
+            prices: TtlCache::new(PRICE_CACHE_TTL),
+            pools: TtlCache::new(POOL_CACHE_TTL),
+            tvl: TtlCache::new(TVL_CACHE_TTL),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[tokio::test]
+    async fn concurrent_fetches_of_a_key_share_one_request() {
+        let cache: TtlCache<u64, u64> = TtlCache::new(Duration::from_secs(10));
+        let calls = AtomicUsize::new(0);
+        let fetch = || async {
+            calls.fetch_add(1, Ordering::SeqCst);
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            Ok::<_, ()>(7)
+        };
+
+        let (a, b, c) = tokio::join!(cache.get_or_fetch(1, fetch()), cache.get_or_fetch(1, fetch()), cache.get_or_fetch(1, fetch()));
+        assert_eq!((a, b, c), (Ok(7), Ok(7), Ok(7)));
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        assert_eq!(cache.get(&1), Some(7));
+    }
+
+    #[tokio::test]
+    async fn failed_fetches_are_not_cached() {
+        let cache: TtlCache<u64, u64> = TtlCache::new(Duration::from_secs(10));
+        assert_eq!(cache.get_or_fetch(1, async { Err::<u64, _>("rpc down") }).await, Err("rpc down"));
+        assert!(cache.inner.read().unwrap().is_empty());
+        assert_eq!(cache.get_or_fetch(1, async { Ok::<_, &str>(3) }).await, Ok(3));
+    }
+
+    #[tokio::test]
+    async fn expired_values_are_fetched_again() {
+        let cache: TtlCache<u64, u64> = TtlCache::new(Duration::from_millis(10));
+        cache.insert(1, 1);
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert_eq!(cache.get(&1), None);
+        assert_eq!(cache.get_or_fetch(1, async { Ok::<_, ()>(2) }).await, Ok(2));
+    }
+}
@@ -1,7 +1,8 @@
-use eyre::Result;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 
+use crate::error::{Error, Result};
+
 // ===== UTILITY FUNCTIONS =====
 
 /// Format asset index as 32-byte padded hex string for HyperCore smart contract calls
@@ -36,6 +37,23 @@ pub struct HyperToken {
     pub hypercore_address: String, // HyperCore address for smart contract calls (32-byte padded hex)
 }
 
+/// OHLC candle from the candleSnapshot API (prices are decimal strings)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Candle {
+    #[serde(rename = "t")]
+    pub open_time_ms: i64,
+    #[serde(rename = "T")]
+    pub close_time_ms: i64,
+    #[serde(rename = "o")]
+    pub open: String,
+    #[serde(rename = "h")]
+    pub high: String,
+    #[serde(rename = "l")]
+    pub low: String,
+    #[serde(rename = "c")]
+    pub close: String,
+}
+
 /// Meta API response structure
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MetaResponse {
@@ -78,7 +96,7 @@ impl HyperLiquidAPI {
             .await?;
 
         if !response.status().is_success() {
-            return Err(eyre::eyre!("API request failed with status: {}", response.status()));
+            return Err(Error::Rpc(format!("API request failed with status: {}", response.status())));
         }
 
         let meta_response: MetaResponse = response.json().await?;
@@ -193,7 +211,7 @@ impl HyperLiquidAPI {
             .await?;
 
         if !resp.status().is_success() {
-            return Err(eyre::eyre!("API request failed with status: {}", resp.status()));
+            return Err(Error::Rpc(format!("API request failed with status: {}", resp.status())));
         }
 
         // Parse directly as HashMap since the response is a flat object with symbol keys
@@ -201,16 +219,40 @@ impl HyperLiquidAPI {
         Ok(data)
     }
 
+    /// Get the candles of an asset between two times (ms), paging through the 5000 candles returned per request
+    /// Equivalent to: curl -X POST https://api.hyperliquid.xyz/info -d '{"type": "candleSnapshot", "req": {"coin": "HYPE", "interval": "1m", "startTime": ..., "endTime": ...}}'
+    pub async fn get_candles(&self, symbol: &str, interval: &str, start_ms: i64, end_ms: i64) -> Result<Vec<Candle>> {
+        let mut candles: Vec<Candle> = Vec::new();
+        let mut start = start_ms;
+        while start < end_ms {
+            let payload = json!({
+                "type": "candleSnapshot",
+                "req": { "coin": symbol, "interval": interval, "startTime": start, "endTime": end_ms }
+            });
+            let resp = self.client.post(&self.api_url).json(&payload).send().await?;
+            if !resp.status().is_success() {
+                return Err(Error::Rpc(format!("API request failed with status: {}", resp.status())));
+            }
+            let page: Vec<Candle> = resp.json().await?;
+            let Some(last) = page.last() else {
+                break;
+            };
+            start = last.close_time_ms + 1;
+            candles.extend(page);
+        }
+        Ok(candles)
+    }
+
     /// Get price for specific asset
     pub async fn get_price(&self, symbol: &str) -> Result<f64> {
         // Get all mid prices
         let mids = self.get_all_mids().await?;
         
         let price_str = mids.get(symbol)
-            .ok_or_else(|| eyre::eyre!("Price not found for symbol: {}", symbol))?;
+            .ok_or_else(|| Error::Rpc(format!("Price not found for symbol: {}", symbol)))?;
         
         let price = price_str.parse::<f64>()
-            .map_err(|e| eyre::eyre!("Failed to parse price: {}", e))?;
+            .map_err(|e| Error::Rpc(format!("Failed to parse price: {}", e)))?;
         
         Ok(price)
     }
@@ -0,0 +1,195 @@
+use alloy::{
+    network::EthereumWallet,
+    primitives::{Address, Bytes, TxHash},
+    providers::ProviderBuilder,
+    signers::local::PrivateKeySigner,
+    sol_types::SolValue,
+};
+use std::str::FromStr;
+
+use crate::{
+    error::{Error, Result},
+    rpc,
+    sol::ICoreWriter,
+    tx::{TX_TRACKER, TxPolicy},
+    types::BotConfig,
+};
+
+// ===== ACTION CONSTANTS =====
+// Based on HLConstants.sol (hyper-evm-lib)
+
+/// Raw action encoding version
+pub const ACTION_VERSION: u8 = 1;
+/// Limit order action id
+pub const LIMIT_ORDER_ACTION: u32 = 1;
+/// Spot send action id
+pub const SPOT_SEND_ACTION: u32 = 6;
+
+/// Limit order time-in-force: add liquidity only
+pub const TIF_ALO: u8 = 1;
+/// Limit order time-in-force: good till cancel
+pub const TIF_GTC: u8 = 2;
+/// Limit order time-in-force: immediate or cancel
+pub const TIF_IOC: u8 = 3;
+
+/// CoreWriter prices and sizes are sent as 10^8 * human readable value
+pub const CORE_PX_SZ_SCALE: f64 = 1e8;
+
+// ===== DATA STRUCTURES =====
+
+alloy::sol! {
+    /// Limit order action payload (abi encoded after the action header)
+    struct LimitOrderAction {
+        uint32 asset;
+        bool isBuy;
+        uint64 limitPx;
+        uint64 sz;
+        bool reduceOnly;
+        uint8 encodedTif;
+        uint128 cloid;
+    }
+}
+
+/// Limit order for the CoreWriter (human readable price and size)
+#[derive(Debug, Clone)]
+pub struct LimitOrder {
+    pub asset: u32,
+    pub is_buy: bool,
+    pub limit_px: f64,
+    pub sz: f64,
+    pub reduce_only: bool,
+    pub tif: u8,
+    pub cloid: u128,
+}
+
+// ===== ENCODING =====
+
+/// Encode a raw action: version (uint8) + action id (uint24) + abi encoded payload
+pub fn encode_action(action_id: u32, payload: Vec<u8>) -> Bytes {
+    let mut raw = Vec::with_capacity(4 + payload.len());
+    raw.push(ACTION_VERSION);
+    raw.extend_from_slice(&action_id.to_be_bytes()[1..]);
+    raw.extend_from_slice(&payload);
+    raw.into()
+}
+
+/// Encode a limit order action: (uint32 asset, bool isBuy, uint64 limitPx, uint64 sz, bool reduceOnly, uint8 tif, uint128 cloid)
+pub fn encode_limit_order(order: &LimitOrder) -> Bytes {
+    let payload = LimitOrderAction {
+        asset: order.asset,
+        isBuy: order.is_buy,
+        limitPx: (order.limit_px * CORE_PX_SZ_SCALE).round() as u64,
+        sz: (order.sz * CORE_PX_SZ_SCALE).round() as u64,
+        reduceOnly: order.reduce_only,
+        encodedTif: order.tif,
+        cloid: order.cloid,
+    };
+    encode_action(LIMIT_ORDER_ACTION, payload.abi_encode())
+}
+
+/// Round a perp price to HyperCore tick rules: 5 significant figures, at most (6 - szDecimals) decimals
+pub fn round_perp_price(px: f64, sz_decimals: u8) -> f64 {
+    if px <= 0.0 || !px.is_finite() {
+        return 0.0;
+    }
+    let integer_digits = px.log10().floor() as i32 + 1;
+    let decimals = (5 - integer_digits).min(6 - sz_decimals as i32).max(0);
+    let factor = 10f64.powi(decimals);
+    (px * factor).round() / factor
+}
+
+/// Round a size down to the asset's szDecimals
+pub fn round_size_down(sz: f64, sz_decimals: u8) -> f64 {
+    let factor = 10f64.powi(sz_decimals as i32);
+    (sz * factor).floor() / factor
+}
+
+// ===== EXECUTION =====
+
+/// Send a raw action through the CoreWriter contract and wait for inclusion
+pub async fn send_raw_action(config: &BotConfig, wallet: PrivateKeySigner, raw_action: Bytes) -> Result<TxHash> {
+    // Build wallet provider with signer (reads fail over, signed transactions go to every broadcast endpoint)
+    let wallet_address = wallet.address();
+    let provider = ProviderBuilder::new().wallet(EthereumWallet::from(wallet)).connect_client(rpc::client(&config.global)?);
+
+    let core_writer = ICoreWriter::new(Address::from_str(&config.hyperevm.core_bridge_contract)?, provider.clone());
+    let request = core_writer.sendRawAction(raw_action).into_transaction_request();
+    // Same wallets as the swaps: nonces, deadline and gas bumps handled by the tracker
+    let receipt = TX_TRACKER.send(&provider, wallet_address, request, TxPolicy::from(&config.gas)).await?.into_receipt()?;
+    let tx_hash = receipt.transaction_hash;
+
+    if !receipt.status() {
+        return Err(Error::Execution(format!("CoreWriter action reverted: 0x{:x}", tx_hash)));
+    }
+    Ok(tx_hash)
+}
+
+/// Place a limit order on HyperCore through the CoreWriter
+pub async fn place_limit_order(config: &BotConfig, wallet: PrivateKeySigner, order: &LimitOrder) -> Result<TxHash> {
+    tracing::info!(
+        "📝 CoreWriter order: {} {} @ {} (asset {}, tif {}, reduce_only {})",
+        if order.is_buy { "BUY" } else { "SELL" },
+        order.sz,
+        order.limit_px,
+        order.asset,
+        order.tif,
+        order.reduce_only
+    );
+    send_raw_action(config, wallet, encode_limit_order(order)).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_action_header() {
+        let raw = encode_action(SPOT_SEND_ACTION, vec![0xaa, 0xbb]);
+        assert_eq!(raw.as_ref(), &[ACTION_VERSION, 0x00, 0x00, 0x06, 0xaa, 0xbb]);
+    }
+
+    #[test]
+    fn test_encode_limit_order() {
+        let order = LimitOrder {
+            asset: 159,
+            is_buy: true,
+            limit_px: 42.123,
+            sz: 1.5,
+            reduce_only: true,
+            tif: TIF_IOC,
+            cloid: 7,
+        };
+        let raw = encode_limit_order(&order);
+        // Version 1, action id 1 as uint24, then 7 abi words
+        assert_eq!(&raw[..4], &[1, 0, 0, 1]);
+        assert_eq!(raw.len(), 4 + 7 * 32);
+
+        let decoded = LimitOrderAction::abi_decode(&raw[4..]).unwrap();
+        assert_eq!(decoded.asset, 159);
+        assert!(decoded.isBuy);
+        assert_eq!(decoded.limitPx, 4_212_300_000);
+        assert_eq!(decoded.sz, 150_000_000);
+        assert!(decoded.reduceOnly);
+        assert_eq!(decoded.encodedTif, TIF_IOC);
+        assert_eq!(decoded.cloid, 7);
+    }
+
+    #[test]
+    fn test_round_perp_price() {
+        // 5 significant figures
+        assert_eq!(round_perp_price(42.123456, 2), 42.123);
+        assert_eq!(round_perp_price(112_345.67, 5), 112_346.0);
+        // At most 6 - szDecimals decimals
+        assert_eq!(round_perp_price(0.0123456, 2), 0.0123);
+        assert_eq!(round_perp_price(0.0123456, 0), 0.012346);
+        // Invalid prices
+        assert_eq!(round_perp_price(0.0, 2), 0.0);
+        assert_eq!(round_perp_price(f64::NAN, 2), 0.0);
+    }
+
+    #[test]
+    fn test_round_size_down() {
+        assert_eq!(round_size_down(1.23456, 2), 1.23);
+        assert_eq!(round_size_down(1.999, 0), 1.0);
+    }
+}
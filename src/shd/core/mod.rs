@@ -1,3 +1,5 @@
 pub mod api;
+pub mod corewriter;
+pub mod perps;
 pub mod precompiles;
 pub mod spot;
@@ -0,0 +1,187 @@
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::error::{Error, Result};
+
+// ===== PERP STATE STRUCTURES =====
+
+/// Cumulative funding for an open position (positive = paid)
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CumFunding {
+    pub all_time: String,
+    pub since_open: String,
+    pub since_change: String,
+}
+
+/// Perp position as returned by clearinghouseState
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PerpPosition {
+    pub coin: String,
+    /// Signed size (negative = short)
+    pub szi: String,
+    #[serde(default)]
+    pub entry_px: Option<String>,
+    pub position_value: String,
+    pub unrealized_pnl: String,
+    #[serde(default)]
+    pub liquidation_px: Option<String>,
+    pub margin_used: String,
+    #[serde(default)]
+    pub cum_funding: CumFunding,
+}
+
+impl PerpPosition {
+    /// Signed size as f64 (negative = short)
+    pub fn size(&self) -> f64 {
+        self.szi.parse().unwrap_or(0.0)
+    }
+
+    /// Unrealized PnL in USD
+    pub fn unrealized_pnl_usd(&self) -> f64 {
+        self.unrealized_pnl.parse().unwrap_or(0.0)
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct AssetPosition {
+    pub position: PerpPosition,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MarginSummary {
+    pub account_value: String,
+    pub total_ntl_pos: String,
+    pub total_margin_used: String,
+}
+
+/// Response from clearinghouseState API
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PerpClearinghouseState {
+    pub asset_positions: Vec<AssetPosition>,
+    pub margin_summary: MarginSummary,
+    #[serde(default)]
+    pub withdrawable: Option<String>,
+}
+
+impl PerpClearinghouseState {
+    /// Find the open position for a coin, if any
+    pub fn position(&self, coin: &str) -> Option<&PerpPosition> {
+        self.asset_positions.iter().map(|p| &p.position).find(|p| p.coin.eq_ignore_ascii_case(coin))
+    }
+}
+
+/// Funding delta from userFunding API
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FundingDelta {
+    pub coin: String,
+    /// USDC received (negative = paid)
+    pub usdc: String,
+    pub szi: String,
+    pub funding_rate: String,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct FundingUpdate {
+    pub time: u64,
+    pub delta: FundingDelta,
+}
+
+/// Fill from userFillsByTime API
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UserFill {
+    pub coin: String,
+    pub px: String,
+    pub sz: String,
+    pub side: String,
+    pub time: u64,
+    pub closed_pnl: String,
+    pub fee: String,
+}
+
+// ===== API CLIENT =====
+
+/// HyperCore perp account reader (positions, funding, fills)
+pub struct HyperliquidPerps {
+    api_url: String,
+    client: reqwest::Client,
+}
+
+impl HyperliquidPerps {
+    pub fn new(api_endpoint: &str) -> Self {
+        Self {
+            api_url: format!("{}/info", api_endpoint),
+            client: reqwest::Client::new(),
+        }
+    }
+
+    async fn request(&self, payload: serde_json::Value) -> Result<serde_json::Value> {
+        let response = self.client.post(&self.api_url).json(&payload).send().await?;
+
+        if !response.status().is_success() {
+            return Err(Error::Rpc(format!("API request failed with status: {}", response.status())));
+        }
+
+        Ok(response.json().await?)
+    }
+
+    /// Get perp positions and margin summary for a user
+    pub async fn get_clearinghouse_state(&self, user_address: &str) -> Result<PerpClearinghouseState> {
+        let response = self
+            .request(json!({
+                "type": "clearinghouseState",
+                "user": user_address
+            }))
+            .await?;
+        serde_json::from_value(response).map_err(|e| Error::Rpc(format!("Unexpected API response: {}", e)))
+    }
+
+    /// Get funding payments for a user since `start_time_ms`
+    pub async fn get_user_funding(&self, user_address: &str, start_time_ms: u64) -> Result<Vec<FundingUpdate>> {
+        let response = self
+            .request(json!({
+                "type": "userFunding",
+                "user": user_address,
+                "startTime": start_time_ms
+            }))
+            .await?;
+        serde_json::from_value(response).map_err(|e| Error::Rpc(format!("Unexpected API response: {}", e)))
+    }
+
+    /// Get fills for a user since `start_time_ms`
+    pub async fn get_user_fills(&self, user_address: &str, start_time_ms: u64) -> Result<Vec<UserFill>> {
+        let response = self
+            .request(json!({
+                "type": "userFillsByTime",
+                "user": user_address,
+                "startTime": start_time_ms
+            }))
+            .await?;
+        serde_json::from_value(response).map_err(|e| Error::Rpc(format!("Unexpected API response: {}", e)))
+    }
+
+    /// Net funding received for a coin since `start_time_ms` (negative = paid)
+    pub async fn get_net_funding(&self, user_address: &str, coin: &str, start_time_ms: u64) -> Result<f64> {
+        let updates = self.get_user_funding(user_address, start_time_ms).await?;
+        Ok(updates
+            .iter()
+            .filter(|u| u.delta.coin.eq_ignore_ascii_case(coin))
+            .map(|u| u.delta.usdc.parse::<f64>().unwrap_or(0.0))
+            .sum())
+    }
+
+    /// Realized PnL net of trading fees for a coin since `start_time_ms`
+    pub async fn get_realized_pnl(&self, user_address: &str, coin: &str, start_time_ms: u64) -> Result<f64> {
+        let fills = self.get_user_fills(user_address, start_time_ms).await?;
+        Ok(fills
+            .iter()
+            .filter(|f| f.coin.eq_ignore_ascii_case(coin))
+            .map(|f| f.closed_pnl.parse::<f64>().unwrap_or(0.0) - f.fee.parse::<f64>().unwrap_or(0.0))
+            .sum())
+    }
+}
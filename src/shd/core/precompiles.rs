@@ -1,13 +1,17 @@
 use alloy::{
+    network::Ethereum,
     primitives::{Address, Bytes, U256},
-    providers::{Provider, ProviderBuilder},
+    providers::{Provider, RootProvider},
     rpc::types::TransactionRequest,
 };
-use eyre::Result;
 use serde::{Deserialize, Serialize};
 use std::str::FromStr;
 
-use super::super::types::BotConfig;
+use crate::{
+    error::{Error, Result},
+    rpc,
+    types::BotConfig,
+};
 
 // ===== PRECOMPILE ADDRESSES =====
 // Based on HyperLiquid documentation and articles
@@ -48,15 +52,15 @@ pub struct L1BlockInfo {
 // ===== PRECOMPILE PRICE READER =====
 
 pub struct PrecompileReader {
-    rpc_url: String,
+    provider: RootProvider<Ethereum>,
 }
 
 impl PrecompileReader {
-    pub fn new(config: &BotConfig) -> Self {
+    pub fn new(config: &BotConfig) -> Result<Self> {
         tracing::info!("🔗 Initializing HyperLiquid precompile reader");
-        Self {
-            rpc_url: config.global.rpc_endpoint.clone(),
-        }
+        Ok(Self {
+            provider: rpc::provider(&config.global)?,
+        })
     }
 
     /// Get decimal places for a specific asset index
@@ -79,7 +83,7 @@ impl PrecompileReader {
     pub async fn get_l1_block_number(&self) -> Result<u64> {
         tracing::info!("📊 Fetching L1 block number from precompile...");
 
-        let provider = ProviderBuilder::new().connect_http(self.rpc_url.parse()?);
+        let provider = &self.provider;
 
         let precompile_addr = Address::from_str(addresses::L1_BLOCK_NUMBER)?;
 
@@ -97,7 +101,7 @@ impl PrecompileReader {
             bytes.copy_from_slice(&call_result[0..8]);
             u64::from_be_bytes(bytes)
         } else {
-            return Err(eyre::eyre!("Invalid L1 block number response length: {}", call_result.len()));
+            return Err(Error::Rpc(format!("Invalid L1 block number response length: {}", call_result.len())));
         };
 
         tracing::info!("✅ L1 Block Number: {}", block_number);
@@ -108,7 +112,7 @@ impl PrecompileReader {
     pub async fn get_mark_price_by_index(&self, token_index: u32) -> Result<f64> {
         tracing::info!("📊 Fetching mark price for token index {}...", token_index);
 
-        let provider = ProviderBuilder::new().connect_http(self.rpc_url.parse()?);
+        let provider = &self.provider;
         let precompile_addr = Address::from_str(addresses::MARK_PRICES)?;
 
         // Create calldata: token index as 32-byte word
@@ -125,7 +129,7 @@ impl PrecompileReader {
 
             // Check if price is zero (token doesn't exist at this index)
             if price_u256.is_zero() {
-                return Err(eyre::eyre!("No mark price available for index {} (returned 0x00...)", token_index));
+                return Err(Error::Rpc(format!("No mark price available for index {} (returned 0x00...)", token_index)));
             }
 
             // Convert perp prices using HyperLiquid formula: divide by 10^(6 - szDecimals)
@@ -140,14 +144,14 @@ impl PrecompileReader {
             return Ok(price);
         }
 
-        Err(eyre::eyre!("Invalid mark price response for index {}", token_index))
+        Err(Error::Rpc(format!("Invalid mark price response for index {}", token_index)))
     }
 
     /// Get oracle price for a specific token by index
     pub async fn get_oracle_price_by_index(&self, token_index: u32) -> Result<f64> {
         tracing::info!("🔮 Fetching oracle price for token index {}...", token_index);
 
-        let provider = ProviderBuilder::new().connect_http(self.rpc_url.parse()?);
+        let provider = &self.provider;
         let precompile_addr = Address::from_str(addresses::ORACLE_PRICES)?;
 
         // Create calldata: token index as 32-byte word
@@ -164,7 +168,7 @@ impl PrecompileReader {
 
             // Check if price is zero (token doesn't exist at this index)
             if price_u256.is_zero() {
-                return Err(eyre::eyre!("No oracle price available for index {} (returned 0x00...)", token_index));
+                return Err(Error::Rpc(format!("No oracle price available for index {} (returned 0x00...)", token_index)));
             }
 
             // Convert perp prices using HyperLiquid formula: divide by 10^(6 - szDecimals)
@@ -179,7 +183,7 @@ impl PrecompileReader {
             return Ok(price);
         }
 
-        Err(eyre::eyre!("Invalid oracle price response for index {}", token_index))
+        Err(Error::Rpc(format!("Invalid oracle price response for index {}", token_index)))
     }
 
     /// Get specific token price by asset index
@@ -240,7 +244,7 @@ impl PrecompileReader {
             }
         }
 
-        Err(eyre::eyre!("HYPE price not available via Hypercore precompiles"))
+        Err(Error::Rpc("HYPE price not available via Hypercore precompiles".to_string()))
     }
 
     /// Get ETH token price (commonly at index 1)
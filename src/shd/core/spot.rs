@@ -1,8 +1,9 @@
-use eyre::Result;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 use std::collections::HashMap;
 
+use crate::error::{Error, Result};
+
 // ===== CONFIGURATION =====
 
 /// Configuration for Hyperliquid spot balance fetcher
@@ -50,20 +51,20 @@ impl SpotBalance {
     /// Get total balance as f64
     pub fn total_as_f64(&self) -> Result<f64> {
         self.total.parse()
-            .map_err(|e| eyre::eyre!("Failed to parse total balance for {}: {}", self.coin, e))
+            .map_err(|e| Error::Rpc(format!("Failed to parse total balance for {}: {}", self.coin, e)))
     }
 
     /// Get hold balance as f64
     pub fn hold_as_f64(&self) -> Result<f64> {
         self.hold.parse()
-            .map_err(|e| eyre::eyre!("Failed to parse hold balance for {}: {}", self.coin, e))
+            .map_err(|e| Error::Rpc(format!("Failed to parse hold balance for {}: {}", self.coin, e)))
     }
 
     /// Get available balance as f64
     pub fn available_as_f64(&self) -> Result<f64> {
         if let Some(available) = &self.available {
             available.parse()
-                .map_err(|e| eyre::eyre!("Failed to parse available balance for {}: {}", self.coin, e))
+                .map_err(|e| Error::Rpc(format!("Failed to parse available balance for {}: {}", self.coin, e)))
         } else {
             // Calculate if not already calculated
             Ok(self.total_as_f64()? - self.hold_as_f64()?)
@@ -232,7 +233,7 @@ impl HyperliquidSpotBalances {
         let client = reqwest::Client::builder()
             .timeout(std::time::Duration::from_secs(config.timeout_secs))
             .build()
-            .map_err(|e| eyre::eyre!("Failed to create HTTP client: {}", e))?;
+            .map_err(|e| Error::Rpc(format!("Failed to create HTTP client: {}", e)))?;
 
         Ok(Self { config, client, api_url })
     }
@@ -245,17 +246,17 @@ impl HyperliquidSpotBalances {
             .json(&payload)
             .send()
             .await
-            .map_err(|e| eyre::eyre!("Failed to send API request: {}", e))?;
+            .map_err(|e| Error::Rpc(format!("Failed to send API request: {}", e)))?;
 
         if !response.status().is_success() {
             let status = response.status();
             let text = response.text().await
-                .map_err(|e| eyre::eyre!("Failed to read error response: {}", e))?;
-            return Err(eyre::eyre!("API request failed with status {}: {}", status, text));
+                .map_err(|e| Error::Rpc(format!("Failed to read error response: {}", e)))?;
+            return Err(Error::Rpc(format!("API request failed with status {}: {}", status, text)));
         }
 
         let json = response.json().await
-            .map_err(|e| eyre::eyre!("Failed to parse API response as JSON: {}", e))?;
+            .map_err(|e| Error::Rpc(format!("Failed to parse API response as JSON: {}", e)))?;
         Ok(json)
     }
 
@@ -273,7 +274,7 @@ impl HyperliquidSpotBalances {
         });
 
         let response = self.request(payload).await?;
-        let state: SpotClearinghouseState = serde_json::from_value(response)?;
+        let state: SpotClearinghouseState = serde_json::from_value(response).map_err(|e| Error::Rpc(format!("Unexpected API response: {}", e)))?;
 
         // Convert raw balances to SpotBalance with available calculation
         let mut balances: Vec<SpotBalance> = state
@@ -347,7 +348,7 @@ impl HyperliquidSpotBalances {
         });
 
         let response = self.request(payload).await?;
-        let meta: SpotMetaResponse = serde_json::from_value(response)?;
+        let meta: SpotMetaResponse = serde_json::from_value(response).map_err(|e| Error::Rpc(format!("Unexpected API response: {}", e)))?;
 
         Ok(meta.tokens)
     }
@@ -1 +1,287 @@
-// Database operations and entity management
\ No newline at end of file
+// Database operations and entity management
+
+pub mod entity;
+
+use chrono::Utc;
+use sea_orm::{ActiveValue::Set, Database, DatabaseConnection, DbErr, EntityTrait};
+use tokio::sync::mpsc::{self, error::TrySendError};
+
+use crate::{
+    dex::{
+        pnl::TradePnl,
+        swap::{BestOpportunity, SwapExecution},
+    },
+    error::Result,
+    types::ArbTarget,
+};
+use entity::{arb_execution, arb_opportunity, arb_trade, balance_snapshot};
+
+/// Writer channel capacity, records beyond it are dropped so the hot path never waits on the DB
+pub const DB_CHANNEL_CAPACITY: usize = 1024;
+
+/// Instance name stored with every record (e.g. "HyperArb-bravo")
+pub fn instance_name(vault_name: &str) -> String {
+    format!("HyperArb-{}", vault_name)
+}
+
+// ===== RECORDS =====
+
+/// Opportunity detected during a pool scan
+#[derive(Debug, Clone)]
+pub struct OpportunityRecord {
+    pub instance_name: String,
+    pub block: u64,
+    pub dex: String,
+    pub pool: String,
+    pub pool_price: f64,
+    pub reference: String,
+    pub reference_price: f64,
+    pub spread_bps: f64,
+    pub fee_bps: f64,
+    pub net_bps: f64,
+    pub executable: bool,
+}
+
+/// Final status of an execution attempt
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ExecutionStatus {
+    Confirmed,
+    Reverted,
+    Failed,
+}
+
+impl std::fmt::Display for ExecutionStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ExecutionStatus::Confirmed => write!(f, "confirmed"),
+            ExecutionStatus::Reverted => write!(f, "reverted"),
+            ExecutionStatus::Failed => write!(f, "failed"),
+        }
+    }
+}
+
+/// Execution attempt (confirmed, reverted or failed before inclusion)
+#[derive(Debug, Clone)]
+pub struct ExecutionRecord {
+    pub instance_name: String,
+    pub block: u64,
+    pub dex: String,
+    pub pool: String,
+    pub is_buy: bool,
+    pub token_in: String,
+    pub token_out: String,
+    pub amount_in: String,
+    pub amount_out_min: String,
+    pub amount_out: Option<String>,
+    pub tx_hash: Option<String>,
+    pub gas_used: Option<u64>,
+    pub status: ExecutionStatus,
+    pub error: Option<String>,
+}
+
+impl ExecutionRecord {
+    /// Record for a swap that reached the chain
+    pub fn from_execution(target: &ArbTarget, block: u64, execution: &SwapExecution) -> Self {
+        Self {
+            instance_name: instance_name(&target.vault_name),
+            block,
+            dex: execution.dex.clone(),
+            pool: execution.pool_address.clone(),
+            is_buy: execution.is_buy,
+            token_in: format!("{:?}", execution.token_in),
+            token_out: format!("{:?}", execution.token_out),
+            amount_in: execution.amount_in.to_string(),
+            amount_out_min: execution.amount_out_min.to_string(),
+            amount_out: execution.amount_out.map(|a| a.to_string()),
+            tx_hash: Some(format!("0x{:x}", execution.tx_hash)),
+            gas_used: Some(execution.gas_used),
+            status: if execution.success { ExecutionStatus::Confirmed } else { ExecutionStatus::Reverted },
+            error: None,
+        }
+    }
+
+    /// Record for an attempt that failed before a receipt was obtained
+    pub fn failed(target: &ArbTarget, block: u64, opportunity: &BestOpportunity, error: String) -> Self {
+        // Pool cheaper than reference = buy base with quote
+        let is_buy = opportunity.spread_bps < 0.0;
+        let (token_in, token_out) = if is_buy { (&target.quote_token_address, &target.base_token_address) } else { (&target.base_token_address, &target.quote_token_address) };
+        Self {
+            instance_name: instance_name(&target.vault_name),
+            block,
+            dex: opportunity.dex.clone(),
+            pool: opportunity.pool_address.clone(),
+            is_buy,
+            token_in: token_in.to_lowercase(),
+            token_out: token_out.to_lowercase(),
+            amount_in: "0".to_string(),
+            amount_out_min: "0".to_string(),
+            amount_out: None,
+            tx_hash: None,
+            gas_used: None,
+            status: ExecutionStatus::Failed,
+            error: Some(error),
+        }
+    }
+}
+
+/// Vault balances at a point in time
+#[derive(Debug, Clone)]
+pub struct BalanceSnapshotRecord {
+    pub instance_name: String,
+    pub label: String,
+    pub base_token: String,
+    pub quote_token: String,
+    pub base_balance: f64,
+    pub quote_balance: f64,
+    pub base_value_usd: f64,
+    pub quote_value_usd: f64,
+    pub total_value_usd: f64,
+}
+
+/// Realised PnL of a confirmed or reverted trade (stored as JSON in ArbTrade)
+#[derive(Debug, Clone)]
+pub struct TradeRecord {
+    pub instance_name: String,
+    pub pnl: TradePnl,
+}
+
+/// Any record the writer task can persist
+#[derive(Debug, Clone)]
+pub enum DbRecord {
+    Opportunity(OpportunityRecord),
+    Execution(ExecutionRecord),
+    BalanceSnapshot(BalanceSnapshotRecord),
+    Trade(TradeRecord),
+}
+
+// ===== WRITER =====
+
+/// Handle to the background DB writer (cheap to clone)
+#[derive(Debug, Clone)]
+pub struct DbWriter {
+    sender: Option<mpsc::Sender<DbRecord>>,
+    connection: Option<DatabaseConnection>,
+}
+
+impl DbWriter {
+    /// Writer that discards every record (no database configured or reachable)
+    pub fn disabled() -> Self {
+        Self { sender: None, connection: None }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.sender.is_some()
+    }
+
+    /// Connection shared with the writer task, for startup reads of persisted records
+    pub fn connection(&self) -> Option<&DatabaseConnection> {
+        self.connection.as_ref()
+    }
+
+    /// Queue a record without waiting, dropped with a warning when the channel is full
+    pub fn record(&self, record: DbRecord) {
+        let Some(sender) = &self.sender else {
+            return;
+        };
+        match sender.try_send(record) {
+            Ok(()) => {}
+            Err(TrySendError::Full(_)) => tracing::warn!("🗄️ DB writer channel full, dropping record"),
+            Err(TrySendError::Closed(_)) => tracing::error!("🗄️ DB writer task stopped, dropping record"),
+        }
+    }
+}
+
+/// Connect to the database and spawn the writer task
+pub async fn spawn_writer(database_url: &str, capacity: usize) -> Result<DbWriter> {
+    let db = Database::connect(database_url).await?;
+    let (sender, receiver) = mpsc::channel(capacity);
+    tokio::spawn(writer_task(db.clone(), receiver));
+    Ok(DbWriter {
+        sender: Some(sender),
+        connection: Some(db),
+    })
+}
+
+async fn writer_task(db: DatabaseConnection, mut receiver: mpsc::Receiver<DbRecord>) {
+    while let Some(record) = receiver.recv().await {
+        if let Err(e) = insert(&db, record).await {
+            tracing::error!("🗄️ Failed to persist record: {}", e);
+        }
+    }
+    tracing::info!("🗄️ DB writer channel closed, writer task exiting");
+}
+
+async fn insert(db: &DatabaseConnection, record: DbRecord) -> Result<(), DbErr> {
+    let id = uuid::Uuid::new_v4().to_string();
+    let now = Utc::now().naive_utc();
+
+    match record {
+        DbRecord::Opportunity(r) => {
+            let model = arb_opportunity::ActiveModel {
+                id: Set(id),
+                created_at: Set(now),
+                instance_name: Set(r.instance_name),
+                block: Set(r.block as i64),
+                dex: Set(r.dex),
+                pool: Set(r.pool),
+                pool_price: Set(r.pool_price),
+                reference: Set(r.reference),
+                reference_price: Set(r.reference_price),
+                spread_bps: Set(r.spread_bps),
+                fee_bps: Set(r.fee_bps),
+                net_bps: Set(r.net_bps),
+                executable: Set(r.executable),
+            };
+            arb_opportunity::Entity::insert(model).exec(db).await?;
+        }
+        DbRecord::Execution(r) => {
+            let model = arb_execution::ActiveModel {
+                id: Set(id),
+                created_at: Set(now),
+                instance_name: Set(r.instance_name),
+                block: Set(r.block as i64),
+                dex: Set(r.dex),
+                pool: Set(r.pool),
+                is_buy: Set(r.is_buy),
+                token_in: Set(r.token_in),
+                token_out: Set(r.token_out),
+                amount_in: Set(r.amount_in),
+                amount_out_min: Set(r.amount_out_min),
+                amount_out: Set(r.amount_out),
+                tx_hash: Set(r.tx_hash),
+                gas_used: Set(r.gas_used.map(|g| g as i64)),
+                status: Set(r.status.to_string()),
+                error: Set(r.error),
+            };
+            arb_execution::Entity::insert(model).exec(db).await?;
+        }
+        DbRecord::BalanceSnapshot(r) => {
+            let model = balance_snapshot::ActiveModel {
+                id: Set(id),
+                created_at: Set(now),
+                instance_name: Set(r.instance_name),
+                label: Set(r.label),
+                base_token: Set(r.base_token),
+                quote_token: Set(r.quote_token),
+                base_balance: Set(r.base_balance),
+                quote_balance: Set(r.quote_balance),
+                base_value_usd: Set(r.base_value_usd),
+                quote_value_usd: Set(r.quote_value_usd),
+                total_value_usd: Set(r.total_value_usd),
+            };
+            balance_snapshot::Entity::insert(model).exec(db).await?;
+        }
+        DbRecord::Trade(r) => {
+            let values = serde_json::to_value(&r.pnl).map_err(|e| DbErr::Custom(e.to_string()))?;
+            let model = arb_trade::ActiveModel {
+                id: Set(id),
+                created_at: Set(now),
+                updated_at: Set(now),
+                instance_name: Set(r.instance_name),
+                values: Set(values),
+            };
+            arb_trade::Entity::insert(model).exec(db).await?;
+        }
+    }
+    Ok(())
+}
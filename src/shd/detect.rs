@@ -0,0 +1,102 @@
+// Per-pool detection shared by the live loop and the backtest: spread and threshold maths against the
+// reference, and the candidates of each trading mode. Modes are tried in this order: mean reversion, then
+// cross-pool (falling back to the reference threshold when no round trip clears), then the reference
+// threshold (single pool in statistical-arb mode, double leg otherwise)
+
+use alloy::primitives::Address;
+use std::str::FromStr;
+
+use crate::{
+    dex::{
+        pool_data::{PoolInfo, calculate_pool_prices},
+        swap::BestOpportunity,
+        swap_cross_pool::PoolCandidate,
+    },
+    stats::{MeanReversionState, SpreadStats},
+    types::{ArbTarget, PriceReference},
+};
+
+// Constants
+const BASIS_POINT_DENO: f64 = 10000.0;
+
+/// Spread of one pool against the reference, with the executable threshold of its direction
+#[derive(Debug, Clone)]
+pub struct PoolSpread {
+    pub opportunity: BestOpportunity,
+    pub base_is_token0: bool,
+    /// Skew-adjusted threshold on the spread net of the pool fee (can be negative for lossy trades)
+    pub exec_threshold_bps: f64,
+}
+
+impl PoolSpread {
+    /// Above the watch threshold: logged and persisted, and required to trade in reference mode
+    pub fn is_watched(&self, target: &ArbTarget) -> bool {
+        self.opportunity.spread_bps.abs() >= target.min_watch_spread_bps
+    }
+
+    pub fn is_executable(&self) -> bool {
+        self.opportunity.net_profit_bps >= self.exec_threshold_bps
+    }
+}
+
+/// Price of the base token in quote on a pool and its spread, None when the pool does not hold the base token
+pub fn pool_spread(target: &ArbTarget, dex: &str, pool: &str, pool_info: &PoolInfo, reference_price: f64, inventory_skew: f64) -> Option<PoolSpread> {
+    let base_token = Address::from_str(&target.base_token_address).ok()?;
+    let price = calculate_pool_prices(pool_info);
+    let base_is_token0 = pool_info.token0 == base_token;
+    let pool_price = if base_is_token0 {
+        price.token0_price
+    } else if pool_info.token1 == base_token {
+        price.token1_price
+    } else {
+        return None;
+    };
+    let spread_bps = ((pool_price - reference_price) / reference_price) * BASIS_POINT_DENO;
+    let fee_bps = (price.fee as f64) / 100.0;
+    Some(PoolSpread {
+        opportunity: BestOpportunity {
+            dex: dex.to_string(),
+            pool_address: pool.to_string(),
+            pool_price,
+            spread_bps,
+            fee_bps,
+            net_profit_bps: spread_bps.abs() - fee_bps, // Single fee for one-way trade
+            pool_fee_tier: price.fee,
+        },
+        base_is_token0,
+        exec_threshold_bps: target.executable_threshold_bps(spread_bps < 0.0, inventory_skew),
+    })
+}
+
+/// Tradable pools of a pass, gathered for every mode of the target
+#[derive(Debug, Default)]
+pub struct Candidates {
+    /// Best net spread above the executable and watch thresholds (reference mode)
+    pub best: Option<(BestOpportunity, PoolInfo)>,
+    /// Every pool with its spread statistics (mean-reversion mode)
+    pub reversion: Vec<(BestOpportunity, PoolInfo, Option<SpreadStats>)>,
+    /// Every pool with its state, for price impact sizing (cross-pool mode)
+    pub cross_pool: Vec<PoolCandidate>,
+    /// Every pool (double-leg mode, HyperCore reference only)
+    pub double_leg: Vec<BestOpportunity>,
+}
+
+impl Candidates {
+    /// Add a pool that passed the depth filters, feeding its spread window in mean-reversion mode
+    pub fn add(&mut self, target: &ArbTarget, spread: &PoolSpread, pool_info: &PoolInfo, mean_reversion: &mut MeanReversionState) {
+        let opportunity = &spread.opportunity;
+        if let Some(mr) = &target.mean_reversion {
+            let stats = mean_reversion.observe(pool_info.address, opportunity.spread_bps, mr.window);
+            self.reversion.push((opportunity.clone(), pool_info.clone(), stats));
+        }
+        if target.cross_pool.is_some() {
+            self.cross_pool.push((opportunity.clone(), pool_info.clone()));
+        }
+        if spread.is_executable() && spread.is_watched(target) && self.best.as_ref().is_none_or(|(best, _)| opportunity.net_profit_bps > best.net_profit_bps) {
+            self.best = Some((opportunity.clone(), pool_info.clone()));
+        }
+        if !target.statistical_arb && target.reference == PriceReference::Hypercore {
+            self.double_leg.push(opportunity.clone());
+        }
+    }
+}
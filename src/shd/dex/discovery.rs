@@ -0,0 +1,161 @@
+// Automatic pool discovery from the configured DEX factories
+
+use alloy::{
+    primitives::{Address, aliases::U24},
+    providers::Provider,
+};
+use std::{
+    collections::HashMap,
+    str::FromStr,
+    sync::{Arc, RwLock},
+};
+
+use crate::{
+    dex::pool_data::{IUniswapV3Factory, calculate_pool_tvl, get_pool_info},
+    error::Result,
+    types::{ArbTarget, BotConfig, DiscoveryConfig},
+};
+
+/// DEXs whose pools are scanned, with the target pool list they feed
+pub const DISCOVERY_DEXS: [&str; 2] = ["hyperswap", "projectx"];
+
+/// Pool found through a factory and above the TVL floor
+#[derive(Debug, Clone)]
+pub struct DiscoveredPool {
+    pub dex: String,
+    pub address: Address,
+    pub fee: u32,
+    pub tvl_usd: f64,
+}
+
+/// Query every fee tier of every DEX factory for the target pair, keeping pools above the TVL floor.
+/// Prices are USD per base and per quote token, used to value pool balances.
+pub async fn discover_target_pools<P: Provider + Clone>(
+    provider: P, config: &BotConfig, discovery: &DiscoveryConfig, target: &ArbTarget, base_price_usd: f64, quote_price_usd: f64,
+) -> Result<Vec<DiscoveredPool>> {
+    let base = Address::from_str(&target.base_token_address)?;
+    let quote = Address::from_str(&target.quote_token_address)?;
+    let mut pools = Vec::new();
+
+    for dex_name in DISCOVERY_DEXS {
+        let Some(dex) = config.get_dex(dex_name) else {
+            continue;
+        };
+        let factory = IUniswapV3Factory::new(Address::from_str(&dex.factory)?, provider.clone());
+        for &fee in &discovery.fee_tiers {
+            // A failing tier is skipped, the others are still scanned
+            let address = match factory.getPool(base, quote, U24::from(fee)).call().await {
+                Ok(address) => address,
+                Err(e) => {
+                    tracing::warn!("🔎 {} {} getPool failed (fee {}): {}", target.vault_name, dex_name, fee, e);
+                    continue;
+                }
+            };
+            if address == Address::ZERO {
+                continue;
+            }
+            let info = match get_pool_info(provider.clone(), address).await {
+                Ok(info) => info,
+                Err(e) => {
+                    tracing::warn!("🔎 {} {} pool {} (fee {}) unreadable: {}", target.vault_name, dex_name, address, fee, e);
+                    continue;
+                }
+            };
+            let (price0, price1) = if info.token0 == base {
+                (base_price_usd, quote_price_usd)
+            } else {
+                (quote_price_usd, base_price_usd)
+            };
+            let tvl = match calculate_pool_tvl(provider.clone(), &info, Some(price0), Some(price1)).await {
+                Ok(tvl) => tvl,
+                Err(e) => {
+                    tracing::warn!("🔎 {} {} pool {} (fee {}) TVL unavailable: {}", target.vault_name, dex_name, address, fee, e);
+                    continue;
+                }
+            };
+            let tvl_usd = tvl.total_value_usd.unwrap_or_default();
+            if tvl_usd < discovery.min_tvl_usd {
+                tracing::debug!("🔎 {} {} pool {} (fee {}) below min TVL: ${:.0}", target.vault_name, dex_name, address, fee, tvl_usd);
+                continue;
+            }
+            pools.push(DiscoveredPool {
+                dex: dex_name.to_string(),
+                address,
+                fee,
+                tvl_usd,
+            });
+        }
+    }
+    Ok(pools)
+}
+
+/// Discovered pools per vault, merged into the configured pool lists every block (cheap to clone)
+#[derive(Debug, Clone, Default)]
+pub struct PoolRegistry {
+    inner: Arc<RwLock<HashMap<String, Vec<DiscoveredPool>>>>,
+}
+
+impl PoolRegistry {
+    /// Replace the discovered pools of a vault, logging newly found ones
+    pub fn set(&self, vault_name: &str, pools: Vec<DiscoveredPool>) {
+        let mut guard = self.inner.write().unwrap_or_else(|e| e.into_inner());
+        let previous = guard.get(vault_name).cloned().unwrap_or_default();
+        for pool in &pools {
+            if !previous.iter().any(|p| p.address == pool.address) {
+                tracing::info!("🔎 {} discovered {} pool {} (fee {}) | TVL ${:.0}", vault_name, pool.dex, pool.address, pool.fee, pool.tvl_usd);
+            }
+        }
+        guard.insert(vault_name.to_string(), pools);
+    }
+
+    pub fn get(&self, vault_name: &str) -> Vec<DiscoveredPool> {
+        self.inner.read().unwrap_or_else(|e| e.into_inner()).get(vault_name).cloned().unwrap_or_default()
+    }
+
+    /// Add discovered pools missing from the target pool lists
+    pub fn apply(&self, target: &mut ArbTarget) {
+        for pool in self.get(&target.vault_name) {
+            let pools = if pool.dex == "hyperswap" { &mut target.hyperswap_pools } else { &mut target.prjx_pools };
+            let address = format!("{:?}", pool.address);
+            if !pools.iter().any(|p| p.eq_ignore_ascii_case(&address)) {
+                pools.push(address);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::tests::target;
+
+    const LISTED: &str = "0x337b56d87A6185cD46AF3Ac2cDF03CBC37070C30";
+
+    fn pool(dex: &str, address: &str) -> DiscoveredPool {
+        DiscoveredPool {
+            dex: dex.to_string(),
+            address: Address::from_str(address).unwrap(),
+            fee: 500,
+            tvl_usd: 1e6,
+        }
+    }
+
+    #[test]
+    fn apply_adds_missing_pools_to_their_dex_list() {
+        let registry = PoolRegistry::default();
+        registry.set(
+            "test",
+            vec![
+                pool("hyperswap", LISTED),
+                pool("hyperswap", "0x0000000000000000000000000000000000000a01"),
+                pool("projectx", "0x0000000000000000000000000000000000000b01"),
+            ],
+        );
+        let mut target = target(&format!("hyperswap_pools = [\"{}\"]", LISTED.to_lowercase()));
+        registry.apply(&mut target);
+        registry.apply(&mut target);
+        let lowercase = |pools: &[String]| pools.iter().map(|p| p.to_lowercase()).collect::<Vec<_>>();
+        assert_eq!(lowercase(&target.hyperswap_pools), [LISTED.to_lowercase(), "0x0000000000000000000000000000000000000a01".to_string()]);
+        assert_eq!(lowercase(&target.prjx_pools), ["0x0000000000000000000000000000000000000b01"]);
+    }
+}
@@ -1,5 +1,9 @@
 // pub mod data;  // Commented out due to compilation issues
+pub mod discovery;
+pub mod pnl;
 pub mod pool_data;
+pub mod preflight;
 pub mod swap;
+pub mod swap_cross_pool;
 pub mod swap_double_leg;
-// pub mod swap_router;  // Commented out due to compilation issues
\ No newline at end of file
+pub mod swap_router;
\ No newline at end of file
@@ -0,0 +1,150 @@
+use alloy::{
+    primitives::{Address, I256, U256},
+    rpc::types::TransactionReceipt,
+    sol,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::dex::swap::SwapExecution;
+
+// Constants
+const BASIS_POINT_DENO: f64 = 10000.0;
+
+// ===== RECEIPT EVENTS =====
+
+sol! {
+    /// ERC20 transfer
+    event Transfer(address indexed from, address indexed to, uint256 value);
+
+    /// Uniswap V3 pool swap (amounts are pool deltas: positive = received by the pool)
+    event Swap(address indexed sender, address indexed recipient, int256 amount0, int256 amount1, uint160 sqrtPriceX96, uint128 liquidity, int24 tick);
+}
+
+/// Exact amounts of a swap read from its receipt
+#[derive(Debug, Clone, Copy)]
+pub struct SwapAmounts {
+    pub amount_in: U256,
+    pub amount_out: U256,
+}
+
+/// Amounts from the pool `Swap` event (token0 is always the lower address in V3 pools)
+pub fn decode_swap_amounts(receipt: &TransactionReceipt, pool: Address, token_in: Address, token_out: Address) -> Option<SwapAmounts> {
+    let swap = receipt.logs().iter().filter(|log| log.address() == pool).find_map(|log| log.log_decode::<Swap>().ok())?;
+    let (delta_in, delta_out) = if token_in < token_out {
+        (swap.inner.data.amount0, swap.inner.data.amount1)
+    } else {
+        (swap.inner.data.amount1, swap.inner.data.amount0)
+    };
+
+    // Pool receives token_in (positive) and sends token_out (negative)
+    if delta_in <= I256::ZERO || delta_out >= I256::ZERO {
+        return None;
+    }
+    Some(SwapAmounts {
+        amount_in: delta_in.unsigned_abs(),
+        amount_out: delta_out.unsigned_abs(),
+    })
+}
+
+/// Sum of `token` transferred to `recipient` in a receipt
+pub fn received_amount(receipt: &TransactionReceipt, token: Address, recipient: Address) -> Option<U256> {
+    let amounts: Vec<U256> = receipt
+        .logs()
+        .iter()
+        .filter(|log| log.address() == token)
+        .filter_map(|log| log.log_decode::<Transfer>().ok())
+        .filter(|transfer| transfer.inner.data.to == recipient)
+        .map(|transfer| transfer.inner.data.value)
+        .collect();
+    if amounts.is_empty() { None } else { Some(amounts.into_iter().fold(U256::ZERO, |acc, v| acc + v)) }
+}
+
+/// Gas actually paid by a transaction in wei (gas used * effective gas price)
+pub fn gas_paid_wei(receipt: &TransactionReceipt) -> u128 {
+    receipt.gas_used as u128 * receipt.effective_gas_price
+}
+
+// ===== TRADE PNL =====
+
+/// Realised PnL of a single swap, valued in USD at the reference price.
+/// The quote token is treated as USD, as in trade sizing.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TradePnl {
+    pub tx_hash: String,
+    pub dex: String,
+    pub pool: String,
+    pub is_buy: bool,
+    pub success: bool,
+    /// Base token bought or sold
+    pub base_amount: f64,
+    /// Quote token spent or received
+    pub quote_amount: f64,
+    /// Realised price (quote per base)
+    pub executed_price: f64,
+    /// Pool price net of the pool fee at detection (quote per base)
+    pub expected_price: f64,
+    pub reference_price: f64,
+    /// Price slippage against expected (positive = worse than expected)
+    pub slippage_bps: f64,
+    /// Base leg valued at reference minus quote leg (pool fee already included)
+    pub gross_pnl_usd: f64,
+    /// Pool fee paid on the input leg
+    #[serde(default)]
+    pub fee_usd: f64,
+    pub gas_paid_hype: f64,
+    pub gas_cost_usd: f64,
+    pub net_pnl_usd: f64,
+    /// Fill of a mean-reversion position (replayed at startup to restore the open positions)
+    #[serde(default)]
+    pub mean_reversion: bool,
+}
+
+impl TradePnl {
+    /// Compute the realised PnL of an execution, None if the output amount could not be read from the receipt
+    pub fn compute(execution: &SwapExecution, decimals_in: u8, decimals_out: u8, fee_ratio: f64, expected_price: f64, reference_price: f64, hype_price: f64) -> Option<Self> {
+        let gas_paid_hype = execution.gas_paid_wei as f64 / 1e18;
+        let gas_cost_usd = gas_paid_hype * hype_price;
+
+        let (base_amount, quote_amount) = if execution.success {
+            let amount_out = execution.amount_out?;
+            // Amounts decoded from logs can be anything, saturate rather than panic
+            let amount_in = execution.amount_in.saturating_to::<u128>() as f64 / 10f64.powi(decimals_in as i32);
+            let amount_out = amount_out.saturating_to::<u128>() as f64 / 10f64.powi(decimals_out as i32);
+            if execution.is_buy { (amount_out, amount_in) } else { (amount_in, amount_out) }
+        } else {
+            // Reverted: only gas was spent
+            (0.0, 0.0)
+        };
+
+        let executed_price = if base_amount > 0.0 { quote_amount / base_amount } else { 0.0 };
+        let slippage_bps = if base_amount > 0.0 && expected_price > 0.0 {
+            let deviation = if execution.is_buy { executed_price - expected_price } else { expected_price - executed_price };
+            deviation / expected_price * BASIS_POINT_DENO
+        } else {
+            0.0
+        };
+        let base_value_usd = base_amount * reference_price;
+        let gross_pnl_usd = if execution.is_buy { base_value_usd - quote_amount } else { quote_amount - base_value_usd };
+        let fee_usd = if execution.is_buy { quote_amount * fee_ratio } else { base_value_usd * fee_ratio };
+
+        Some(Self {
+            tx_hash: format!("0x{:x}", execution.tx_hash),
+            dex: execution.dex.clone(),
+            pool: execution.pool_address.clone(),
+            is_buy: execution.is_buy,
+            success: execution.success,
+            base_amount,
+            quote_amount,
+            executed_price,
+            expected_price,
+            reference_price,
+            slippage_bps,
+            gross_pnl_usd,
+            fee_usd,
+            gas_paid_hype,
+            gas_cost_usd,
+            net_pnl_usd: gross_pnl_usd - gas_cost_usd,
+            mean_reversion: false,
+        })
+    }
+}
@@ -4,9 +4,10 @@ use alloy::{
     providers::{Provider, RootProvider},
     rpc::types::Filter,
 };
-use eyre::Result;
 use serde::{Deserialize, Serialize};
 
+use crate::error::Result;
+
 // ===== POOL DATA STRUCTURES =====
 
 /// Pool information from DEX
@@ -103,6 +104,33 @@ alloy::sol!(
             "outputs": [{"internalType": "uint24", "name": "", "type": "uint24"}],
             "stateMutability": "view",
             "type": "function"
+        },
+        {
+            "inputs": [],
+            "name": "factory",
+            "outputs": [{"internalType": "address", "name": "", "type": "address"}],
+            "stateMutability": "view",
+            "type": "function"
+        }
+    ]"#
+);
+
+// Minimal ABI for Uniswap V3 Factory
+alloy::sol!(
+    #[allow(missing_docs)]
+    #[sol(rpc)]
+    IUniswapV3Factory,
+    r#"[
+        {
+            "inputs": [
+                {"internalType": "address", "name": "tokenA", "type": "address"},
+                {"internalType": "address", "name": "tokenB", "type": "address"},
+                {"internalType": "uint24", "name": "fee", "type": "uint24"}
+            ],
+            "name": "getPool",
+            "outputs": [{"internalType": "address", "name": "", "type": "address"}],
+            "stateMutability": "view",
+            "type": "function"
         }
     ]"#
 );
@@ -337,6 +365,54 @@ pub async fn monitor_pool_events<T: Network>(provider: &RootProvider<T>, pool_ad
     Ok(logs)
 }
 
+// ===== SWAP MATH =====
+
+/// Output of an exact-input swap in raw token units, net of the pool fee.
+/// Assumes the swap stays within the current tick range (constant in-range liquidity), so it
+/// overestimates fills that cross initialized ticks - size against thin pools conservatively
+pub fn v3_amount_out(pool_info: &PoolInfo, token_in: Address, amount_in_raw: f64) -> f64 {
+    let Some((liquidity, sqrt_price, next)) = v3_next_sqrt_price(pool_info, token_in, amount_in_raw) else {
+        return 0.0;
+    };
+    if token_in == pool_info.token0 {
+        // token0 in: token1 out = L * (sqrtP - sqrtP')
+        liquidity * (sqrt_price - next)
+    } else {
+        // token1 in: token0 out = L * (1/sqrtP - 1/sqrtP')
+        liquidity * (1.0 / sqrt_price - 1.0 / next)
+    }
+}
+
+/// Pool state after an exact-input swap (sqrtPrice and tick), with the same single-range assumption as `v3_amount_out`
+pub fn v3_state_after(pool_info: &PoolInfo, token_in: Address, amount_in_raw: f64) -> PoolInfo {
+    let Some((_, _, next)) = v3_next_sqrt_price(pool_info, token_in, amount_in_raw) else {
+        return pool_info.clone();
+    };
+    PoolInfo {
+        sqrt_price_x96: U256::try_from(next * 2_f64.powi(96)).unwrap_or(pool_info.sqrt_price_x96),
+        tick: (2.0 * next.ln() / 1.0001_f64.ln()).floor() as i32,
+        ..pool_info.clone()
+    }
+}
+
+/// In-range liquidity, sqrt price and sqrt price after `amount_in_raw` net of the fee, None on an empty pool or amount
+fn v3_next_sqrt_price(pool_info: &PoolInfo, token_in: Address, amount_in_raw: f64) -> Option<(f64, f64, f64)> {
+    let liquidity = pool_info.liquidity.to::<u128>() as f64;
+    let sqrt_price = u128::try_from(pool_info.sqrt_price_x96).map(|v| v as f64).unwrap_or_else(|_| pool_info.sqrt_price_x96.to_string().parse::<f64>().unwrap_or(0.0)) / 2_f64.powi(96);
+    if liquidity <= 0.0 || sqrt_price <= 0.0 || amount_in_raw <= 0.0 {
+        return None;
+    }
+    let amount_in = amount_in_raw * (1.0 - pool_info.fee as f64 / 1_000_000.0);
+    let next = if token_in == pool_info.token0 {
+        // token0 in: 1/sqrtP grows by amount/L
+        1.0 / (1.0 / sqrt_price + amount_in / liquidity)
+    } else {
+        // token1 in: sqrtP grows by amount/L
+        sqrt_price + amount_in / liquidity
+    };
+    Some((liquidity, sqrt_price, next))
+}
+
 // ===== UTILITY FUNCTIONS =====
 
 /// Convert tick to price
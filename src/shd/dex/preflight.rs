@@ -0,0 +1,183 @@
+// Pre-flight validation of the configuration against the chain
+
+use alloy::{
+    primitives::{Address, aliases::U24},
+    providers::Provider,
+};
+use std::{collections::HashSet, str::FromStr};
+
+use crate::{
+    dex::pool_data::{IERC20Metadata, IUniswapV3Factory, IUniswapV3Pool},
+    error::Result,
+    types::{ArbTarget, BotConfig},
+};
+
+/// Outcome of the pre-flight checks
+#[derive(Debug, Clone, Default)]
+pub struct PreflightReport {
+    pub passed: usize,
+    pub failures: Vec<String>,
+}
+
+impl PreflightReport {
+    pub fn is_ok(&self) -> bool {
+        self.failures.is_empty()
+    }
+
+    fn pass(&mut self, message: String) {
+        tracing::info!("  ✅ {}", message);
+        self.passed += 1;
+    }
+
+    fn fail(&mut self, message: String) {
+        tracing::error!("  ❌ {}", message);
+        self.failures.push(message);
+    }
+}
+
+/// Symbols compared case-insensitively on alphanumerics ("USD₮0" matches "USDT0")
+fn normalize_symbol(symbol: &str) -> String {
+    symbol
+        .chars()
+        .map(|c| if c == '₮' { 'T' } else { c })
+        .filter(|c| c.is_ascii_alphanumeric())
+        .collect::<String>()
+        .to_uppercase()
+}
+
+fn parse_address(report: &mut PreflightReport, label: &str, value: &str) -> Option<Address> {
+    match Address::from_str(value) {
+        Ok(address) => Some(address),
+        Err(e) => {
+            report.fail(format!("{}: invalid address '{}': {}", label, value, e));
+            None
+        }
+    }
+}
+
+async fn check_code<P: Provider + Clone>(provider: &P, report: &mut PreflightReport, label: &str, value: &str) {
+    let Some(address) = parse_address(report, label, value) else {
+        return;
+    };
+    match provider.get_code_at(address).await {
+        Ok(code) if !code.is_empty() => report.pass(format!("{} {} has code ({} bytes)", label, value, code.len())),
+        Ok(_) => report.fail(format!("{} {} has no code", label, value)),
+        Err(e) => report.fail(format!("{} {}: failed to fetch code: {}", label, value, e)),
+    }
+}
+
+async fn check_token<P: Provider + Clone>(provider: &P, report: &mut PreflightReport, target: &ArbTarget, symbol: &str, value: &str) -> Option<Address> {
+    let label = format!("{} token {}", target.vault_name, symbol);
+    let address = parse_address(report, &label, value)?;
+    let token = IERC20Metadata::new(address, provider.clone());
+    let decimals = match token.decimals().call().await {
+        Ok(decimals) => decimals,
+        Err(e) => {
+            report.fail(format!("{} ({}): decimals() failed, not an ERC20? {}", label, value, e));
+            return Some(address);
+        }
+    };
+    match token.symbol().call().await {
+        Ok(onchain) if normalize_symbol(&onchain) == normalize_symbol(symbol) => report.pass(format!("{} ({}) symbol {} | {} decimals", label, value, onchain, decimals)),
+        Ok(onchain) => report.fail(format!("{} ({}): on-chain symbol is '{}'", label, value, onchain)),
+        Err(e) => report.fail(format!("{} ({}): symbol() failed: {}", label, value, e)),
+    }
+    Some(address)
+}
+
+async fn check_pool<P: Provider + Clone>(provider: &P, report: &mut PreflightReport, target: &ArbTarget, dex: &str, factory: Address, pool: &str, tokens: (Address, Address)) {
+    let label = format!("{} {} pool", target.vault_name, dex);
+    let Some(address) = parse_address(report, &label, pool) else {
+        return;
+    };
+    let contract = IUniswapV3Pool::new(address, provider.clone());
+    let (token0, token1, fee, pool_factory) = match (
+        contract.token0().call().await,
+        contract.token1().call().await,
+        contract.fee().call().await,
+        contract.factory().call().await,
+    ) {
+        (Ok(token0), Ok(token1), Ok(fee), Ok(pool_factory)) => (token0, token1, fee, pool_factory),
+        _ => {
+            report.fail(format!("{} {}: not a V3 pool (token0/token1/fee/factory calls failed)", label, pool));
+            return;
+        }
+    };
+
+    if pool_factory != factory {
+        report.fail(format!("{} {}: deployed by {} instead of the {} factory {}", label, pool, pool_factory, dex, factory));
+        return;
+    }
+    let configured: HashSet<Address> = [tokens.0, tokens.1].into_iter().collect();
+    let onchain: HashSet<Address> = [token0, token1].into_iter().collect();
+    if configured != onchain {
+        report.fail(format!("{} {}: holds {} / {} instead of {} / {}", label, pool, token0, token1, target.base_token, target.quote_token));
+        return;
+    }
+    // The factory registry is the source of truth for V3 pools
+    match IUniswapV3Factory::new(factory, provider.clone()).getPool(token0, token1, U24::from(fee)).call().await {
+        Ok(registered) if registered == address => report.pass(format!("{} {} ({}/{} fee {})", label, pool, target.base_token, target.quote_token, fee)),
+        Ok(registered) => report.fail(format!("{} {}: factory getPool returns {} for this pair and fee", label, pool, registered)),
+        Err(e) => report.fail(format!("{} {}: factory getPool failed: {}", label, pool, e)),
+    }
+}
+
+/// Verify DEX contracts, tokens and pools of every target against the chain
+pub async fn check_chain<P: Provider + Clone>(provider: P, config: &BotConfig) -> Result<PreflightReport> {
+    let mut report = PreflightReport::default();
+    // Fails fast if the RPC itself is unreachable
+    let block = provider.get_block_number().await?;
+    tracing::info!("🛫 Pre-flight checks against {} at block #{}", config.global.network_name, block);
+
+    for dex in config.get_configured_dexs() {
+        check_code(&provider, &mut report, &format!("{} factory", dex.name), &dex.factory).await;
+        check_code(&provider, &mut report, &format!("{} router", dex.name), &dex.router).await;
+        check_code(&provider, &mut report, &format!("{} quoter", dex.name), &dex.quoter).await;
+    }
+
+    for target in &config.targets {
+        let (base_symbol, quote_symbol) = target.onchain_symbols();
+        let base = check_token(&provider, &mut report, target, base_symbol, &target.base_token_address).await;
+        let quote = check_token(&provider, &mut report, target, quote_symbol, &target.quote_token_address).await;
+        let (Some(base), Some(quote)) = (base, quote) else {
+            continue;
+        };
+
+        for (dex_name, pools) in [("hyperswap", &target.hyperswap_pools), ("projectx", &target.prjx_pools)] {
+            let pools: Vec<&String> = pools.iter().filter(|p| !p.is_empty()).collect();
+            if pools.is_empty() {
+                continue;
+            }
+            let Some(dex) = config.get_dex(dex_name) else {
+                report.fail(format!("{}: {} pools configured but no [[dex]] named {}", target.vault_name, dex_name, dex_name));
+                continue;
+            };
+            let Some(factory) = parse_address(&mut report, &format!("{} factory", dex_name), &dex.factory) else {
+                continue;
+            };
+            for pool in pools {
+                check_pool(&provider, &mut report, target, dex_name, factory, pool, (base, quote)).await;
+            }
+        }
+    }
+
+    if report.is_ok() {
+        tracing::info!("🛫 Pre-flight passed: {} checks", report.passed);
+    } else {
+        tracing::error!("🛫 Pre-flight failed: {} of {} checks", report.failures.len(), report.passed + report.failures.len());
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalize_symbol_matches_display_variants() {
+        assert_eq!(normalize_symbol("USD₮0"), "USDT0");
+        assert_eq!(normalize_symbol("usdt0"), "USDT0");
+        assert_eq!(normalize_symbol("W-HYPE "), "WHYPE");
+        assert_ne!(normalize_symbol("USDC0"), normalize_symbol("USDT0"));
+    }
+}
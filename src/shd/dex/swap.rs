@@ -1,12 +1,16 @@
 use alloy::{
     primitives::{Address, TxHash, U256},
     providers::Provider,
+    rpc::types::TransactionReceipt,
     sol,
 };
-use eyre::Result;
 use std::str::FromStr;
 
 use crate::{
+    error::{Error, Result},
+    rpc,
+    dex::pnl::{TradePnl, decode_swap_amounts, gas_paid_wei, received_amount},
+    tx::{FeeQuote, TX_TRACKER, TxPolicy, quote_fees},
     types::{ArbTarget, BotConfig, EnvConfig, PriceReference},
 };
 
@@ -26,6 +30,39 @@ pub struct BestOpportunity {
     pub pool_fee_tier: u32,
 }
 
+/// Direction and size of a pool trade
+#[derive(Debug, Clone, Copy)]
+pub struct TradeOrder {
+    pub is_buy: bool,
+    /// Amount of the input token (quote when buying, base when selling), None = max_inventory_ratio of the balance
+    pub amount_in: Option<f64>,
+    /// Floor on the output (raw token_out units) above the slippage-based minimum
+    pub min_amount_out: Option<U256>,
+}
+
+impl TradeOrder {
+    /// Raw input amount: the explicit size capped by the balance, or max_inventory_ratio of the balance
+    pub fn amount_in_raw(&self, balance_raw: u128, decimals_in: u8, max_inventory_ratio: f64) -> u128 {
+        match self.amount_in {
+            Some(amount) => ((amount * 10f64.powi(decimals_in as i32)) as u128).min(balance_raw),
+            None => (balance_raw as f64 * max_inventory_ratio) as u128,
+        }
+    }
+}
+
+/// Minimum output of a pool trade: the output at the detection price less SLIPPAGE_PERCENT (raw token_out units)
+pub fn min_amount_out(is_buy: bool, amount_in: f64, pool_price: f64, decimals_out: u8) -> U256 {
+    // Buying base with quote: amount / price, selling base for quote: amount * price
+    let output = if is_buy { amount_in / pool_price } else { amount_in * pool_price };
+    U256::from((output * 10f64.powi(decimals_out as i32)) as u128) * U256::from(100 - SLIPPAGE_PERCENT) / U256::from(100)
+}
+
+/// Expected fill price: pool price net of the pool fee (fee tier in hundredths of a bip)
+pub fn expected_fill_price(is_buy: bool, pool_price: f64, pool_fee_tier: u32) -> f64 {
+    let fee_ratio = pool_fee_tier as f64 / 1_000_000.0;
+    if is_buy { pool_price / (1.0 - fee_ratio) } else { pool_price * (1.0 - fee_ratio) }
+}
+
 /// Double-leg arbitrage opportunity with buy and sell legs
 #[derive(Debug, Clone)]
 pub struct DoubleLegOpportunity {
@@ -51,6 +88,26 @@ pub struct PoolSwapParams {
     pub recipient: Address,
 }
 
+/// Swap that reached the chain, with amounts read back from the receipt
+#[derive(Debug, Clone)]
+pub struct SwapExecution {
+    pub dex: String,
+    pub pool_address: String,
+    pub is_buy: bool,
+    pub token_in: Address,
+    pub token_out: Address,
+    pub amount_in: U256,
+    pub amount_out_min: U256,
+    /// Exact amount of token_out received (None if not found in logs)
+    pub amount_out: Option<U256>,
+    pub tx_hash: TxHash,
+    pub gas_used: u64,
+    pub gas_paid_wei: u128,
+    pub success: bool,
+    /// Realised PnL valued at the reference price
+    pub pnl: Option<TradePnl>,
+}
+
 /// Spot order parameters for CoreWriter
 #[derive(Debug, Clone)]
 pub struct SpotOrderParams {
@@ -154,13 +211,13 @@ sol! {
 }
 
 /// Fetch price based on configured oracle reference
-async fn fetch_price_by_reference(reference: &PriceReference, symbol: &str, config: &BotConfig) -> Result<f64> {
+pub(crate) async fn fetch_price_by_reference(reference: &PriceReference, symbol: &str, config: &BotConfig) -> Result<f64> {
     match reference {
         PriceReference::Pyth => match symbol.to_uppercase().as_str() {
             "BTC" => crate::oracles::pyth::fetch_btc_usd_price().await,
             "ETH" => crate::oracles::pyth::fetch_eth_usd_price().await,
             "HYPE" | "WHYPE" => crate::oracles::fetch_hype_usd_price().await,
-            _ => Err(eyre::eyre!("Pyth oracle doesn't support {} price", symbol)),
+            _ => Err(Error::Oracle(format!("Pyth oracle doesn't support {} price", symbol))),
         },
         PriceReference::Redstone => match symbol.to_uppercase().as_str() {
             "BTC" => crate::oracles::fetch_btc_usd_price().await,
@@ -178,7 +235,15 @@ async fn fetch_price_by_reference(reference: &PriceReference, symbol: &str, conf
     }
 }
 
-/// Execute statistical arbitrage trade
+/// USD price of the target's quote token, 1.0 for dollar-pegged quotes
+pub(crate) async fn fetch_quote_price(target: &ArbTarget, config: &BotConfig) -> Result<f64> {
+    if target.quote_is_usd() {
+        return Ok(1.0);
+    }
+    fetch_price_by_reference(&target.reference, &target.quote_token, config).await
+}
+
+/// Execute statistical arbitrage trade (buy when the pool is below the reference, sized by max_inventory_ratio)
 pub async fn execute_statistical_arbitrage<P: Provider + Clone>(
     provider: P,
     best_opportunity: BestOpportunity,
@@ -186,7 +251,25 @@ pub async fn execute_statistical_arbitrage<P: Provider + Clone>(
     env: &EnvConfig,
     config: &BotConfig,
     reference_price: f64,
-) -> Result<()> {
+) -> Result<Option<SwapExecution>> {
+    let order = TradeOrder {
+        is_buy: best_opportunity.spread_bps < 0.0,
+        amount_in: None,
+        min_amount_out: None,
+    };
+    execute_pool_trade(provider, best_opportunity, order, target, env, config, reference_price).await
+}
+
+/// Execute a swap on the opportunity pool with an explicit direction and size
+pub async fn execute_pool_trade<P: Provider + Clone>(
+    provider: P,
+    best_opportunity: BestOpportunity,
+    order: TradeOrder,
+    target: &ArbTarget,
+    env: &EnvConfig,
+    config: &BotConfig,
+    reference_price: f64,
+) -> Result<Option<SwapExecution>> {
     let BestOpportunity {
         dex,
         pool_address: pool_address_str,
@@ -197,14 +280,15 @@ pub async fn execute_statistical_arbitrage<P: Provider + Clone>(
         pool_fee_tier,
     } = best_opportunity;
     
-    // Step 1: Gas price check
-    let gas_price_wei = get_gas_price(provider.clone()).await?;
+    // Step 1: EIP-1559 fees, the priority fee grows with the spread above the executable threshold
+    let fees = quote_fees(&provider, &config.gas, spread_bps.abs() - target.min_executable_spread_bps, target.max_priority_fee_gwei).await?;
+    let gas_price_wei = fees.effective_gas_price();
     let gas_price_gwei = gas_price_wei / 1_000_000_000;
     
     if gas_price_gwei > config.gas.max_gas_price_gwei as u128 {
         tracing::info!("⛽ Gas too high: {} gwei > {} max. Skipping.", 
             gas_price_gwei, config.gas.max_gas_price_gwei);
-        return Ok(());
+        return Ok(None);
     }
     
     // Step 2: Get HYPE price safely (no fallback)
@@ -212,11 +296,11 @@ pub async fn execute_statistical_arbitrage<P: Provider + Clone>(
         Ok(price) if price > 0.0 => price,
         Ok(_) => {
             tracing::error!("Invalid HYPE price (0 or negative). Skipping trade.");
-            return Ok(());
+            return Ok(None);
         },
         Err(e) => {
             tracing::error!("Failed to fetch HYPE price: {}. Skipping trade.", e);
-            return Ok(());
+            return Ok(None);
         }
     };
     
@@ -230,7 +314,7 @@ pub async fn execute_statistical_arbitrage<P: Provider + Clone>(
         Some(signer) => signer,
         None => {
             tracing::error!("No wallet found for target address: {}", target.address);
-            return Ok(());
+            return Ok(None);
         }
     };
     let wallet_address = wallet.address();
@@ -253,7 +337,7 @@ pub async fn execute_statistical_arbitrage<P: Provider + Clone>(
     
     // Step 5: Determine trade direction and calculate amount
     let (is_buy, token_in, token_out, balance_raw, decimals_in, decimals_out) = 
-        if spread_bps < 0.0 {
+        if order.is_buy {
             // Buy base with quote (pool cheaper than reference)
             (true, quote_token_address, base_token_address, 
              quote_balance, quote_decimals, base_decimals)
@@ -263,8 +347,8 @@ pub async fn execute_statistical_arbitrage<P: Provider + Clone>(
              base_balance, base_decimals, quote_decimals)
         };
     
-    // Calculate trade amount (inventory ratio)
-    let amount_in_raw = (balance_raw.to::<u128>() as f64 * target.max_inventory_ratio) as u128;
+    // Calculate trade amount (explicit size capped by the balance, or inventory ratio)
+    let amount_in_raw = order.amount_in_raw(balance_raw.to::<u128>(), decimals_in, target.max_inventory_ratio);
     let amount_in = U256::from(amount_in_raw);
     
     // Step 6: Check minimum trade value in USD
@@ -278,7 +362,7 @@ pub async fn execute_statistical_arbitrage<P: Provider + Clone>(
     if trade_value_usd < target.min_trade_value_usd {
         tracing::info!("Trade value ${:.2} below minimum ${:.2}. Skipping.", 
             trade_value_usd, target.min_trade_value_usd);
-        return Ok(());
+        return Ok(None);
     }
     
     // Step 7: Check allowance (skip trade if insufficient)
@@ -287,17 +371,17 @@ pub async fn execute_statistical_arbitrage<P: Provider + Clone>(
             let router_str = config.dex.iter()
                 .find(|d| d.name.to_lowercase() == "hyperswap")
                 .map(|d| &d.router)
-                .ok_or_else(|| eyre::eyre!("Hyperswap router not found in config"))?;
+                .ok_or_else(|| Error::Config("Hyperswap router not found in config".to_string()))?;
             Address::from_str(router_str)?
         },
         "projectx" => {
             let router_str = config.dex.iter()
                 .find(|d| d.name.to_lowercase() == "projectx")
                 .map(|d| &d.router)
-                .ok_or_else(|| eyre::eyre!("ProjectX router not found in config"))?;
+                .ok_or_else(|| Error::Config("ProjectX router not found in config".to_string()))?;
             Address::from_str(router_str)?
         },
-        _ => return Err(eyre::eyre!("Unknown DEX: {}", dex)),
+        _ => return Err(Error::Config(format!("Unknown DEX: {}", dex))),
     };
     
     let token_in_contract = IERC20::new(token_in, provider.clone());
@@ -311,28 +395,18 @@ pub async fn execute_statistical_arbitrage<P: Provider + Clone>(
             current_allowance, amount_in);
         tracing::info!("Set infinite approval with: cast send {} 'approve(address,uint256)' {} {}",
             token_in, router_address, U256::MAX);
-        return Ok(());
+        return Ok(None);
     }
     
     // Step 8: Calculate expected output with slippage
-    let expected_output = if is_buy {
-        // Buying WHYPE with USDT: amount / price
-        let output = amount_in_normalized / pool_price;
-        U256::from((output * 10f64.powi(decimals_out as i32)) as u128)
-    } else {
-        // Selling WHYPE for USDT: amount * price
-        let output = amount_in_normalized * pool_price;
-        U256::from((output * 10f64.powi(decimals_out as i32)) as u128)
-    };
-    
-    let amount_out_min = expected_output * U256::from(100 - SLIPPAGE_PERCENT) / U256::from(100);
+    let amount_out_min = min_amount_out(is_buy, amount_in_normalized, pool_price, decimals_out).max(order.min_amount_out.unwrap_or_default());
     
     // Step 9: Log trade details
     tracing::info!("📊 Executing {} on {}:", 
         if is_buy { "BUY" } else { "SELL" }, dex);
     tracing::info!("  Pool: {} | Fee tier: {}", &pool_address_str[..10], pool_fee_tier);
     tracing::info!("  Amount in: {:.6} ({:.1}% of balance)", 
-        amount_in_normalized, target.max_inventory_ratio * 100.0);
+        amount_in_normalized, amount_in_raw as f64 / balance_raw.to::<u128>().max(1) as f64 * 100.0);
     tracing::info!("  Value: ${:.2} | Gas: ${:.2} | Net profit: {:.2} bps",
         trade_value_usd, gas_cost_usd, 
         net_profit_bps - (gas_cost_usd / trade_value_usd * 10000.0));
@@ -343,16 +417,14 @@ pub async fn execute_statistical_arbitrage<P: Provider + Clone>(
         tracing::info!("  Would send swap to {} router: {}", dex, router_address);
         tracing::info!("  Token in: {} | Token out: {}", token_in, token_out);
         tracing::info!("  Amount in: {} | Min out: {}", amount_in, amount_out_min);
-        return Ok(());
+        return Ok(None);
     }
     
-    // Step 11: Log RPC endpoint being used for broadcast
-    if config.global.broadcast_rpc_endpoint.is_some() {
-        tracing::debug!("Using broadcast RPC endpoint for swap transaction");
-    }
+    // Step 11: Log RPC endpoints being used for broadcast
+    tracing::debug!("Broadcasting swap transaction to {} endpoint(s)", config.global.broadcast_endpoints().len());
     
     // Step 12: Build and execute swap based on DEX
-    let tx_hash = match dex.to_lowercase().as_str() {
+    let receipt = match dex.to_lowercase().as_str() {
         "hyperswap" => {
             execute_hyperswap(
                 provider.clone(),
@@ -363,7 +435,7 @@ pub async fn execute_statistical_arbitrage<P: Provider + Clone>(
                 amount_out_min,
                 pool_fee_tier,
                 wallet_address,
-                gas_price_wei,
+                fees,
                 config,
                 wallet.clone(),
             ).await?
@@ -378,17 +450,63 @@ pub async fn execute_statistical_arbitrage<P: Provider + Clone>(
                 amount_out_min,
                 pool_fee_tier,
                 wallet_address,
-                gas_price_wei,
+                fees,
                 config,
                 wallet.clone(),
             ).await?
         },
-        _ => return Err(eyre::eyre!("Unknown DEX")),
+        _ => return Err(Error::Config(format!("Unknown DEX: {}", dex))),
     };
     
-    tracing::info!("✅ Swap executed: 0x{:x}", tx_hash);
+    let tx_hash = receipt.transaction_hash;
+    let pool_address = Address::from_str(&pool_address_str)?;
+
+    // Exact amounts from the pool Swap event, falling back to the ERC20 transfer to the wallet
+    let (amount_in_actual, amount_out) = match decode_swap_amounts(&receipt, pool_address, token_in, token_out) {
+        Some(amounts) => (amounts.amount_in, Some(amounts.amount_out)),
+        None => (amount_in, received_amount(&receipt, token_out, wallet_address)),
+    };
+
+    let mut execution = SwapExecution {
+        dex,
+        pool_address: pool_address_str,
+        is_buy,
+        token_in,
+        token_out,
+        amount_in: amount_in_actual,
+        amount_out_min,
+        amount_out,
+        tx_hash,
+        gas_used: receipt.gas_used,
+        gas_paid_wei: gas_paid_wei(&receipt),
+        success: receipt.status(),
+        pnl: None,
+    };
+
+    let fee_ratio = pool_fee_tier as f64 / 1_000_000.0;
+    let expected_price = expected_fill_price(is_buy, pool_price, pool_fee_tier);
+    execution.pnl = TradePnl::compute(&execution, decimals_in, decimals_out, fee_ratio, expected_price, reference_price, hype_price);
+
+    if execution.success {
+        tracing::info!("✅ Swap executed: 0x{:x} | Gas used: {}", tx_hash, execution.gas_used);
+    } else {
+        tracing::error!("❌ Swap reverted: 0x{:x}", tx_hash);
+    }
+    match &execution.pnl {
+        Some(pnl) => tracing::info!(
+            "💵 Realised: {:.6} base @ {:.6} (expected {:.6}, slippage {:.2} bps) | Gross: ${:.4} | Gas: ${:.4} | Net: ${:.4}",
+            pnl.base_amount,
+            pnl.executed_price,
+            pnl.expected_price,
+            pnl.slippage_bps,
+            pnl.gross_pnl_usd,
+            pnl.gas_cost_usd,
+            pnl.net_pnl_usd
+        ),
+        None => tracing::warn!("Could not read swap output from receipt 0x{:x}, PnL not computed", tx_hash),
+    }
     tracing::info!("   Explorer: {}tx/0x{:x}", config.global.explorer_base_url, tx_hash);
-    Ok(())
+    Ok(Some(execution))
 }
 
 /// Execute swap on Hyperswap (7 params, no deadline)
@@ -401,32 +519,29 @@ async fn execute_hyperswap<P: Provider + Clone>(
     amount_out_min: U256,
     fee: u32,
     recipient: Address,
-    gas_price: u128,
+    fees: FeeQuote,
     config: &BotConfig,
     wallet: alloy::signers::local::PrivateKeySigner,
-) -> Result<TxHash> {
+) -> Result<TransactionReceipt> {
     use alloy::network::EthereumWallet;
     use alloy::providers::ProviderBuilder;
     use std::sync::Arc;
     
-    // Build wallet provider with signer (use broadcast RPC if available)
-    let rpc_url = config.global.broadcast_rpc_endpoint
-        .as_ref()
-        .unwrap_or(&config.global.rpc_endpoint);
+    // Build wallet provider with signer (reads fail over, signed transactions go to every broadcast endpoint)
+    let wallet_address = wallet.address();
     let eth_wallet = EthereumWallet::from(wallet);
     let provider = ProviderBuilder::new()
         .wallet(eth_wallet)
-        .connect_http(rpc_url.parse()?);
+        .connect_client(rpc::client(&config.global)?);
     
     // Create router contract instance
-    let router = IHyperSwapRouter::new(router_address, Arc::new(provider));
+    let router = IHyperSwapRouter::new(router_address, Arc::new(provider.clone()));
     
     // Prepare swap call
     let gas_limit = (SWAP_GAS_UNITS as f64 * config.gas.gas_estimate_multiplier) as u64;
-    let adjusted_gas_price = (gas_price as f64 * config.gas.gas_price_multiplier) as u128;
     
-    // Execute swap with contract method (will handle signing)
-    let pending = router
+    // Build the swap transaction, signed by the wallet provider on send
+    let request = router
         .exactInputSingle((
             token_in,
             token_out,
@@ -437,14 +552,12 @@ async fn execute_hyperswap<P: Provider + Clone>(
             alloy::primitives::Uint::<160, 3>::ZERO, // sqrtPriceLimitX96
         ))
         .gas(gas_limit)
-        .gas_price(adjusted_gas_price)
-        .send()
-        .await?;
-    
-    let tx_hash = *pending.tx_hash();
-    let _receipt = pending.get_receipt().await?;
+        .max_fee_per_gas(fees.max_fee_per_gas)
+        .max_priority_fee_per_gas(fees.max_priority_fee_per_gas)
+        .into_transaction_request();
     
-    Ok(tx_hash)
+    // Nonce, inclusion deadline and gas bumps handled by the tracker
+    TX_TRACKER.send(&provider, wallet_address, request, TxPolicy::from(&config.gas).capped(&fees)).await?.into_receipt()
 }
 
 /// Execute swap on ProjectX (8 params, with deadline)
@@ -457,39 +570,37 @@ async fn execute_projectx<P: Provider + Clone>(
     amount_out_min: U256,
     fee: u32,
     recipient: Address,
-    gas_price: u128,
+    fees: FeeQuote,
     config: &BotConfig,
     wallet: alloy::signers::local::PrivateKeySigner,
-) -> Result<TxHash> {
+) -> Result<TransactionReceipt> {
     use alloy::network::EthereumWallet;
     use alloy::providers::ProviderBuilder;
     use std::sync::Arc;
     
-    // Build wallet provider with signer (use broadcast RPC if available)
-    let rpc_url = config.global.broadcast_rpc_endpoint
-        .as_ref()
-        .unwrap_or(&config.global.rpc_endpoint);
+    // Build wallet provider with signer (reads fail over, signed transactions go to every broadcast endpoint)
+    let wallet_address = wallet.address();
     let eth_wallet = EthereumWallet::from(wallet);
     let provider = ProviderBuilder::new()
         .wallet(eth_wallet)
-        .connect_http(rpc_url.parse()?);
+        .connect_client(rpc::client(&config.global)?);
     
     // Create router contract instance
-    let router = IProjectXRouter::new(router_address, Arc::new(provider));
+    let router = IProjectXRouter::new(router_address, Arc::new(provider.clone()));
     
     // ProjectX uses 8 params (with deadline)
     let deadline = U256::from(
         std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)?
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_err(|e| Error::Execution(e.to_string()))?
             .as_secs() + 300 // 5 minutes
     );
     
     // Prepare swap call
     let gas_limit = (SWAP_GAS_UNITS as f64 * config.gas.gas_estimate_multiplier) as u64;
-    let adjusted_gas_price = (gas_price as f64 * config.gas.gas_price_multiplier) as u128;
     
-    // Execute swap with contract method (will handle signing)
-    let pending = router
+    // Build the swap transaction, signed by the wallet provider on send
+    let request = router
         .exactInputSingle((
             token_in,
             token_out,
@@ -501,12 +612,10 @@ async fn execute_projectx<P: Provider + Clone>(
             alloy::primitives::Uint::<160, 3>::ZERO,
         ))
         .gas(gas_limit)
-        .gas_price(adjusted_gas_price)
-        .send()
-        .await?;
-    
-    let tx_hash = *pending.tx_hash();
-    let _receipt = pending.get_receipt().await?;
+        .max_fee_per_gas(fees.max_fee_per_gas)
+        .max_priority_fee_per_gas(fees.max_priority_fee_per_gas)
+        .into_transaction_request();
     
-    Ok(tx_hash)
+    // Nonce, inclusion deadline and gas bumps handled by the tracker
+    TX_TRACKER.send(&provider, wallet_address, request, TxPolicy::from(&config.gas).capped(&fees)).await?.into_receipt()
 }
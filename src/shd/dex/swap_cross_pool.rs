@@ -0,0 +1,576 @@
+use alloy::{
+    network::EthereumWallet,
+    primitives::{Address, U256, Uint},
+    providers::{Provider, ProviderBuilder},
+    rpc::types::TransactionReceipt,
+    sol,
+};
+use std::str::FromStr;
+
+use crate::{
+    dex::{
+        pnl::{TradePnl, decode_swap_amounts, gas_paid_wei},
+        pool_data::{PoolInfo, v3_amount_out},
+        swap_router::{ArbitrageParams, execute_arbitrage},
+    },
+    error::{Error, Result},
+    rpc,
+    tx::{TX_TRACKER, TxPolicy, quote_fees},
+    types::{ArbTarget, BotConfig, CrossPoolConfig, CrossPoolMode, EnvConfig},
+};
+
+use super::swap::{
+    BestOpportunity, IERC20, PoolSwapParams, SLIPPAGE_PERCENT, SWAP_GAS_UNITS, SwapExecution, TradeOrder, execute_pool_trade, expected_fill_price, fetch_price_by_reference, fetch_quote_price,
+};
+
+// Both swaps plus the contract transfers and profit check (atomic and flash modes)
+const ATOMIC_GAS_UNITS: u128 = 2 * SWAP_GAS_UNITS + 60_000;
+
+// Iterations of the size search (the bracket shrinks by a third each time)
+const SIZE_SEARCH_ITERATIONS: usize = 100;
+
+// ===== HYPERARB CONTRACT ABI =====
+
+sol! {
+    #[sol(rpc)]
+    contract IHyperArb {
+        struct PoolSwapParams {
+            string dex;
+            address routerAddress;
+            address tokenIn;
+            address tokenOut;
+            uint256 amountIn;
+            uint256 amountOutMin;
+            string poolAddress;
+            uint24 poolFeeTier;
+            address recipient;
+        }
+
+        function crossPoolArb(PoolSwapParams calldata buy, PoolSwapParams calldata sell, uint256 minProfit) external returns (uint256 profit);
+    }
+}
+
+impl From<&PoolSwapParams> for IHyperArb::PoolSwapParams {
+    fn from(params: &PoolSwapParams) -> Self {
+        Self {
+            dex: params.dex.to_lowercase(),
+            routerAddress: params.router_address,
+            tokenIn: params.token_in,
+            tokenOut: params.token_out,
+            amountIn: params.amount_in,
+            amountOutMin: params.amount_out_min,
+            poolAddress: params.pool_address.clone(),
+            poolFeeTier: Uint::<24, 1>::from(params.pool_fee_tier),
+            recipient: params.recipient,
+        }
+    }
+}
+
+// ===== SIZING =====
+
+/// Round trip through both pools for a given quote input (raw units)
+#[derive(Debug, Clone, Copy)]
+pub struct CrossPoolQuote {
+    pub quote_in: f64,
+    pub base_out: f64,
+    pub quote_out: f64,
+}
+
+impl CrossPoolQuote {
+    pub fn profit(&self) -> f64 {
+        self.quote_out - self.quote_in
+    }
+}
+
+/// Quote in -> base on the buy pool -> quote on the sell pool, with both pool fees and price impacts
+pub fn round_trip(buy_pool: &PoolInfo, sell_pool: &PoolInfo, base: Address, quote: Address, quote_in: f64) -> CrossPoolQuote {
+    let base_out = v3_amount_out(buy_pool, quote, quote_in);
+    let quote_out = v3_amount_out(sell_pool, base, base_out);
+    CrossPoolQuote { quote_in, base_out, quote_out }
+}
+
+/// Most profitable quote input up to `max_quote_in` (raw units).
+/// Profit is concave in size (each pool's output is concave), so a ternary search finds the peak
+pub fn optimal_round_trip(buy_pool: &PoolInfo, sell_pool: &PoolInfo, base: Address, quote: Address, max_quote_in: f64) -> CrossPoolQuote {
+    let (mut low, mut high) = (0.0, max_quote_in.max(0.0));
+    for _ in 0..SIZE_SEARCH_ITERATIONS {
+        let left = low + (high - low) / 3.0;
+        let right = high - (high - low) / 3.0;
+        if round_trip(buy_pool, sell_pool, base, quote, left).profit() < round_trip(buy_pool, sell_pool, base, quote, right).profit() {
+            low = left;
+        } else {
+            high = right;
+        }
+    }
+    round_trip(buy_pool, sell_pool, base, quote, (low + high) / 2.0)
+}
+
+/// Quote input cap (raw units): the inventory ratio of the quote balance and the per-trade cap (USD at `quote_price`).
+/// Flash swaps borrow the base, only the cap applies
+pub fn max_quote_in(cross_pool: &CrossPoolConfig, target: &ArbTarget, quote_balance_raw: f64, quote_decimals: u8, quote_price: f64) -> f64 {
+    let max_trade = cross_pool.max_trade_usd / quote_price * 10f64.powi(quote_decimals as i32);
+    match cross_pool.mode {
+        CrossPoolMode::Flash => max_trade,
+        _ => (quote_balance_raw * target.max_inventory_ratio).min(max_trade),
+    }
+}
+
+/// Gas of both legs (two transactions, or one larger contract call)
+pub fn gas_units(mode: &CrossPoolMode) -> u128 {
+    match mode {
+        CrossPoolMode::Sequential => 2 * SWAP_GAS_UNITS,
+        CrossPoolMode::Atomic | CrossPoolMode::Flash => ATOMIC_GAS_UNITS,
+    }
+}
+
+// ===== DETECTION =====
+
+/// Sized pool-to-pool opportunity
+#[derive(Debug, Clone)]
+pub struct CrossPoolOpportunity {
+    pub buy: BestOpportunity,
+    pub sell: BestOpportunity,
+    /// Pool states the size was searched on
+    pub buy_pool: PoolInfo,
+    pub sell_pool: PoolInfo,
+    pub quote_in: U256,
+    pub expected_base_out: U256,
+    pub expected_quote_out: U256,
+    pub base_decimals: u8,
+    pub quote_decimals: u8,
+    /// Quote gained after both pool fees and price impacts, in USD
+    pub profit_usd: f64,
+    /// USD price of the quote token
+    pub quote_price: f64,
+    pub gas_cost_usd: f64,
+    pub hype_price: f64,
+}
+
+impl CrossPoolOpportunity {
+    pub fn net_profit_usd(&self) -> f64 {
+        self.profit_usd - self.gas_cost_usd
+    }
+
+    pub fn quote_in_normalized(&self) -> f64 {
+        self.quote_in.to::<u128>() as f64 / 10f64.powi(self.quote_decimals as i32)
+    }
+
+    pub fn quote_in_usd(&self) -> f64 {
+        self.quote_in_normalized() * self.quote_price
+    }
+
+    pub fn net_profit_bps(&self) -> f64 {
+        self.net_profit_usd() / self.quote_in_usd() * 10_000.0
+    }
+
+    /// min_profit_usd plus the gas of both legs, in raw quote units
+    pub fn min_profit_raw(&self, min_profit_usd: f64) -> f64 {
+        (min_profit_usd + self.gas_cost_usd) / self.quote_price * 10f64.powi(self.quote_decimals as i32)
+    }
+
+    /// Net of gas, the round trip clears min_profit_usd and min_executable_spread_bps
+    pub fn is_executable(&self, cross_pool: &CrossPoolConfig, target: &ArbTarget) -> bool {
+        self.net_profit_usd() >= cross_pool.min_profit_usd && self.net_profit_bps() >= target.min_executable_spread_bps
+    }
+}
+
+/// Minimum output of the sequential sell leg (raw quote): the quote spent on the buy leg plus min_profit_usd and gas.
+/// None when the sell pool state it was sized on does not pay that back (the bought base is then kept)
+pub fn sell_leg_min_out(opportunity: &CrossPoolOpportunity, base: Address, base_in_raw: f64, quote_spent_raw: f64, min_profit_usd: f64) -> Option<U256> {
+    let floor = quote_spent_raw + opportunity.min_profit_raw(min_profit_usd);
+    (v3_amount_out(&opportunity.sell_pool, base, base_in_raw) >= floor).then(|| U256::from(floor.ceil() as u128))
+}
+
+/// Tradable pool with the state it is sized on
+pub type PoolCandidate = (BestOpportunity, PoolInfo);
+
+/// Cheapest and richest candidates with their price gap in bps, None when the gap does not cover both pool fees
+pub fn price_gap(candidates: &[PoolCandidate]) -> Option<(&PoolCandidate, &PoolCandidate, f64)> {
+    let buy = candidates.iter().min_by(|a, b| a.0.pool_price.total_cmp(&b.0.pool_price))?;
+    let sell = candidates.iter().max_by(|a, b| a.0.pool_price.total_cmp(&b.0.pool_price))?;
+    let gap_bps = (sell.0.pool_price - buy.0.pool_price) / buy.0.pool_price * 10_000.0;
+    if buy.0.pool_address == sell.0.pool_address || gap_bps <= buy.0.fee_bps + sell.0.fee_bps {
+        return None;
+    }
+    Some((buy, sell, gap_bps))
+}
+
+/// Cheapest and richest of the candidate pools, sized on their V3 state, when the round trip clears
+/// min_profit_usd and min_executable_spread_bps after gas. Candidates are the target's tradable pools
+pub async fn find_cross_pool_opportunity<P: Provider + Clone>(
+    provider: P, candidates: &[(BestOpportunity, PoolInfo)], target: &ArbTarget, env: &EnvConfig, config: &BotConfig,
+) -> Result<Option<CrossPoolOpportunity>> {
+    let Some(cross_pool) = &target.cross_pool else {
+        return Ok(None);
+    };
+    // Not worth sizing when the price gap does not even cover both fees
+    let Some(((buy, buy_pool), (sell, sell_pool), gap_bps)) = price_gap(candidates) else {
+        tracing::debug!("No cross-pool gap for {} covering both pool fees", target.vault_name);
+        return Ok(None);
+    };
+
+    let wallet_address = env
+        .get_signer_for_address(&target.address)
+        .ok_or_else(|| Error::Config(format!("No wallet found for target address: {}", target.address)))?
+        .address();
+    let base_token_address = Address::from_str(&target.base_token_address)?;
+    let quote_token_address = Address::from_str(&target.quote_token_address)?;
+    let base_token_contract = IERC20::new(base_token_address, provider.clone());
+    let quote_token_contract = IERC20::new(quote_token_address, provider.clone());
+    let base_decimals = base_token_contract.decimals().call().await?;
+    let quote_decimals = quote_token_contract.decimals().call().await?;
+    let quote_balance = quote_token_contract.balanceOf(wallet_address).call().await?;
+    let quote_price = fetch_quote_price(target, config).await?;
+
+    // Size within the inventory ratio and the per-trade cap
+    let quote_scale = 10f64.powi(quote_decimals as i32);
+    let max_quote_in = max_quote_in(cross_pool, target, quote_balance.to::<u128>() as f64, quote_decimals, quote_price);
+    let sized = optimal_round_trip(buy_pool, sell_pool, base_token_address, quote_token_address, max_quote_in);
+    let profit_usd = sized.profit() / quote_scale * quote_price;
+    let quote_in_usd = sized.quote_in / quote_scale * quote_price;
+    if profit_usd <= 0.0 || quote_in_usd < target.min_trade_value_usd {
+        tracing::debug!(
+            "Cross-pool {} -> {} for {}: no profitable size (${:.2} in, ${:.4} profit)",
+            buy.dex,
+            sell.dex,
+            target.vault_name,
+            quote_in_usd,
+            profit_usd
+        );
+        return Ok(None);
+    }
+
+    // Gas of both legs (two transactions, or one larger contract call)
+    let fees = quote_fees(&provider, &config.gas, gap_bps - target.min_executable_spread_bps, target.max_priority_fee_gwei).await?;
+    let gas_price_wei = fees.effective_gas_price();
+    if gas_price_wei / 1_000_000_000 > config.gas.max_gas_price_gwei as u128 {
+        tracing::info!(
+            "⛽ Gas too high for cross-pool: {} gwei > {} max. Skipping.",
+            gas_price_wei / 1_000_000_000,
+            config.gas.max_gas_price_gwei
+        );
+        return Ok(None);
+    }
+    let hype_price = fetch_price_by_reference(&target.reference, "HYPE", config).await?;
+    let gas_cost_usd = (gas_units(&cross_pool.mode) * gas_price_wei) as f64 / 1e18 * hype_price;
+
+    let opportunity = CrossPoolOpportunity {
+        buy: buy.clone(),
+        sell: sell.clone(),
+        buy_pool: buy_pool.clone(),
+        sell_pool: sell_pool.clone(),
+        quote_in: U256::from(sized.quote_in as u128),
+        expected_base_out: U256::from(sized.base_out as u128),
+        expected_quote_out: U256::from(sized.quote_out as u128),
+        base_decimals,
+        quote_decimals,
+        profit_usd,
+        quote_price,
+        gas_cost_usd,
+        hype_price,
+    };
+    let net_profit_bps = opportunity.net_profit_bps();
+    tracing::info!(
+        "🔁 Cross-pool {} {} @ {:.6} -> {} {} @ {:.6} | Gap: {:.2} bps | Size: ${:.2} | After fees & impact: ${:.4} | Gas: ${:.4} | Net: {:.2} bps",
+        buy.dex,
+        &buy.pool_address[..10],
+        buy.pool_price,
+        sell.dex,
+        &sell.pool_address[..10],
+        sell.pool_price,
+        gap_bps,
+        quote_in_usd,
+        profit_usd,
+        gas_cost_usd,
+        net_profit_bps
+    );
+    if !opportunity.is_executable(cross_pool, target) {
+        tracing::info!(
+            "Cross-pool net ${:.4} ({:.2} bps) below minimum ${:.2} / {:.2} bps",
+            opportunity.net_profit_usd(),
+            net_profit_bps,
+            cross_pool.min_profit_usd,
+            target.min_executable_spread_bps
+        );
+        return Ok(None);
+    }
+    Ok(Some(opportunity))
+}
+
+// ===== EXECUTION =====
+
+/// Execute both legs in the configured mode. Returns the outcome of each leg that was attempted
+/// (the sequential sell leg is only sent once the buy leg filled)
+pub async fn execute_cross_pool<P: Provider + Clone>(
+    provider: P, opportunity: &CrossPoolOpportunity, target: &ArbTarget, env: &EnvConfig, config: &BotConfig, reference_price: f64,
+) -> Vec<(BestOpportunity, Result<Option<SwapExecution>>)> {
+    let (mode, min_profit_usd) = target.cross_pool.as_ref().map(|c| (c.mode, c.min_profit_usd)).unwrap_or_default();
+    match mode {
+        CrossPoolMode::Sequential => {
+            let buy_order = TradeOrder {
+                is_buy: true,
+                amount_in: Some(opportunity.quote_in_normalized()),
+                min_amount_out: None,
+            };
+            let bought = execute_pool_trade(provider.clone(), opportunity.buy.clone(), buy_order, target, env, config, reference_price).await;
+            let filled = match &bought {
+                Ok(Some(execution)) if execution.success => execution.pnl.as_ref().filter(|p| p.base_amount > 0.0).map(|p| (p.base_amount, execution.amount_in)),
+                _ => None,
+            };
+            let mut legs = vec![(opportunity.buy.clone(), bought)];
+            let Some((base_amount, quote_spent)) = filled else {
+                tracing::warn!("Cross-pool buy leg did not fill, sell leg on {} not sent", opportunity.sell.dex);
+                return legs;
+            };
+            // The sell leg must return the quote spent plus the minimum profit and gas, or it is not sent
+            let base_in_raw = base_amount * 10f64.powi(opportunity.base_decimals as i32);
+            let min_out = Address::from_str(&target.base_token_address)
+                .ok()
+                .and_then(|base| sell_leg_min_out(opportunity, base, base_in_raw, quote_spent.to::<u128>() as f64, min_profit_usd));
+            match min_out {
+                Some(min_out) => {
+                    let sell_order = TradeOrder {
+                        is_buy: false,
+                        amount_in: Some(base_amount),
+                        min_amount_out: Some(min_out),
+                    };
+                    let sold = execute_pool_trade(provider, opportunity.sell.clone(), sell_order, target, env, config, reference_price).await;
+                    legs.push((opportunity.sell.clone(), sold));
+                }
+                None => tracing::warn!(
+                    "Cross-pool sell of {:.6} {} on {} would not return {} {} spent plus ${:.2} profit, base kept",
+                    base_amount,
+                    target.base_token,
+                    opportunity.sell.dex,
+                    quote_spent,
+                    target.quote_token,
+                    min_profit_usd
+                ),
+            }
+            legs
+        }
+        CrossPoolMode::Atomic | CrossPoolMode::Flash => {
+            let executed = if mode == CrossPoolMode::Flash {
+                execute_flash(opportunity, target, env, config, reference_price).await
+            } else {
+                execute_atomic(opportunity, target, env, config, reference_price).await
+            };
+            match executed {
+                Ok(Some((bought, sold))) => vec![(opportunity.buy.clone(), Ok(Some(bought))), (opportunity.sell.clone(), Ok(Some(sold)))],
+                Ok(None) => vec![(opportunity.buy.clone(), Ok(None))],
+                Err(e) => vec![(opportunity.buy.clone(), Err(e))],
+            }
+        }
+    }
+}
+
+/// HyperArb contract of the target's cross_pool config
+fn contract_address(target: &ArbTarget) -> Result<Address> {
+    let cross_pool = target.cross_pool.as_ref().ok_or_else(|| Error::Config(format!("No cross_pool config for {}", target.vault_name)))?;
+    let address = cross_pool
+        .contract_address
+        .as_deref()
+        .ok_or_else(|| Error::Config(format!("cross_pool {} mode for {} needs contract_address", cross_pool.mode, target.vault_name)))?;
+    Ok(Address::from_str(address)?)
+}
+
+/// Router of a DEX from the config
+fn router_address(config: &BotConfig, dex: &str) -> Result<Address> {
+    let router = config
+        .dex
+        .iter()
+        .find(|d| d.name.to_lowercase() == dex.to_lowercase())
+        .map(|d| &d.router)
+        .ok_or_else(|| Error::Config(format!("{} router not found in config", dex)))?;
+    Ok(Address::from_str(router)?)
+}
+
+/// Both legs in one HyperArb.crossPoolArb call, which reverts unless the quote returned covers the
+/// input plus min_profit_usd and gas. Returns the buy and sell legs read from the single receipt
+async fn execute_atomic(opportunity: &CrossPoolOpportunity, target: &ArbTarget, env: &EnvConfig, config: &BotConfig, reference_price: f64) -> Result<Option<(SwapExecution, SwapExecution)>> {
+    let cross_pool = target.cross_pool.as_ref().ok_or_else(|| Error::Config(format!("No cross_pool config for {}", target.vault_name)))?;
+    let contract_address = contract_address(target)?;
+    let wallet = env
+        .get_signer_for_address(&target.address)
+        .ok_or_else(|| Error::Config(format!("No wallet found for target address: {}", target.address)))?;
+    let wallet_address = wallet.address();
+    let provider = ProviderBuilder::new().wallet(EthereumWallet::from(wallet)).connect_client(rpc::client(&config.global)?);
+
+    let base_token_address = Address::from_str(&target.base_token_address)?;
+    let quote_token_address = Address::from_str(&target.quote_token_address)?;
+
+    // The contract pulls the quote input from the wallet
+    let allowance = IERC20::new(quote_token_address, provider.clone()).allowance(wallet_address, contract_address).call().await?;
+    if allowance < opportunity.quote_in {
+        tracing::warn!("Insufficient allowance to HyperArb: {} < {}. Skipping trade.", allowance, opportunity.quote_in);
+        tracing::info!(
+            "Set infinite approval with: cast send {} 'approve(address,uint256)' {} {}",
+            quote_token_address,
+            contract_address,
+            U256::MAX
+        );
+        return Ok(None);
+    }
+
+    let slippage = |amount: U256| amount * U256::from(100 - SLIPPAGE_PERCENT) / U256::from(100);
+    let buy = PoolSwapParams {
+        dex: opportunity.buy.dex.clone(),
+        router_address: router_address(config, &opportunity.buy.dex)?,
+        token_in: quote_token_address,
+        token_out: base_token_address,
+        amount_in: opportunity.quote_in,
+        amount_out_min: slippage(opportunity.expected_base_out),
+        pool_address: opportunity.buy.pool_address.clone(),
+        pool_fee_tier: opportunity.buy.pool_fee_tier,
+        recipient: contract_address,
+    };
+    let sell = PoolSwapParams {
+        dex: opportunity.sell.dex.clone(),
+        router_address: router_address(config, &opportunity.sell.dex)?,
+        token_in: base_token_address,
+        token_out: quote_token_address,
+        amount_in: U256::ZERO,      // Whole buy output
+        amount_out_min: U256::ZERO, // Bounded by min_profit on the contract
+        pool_address: opportunity.sell.pool_address.clone(),
+        pool_fee_tier: opportunity.sell.pool_fee_tier,
+        recipient: contract_address,
+    };
+    let min_profit = U256::from(opportunity.min_profit_raw(cross_pool.min_profit_usd) as u128);
+
+    tracing::info!(
+        "📊 Executing atomic cross-pool via {}: {} {} quote in, min profit {} | Buy {} {} | Sell {} {}",
+        contract_address,
+        opportunity.quote_in_normalized(),
+        target.quote_token,
+        min_profit,
+        buy.dex,
+        &buy.pool_address[..10],
+        sell.dex,
+        &sell.pool_address[..10]
+    );
+    if env.testing {
+        tracing::info!("🧪 TESTING MODE - Cross-pool arbitrage would be executed but not broadcast");
+        return Ok(None);
+    }
+
+    let fees = quote_fees(&provider, &config.gas, opportunity.net_profit_bps() - target.min_executable_spread_bps, target.max_priority_fee_gwei).await?;
+    let request = IHyperArb::new(contract_address, provider.clone())
+        .crossPoolArb((&buy).into(), (&sell).into(), min_profit)
+        .gas((ATOMIC_GAS_UNITS as f64 * config.gas.gas_estimate_multiplier) as u64)
+        .max_fee_per_gas(fees.max_fee_per_gas)
+        .max_priority_fee_per_gas(fees.max_priority_fee_per_gas)
+        .into_transaction_request();
+    let receipt = TX_TRACKER.send(&provider, wallet_address, request, TxPolicy::from(&config.gas).capped(&fees)).await?.into_receipt()?;
+
+    receipt_legs(&receipt, opportunity, target, config, reference_price).map(Some)
+}
+
+/// Flash swap through HyperArb.flashArb: the buy pool lends the base, the callback sells it on the sell pool
+/// and repays the quote owed, the profit goes to the wallet. Nothing is needed in the wallet but gas
+async fn execute_flash(opportunity: &CrossPoolOpportunity, target: &ArbTarget, env: &EnvConfig, config: &BotConfig, reference_price: f64) -> Result<Option<(SwapExecution, SwapExecution)>> {
+    let cross_pool = target.cross_pool.as_ref().ok_or_else(|| Error::Config(format!("No cross_pool config for {}", target.vault_name)))?;
+    let contract_address = contract_address(target)?;
+    let wallet = env
+        .get_signer_for_address(&target.address)
+        .ok_or_else(|| Error::Config(format!("No wallet found for target address: {}", target.address)))?;
+    let wallet_address = wallet.address();
+    let provider = ProviderBuilder::new().wallet(EthereumWallet::from(wallet)).connect_client(rpc::client(&config.global)?);
+
+    let base_token_address = Address::from_str(&target.base_token_address)?;
+    let quote_token_address = Address::from_str(&target.quote_token_address)?;
+    let min_profit = U256::from(opportunity.min_profit_raw(cross_pool.min_profit_usd) as u128);
+
+    // Sizes around the V3-math optimum, simulated in parallel, the most profitable one is sent
+    let max_quote_in = cross_pool.max_trade_usd / opportunity.quote_price * 10f64.powi(opportunity.quote_decimals as i32);
+    let optimum = opportunity.quote_in.to::<u128>() as f64;
+    let mut amounts: Vec<f64> = match cross_pool.flash_sizes {
+        0 | 1 => vec![optimum],
+        n => (0..n).map(|i| (optimum * (0.5 + i as f64 / (n - 1) as f64)).min(max_quote_in)).collect(),
+    };
+    amounts.dedup();
+    let mut candidates = Vec::with_capacity(amounts.len());
+    for amount in amounts {
+        let quote = round_trip(&opportunity.buy_pool, &opportunity.sell_pool, base_token_address, quote_token_address, amount);
+        candidates.push(ArbitrageParams {
+            buy_pool: Address::from_str(&opportunity.buy.pool_address)?,
+            sell_pool: Address::from_str(&opportunity.sell.pool_address)?,
+            token_in: quote_token_address,
+            token_out: base_token_address,
+            amount: U256::from(amount as u128),
+            expected_profit: U256::from(quote.profit().max(0.0) as u128),
+            min_profit,
+        });
+    }
+
+    tracing::info!(
+        "⚡ Executing flash cross-pool via {}: borrow on {} {}, sell on {} {} | {} {} quote owed, min profit {}",
+        contract_address,
+        opportunity.buy.dex,
+        &opportunity.buy.pool_address[..10],
+        opportunity.sell.dex,
+        &opportunity.sell.pool_address[..10],
+        opportunity.quote_in_normalized(),
+        target.quote_token,
+        min_profit
+    );
+    if env.testing {
+        tracing::info!("🧪 TESTING MODE - Flash arbitrage would be executed but not broadcast");
+        return Ok(None);
+    }
+
+    let fees = quote_fees(&provider, &config.gas, opportunity.net_profit_bps() - target.min_executable_spread_bps, target.max_priority_fee_gwei).await?;
+    let (_, receipt) = execute_arbitrage(provider, wallet_address, contract_address, &candidates, &fees, &config.gas).await?;
+    receipt_legs(&receipt, opportunity, target, config, reference_price).map(Some)
+}
+
+/// Buy and sell legs of a single-transaction round trip, read from the two pool Swap events of the receipt
+/// (gas is booked on the buy leg only)
+fn receipt_legs(receipt: &TransactionReceipt, opportunity: &CrossPoolOpportunity, target: &ArbTarget, config: &BotConfig, reference_price: f64) -> Result<(SwapExecution, SwapExecution)> {
+    let base_token_address = Address::from_str(&target.base_token_address)?;
+    let quote_token_address = Address::from_str(&target.quote_token_address)?;
+    let (base_decimals, quote_decimals, hype_price) = (opportunity.base_decimals, opportunity.quote_decimals, opportunity.hype_price);
+    let leg = |pool_leg: &BestOpportunity, is_buy: bool, token_in: Address, token_out: Address, planned_in: U256, gas_paid: u128| -> Result<SwapExecution> {
+        let pool = Address::from_str(&pool_leg.pool_address)?;
+        let amounts = decode_swap_amounts(receipt, pool, token_in, token_out);
+        let mut execution = SwapExecution {
+            dex: pool_leg.dex.clone(),
+            pool_address: pool_leg.pool_address.clone(),
+            is_buy,
+            token_in,
+            token_out,
+            amount_in: amounts.map(|a| a.amount_in).unwrap_or(planned_in),
+            amount_out_min: U256::ZERO,
+            amount_out: amounts.map(|a| a.amount_out),
+            tx_hash: receipt.transaction_hash,
+            gas_used: receipt.gas_used,
+            gas_paid_wei: gas_paid,
+            success: receipt.status(),
+            pnl: None,
+        };
+        let fee_ratio = pool_leg.pool_fee_tier as f64 / 1_000_000.0;
+        let expected_price = expected_fill_price(is_buy, pool_leg.pool_price, pool_leg.pool_fee_tier);
+        let (decimals_in, decimals_out) = if is_buy { (quote_decimals, base_decimals) } else { (base_decimals, quote_decimals) };
+        execution.pnl = TradePnl::compute(&execution, decimals_in, decimals_out, fee_ratio, expected_price, reference_price, hype_price);
+        Ok(execution)
+    };
+    let bought = leg(&opportunity.buy, true, quote_token_address, base_token_address, opportunity.quote_in, gas_paid_wei(receipt))?;
+    let sold = leg(&opportunity.sell, false, base_token_address, quote_token_address, opportunity.expected_base_out, 0)?;
+
+    if receipt.status() {
+        let profit = match (bought.amount_in, sold.amount_out) {
+            (spent, Some(received)) => (received.to::<u128>() as f64 - spent.to::<u128>() as f64) / 10f64.powi(quote_decimals as i32),
+            _ => 0.0,
+        };
+        tracing::info!(
+            "✅ Cross-pool executed: 0x{:x} | Profit: {:.6} {} | Gas used: {}",
+            receipt.transaction_hash,
+            profit,
+            target.quote_token,
+            receipt.gas_used
+        );
+    } else {
+        tracing::error!("❌ Cross-pool reverted: 0x{:x}", receipt.transaction_hash);
+    }
+    tracing::info!("   Explorer: {}tx/0x{:x}", config.global.explorer_base_url, receipt.transaction_hash);
+    Ok((bought, sold))
+}
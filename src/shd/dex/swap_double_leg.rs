@@ -2,10 +2,10 @@ use alloy::{
     primitives::{Address, U256},
     providers::Provider,
 };
-use eyre::Result;
 use std::str::FromStr;
 
 use crate::{
+    error::{Error, Result},
     types::{ArbTarget, BotConfig, EnvConfig, PriceReference},
 };
 
@@ -21,7 +21,7 @@ async fn fetch_price_by_reference(reference: &PriceReference, symbol: &str, conf
             "BTC" => crate::oracles::pyth::fetch_btc_usd_price().await,
             "ETH" => crate::oracles::pyth::fetch_eth_usd_price().await,
             "HYPE" | "WHYPE" => crate::oracles::fetch_hype_usd_price().await,
-            _ => Err(eyre::eyre!("Pyth oracle doesn't support {} price", symbol)),
+            _ => Err(Error::Oracle(format!("Pyth oracle doesn't support {} price", symbol))),
         },
         PriceReference::Redstone => match symbol.to_uppercase().as_str() {
             "BTC" => crate::oracles::fetch_btc_usd_price().await,
@@ -55,15 +55,15 @@ pub async fn prepare_double_leg_arbitrage<P: Provider + Clone>(
     let gas_price_gwei = gas_price_wei / 1_000_000_000;
     
     if gas_price_gwei > config.gas.max_gas_price_gwei as u128 {
-        return Err(eyre::eyre!("Gas too high: {} gwei > {} max", 
-            gas_price_gwei, config.gas.max_gas_price_gwei));
+        return Err(Error::Execution(format!("Gas too high: {} gwei > {} max", 
+            gas_price_gwei, config.gas.max_gas_price_gwei)));
     }
     
     // Step 2: Get HYPE price
     let hype_price = match fetch_price_by_reference(&target.reference, "HYPE", config).await {
         Ok(price) if price > 0.0 => price,
-        Ok(_) => return Err(eyre::eyre!("Invalid HYPE price")),
-        Err(e) => return Err(eyre::eyre!("Failed to fetch HYPE price: {}", e)),
+        Ok(_) => return Err(Error::Oracle("Invalid HYPE price".to_string())),
+        Err(e) => return Err(Error::Oracle(format!("Failed to fetch HYPE price: {}", e))),
     };
     
     // Step 3: Calculate gas cost for both legs
@@ -74,7 +74,7 @@ pub async fn prepare_double_leg_arbitrage<P: Provider + Clone>(
     // Step 4: Get wallet and balances
     let wallet = match env.get_signer_for_address(&target.address) {
         Some(signer) => signer,
-        None => return Err(eyre::eyre!("No wallet found for target address: {}", target.address)),
+        None => return Err(Error::Config(format!("No wallet found for target address: {}", target.address))),
     };
     let wallet_address = wallet.address();
     
@@ -119,8 +119,11 @@ pub async fn prepare_double_leg_arbitrage<P: Provider + Clone>(
     // Step 6: Check minimum trade value
     let trade_value_usd = base_normalized * reference_price;
     if trade_value_usd < target.min_trade_value_usd {
-        return Err(eyre::eyre!("Trade value ${:.2} below minimum ${:.2}", 
-            trade_value_usd, target.min_trade_value_usd));
+        return Err(Error::InsufficientFunds {
+            token: target.quote_token.clone(),
+            available: trade_value_usd,
+            required: target.min_trade_value_usd,
+        });
     }
     
     // Step 7: Calculate expected profit
@@ -129,7 +132,7 @@ pub async fn prepare_double_leg_arbitrage<P: Provider + Clone>(
     let expected_profit_usd = sell_revenue - buy_cost - gas_cost_usd;
     
     if expected_profit_usd <= 0.0 {
-        return Err(eyre::eyre!("No profit after gas costs: ${:.2}", expected_profit_usd));
+        return Err(Error::Execution(format!("No profit after gas costs: ${:.2}", expected_profit_usd)));
     }
     
     // Step 8: Calculate slippage-adjusted outputs
@@ -147,11 +150,11 @@ pub async fn prepare_double_leg_arbitrage<P: Provider + Clone>(
     let buy_router = match buy_opportunity.dex.to_lowercase().as_str() {
         "hyperswap" => Address::from_str(&config.dex.iter()
             .find(|d| d.name.to_lowercase() == "hyperswap")
-            .ok_or_else(|| eyre::eyre!("Hyperswap router not found"))?.router)?,
+            .ok_or_else(|| Error::Config("Hyperswap router not found".to_string()))?.router)?,
         "projectx" => Address::from_str(&config.dex.iter()
             .find(|d| d.name.to_lowercase() == "projectx")
-            .ok_or_else(|| eyre::eyre!("ProjectX router not found"))?.router)?,
-        _ => return Err(eyre::eyre!("Unknown DEX: {}", buy_opportunity.dex)),
+            .ok_or_else(|| Error::Config("ProjectX router not found".to_string()))?.router)?,
+        _ => return Err(Error::Config(format!("Unknown DEX: {}", buy_opportunity.dex))),
     };
     
     // Step 10: Prepare pool swap params for buy leg
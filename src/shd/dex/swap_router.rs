@@ -1,14 +1,22 @@
 use alloy::{
-    network::EthereumWallet,
-    primitives::{Address, U256},
+    network::{EthereumWallet, TransactionBuilder},
+    primitives::{Address, U256, Uint},
     providers::{Provider, ProviderBuilder},
+    rpc::types::{TransactionReceipt, TransactionRequest},
     signers::local::PrivateKeySigner,
+    sol_types::SolValue,
 };
 use chrono::Utc;
-use eyre::Result;
 use serde::{Deserialize, Serialize};
 use std::str::FromStr;
 
+use crate::{
+    error::{Error, Result},
+    sim::Simulator,
+    tx::{FeeQuote, TX_TRACKER, TxPolicy},
+    types::GasConfig,
+};
+
 // ===== ROUTER INTERFACES =====
 
 // Uniswap V3 Router Interface
@@ -157,7 +165,8 @@ pub struct SwapPath {
 pub struct SwapResult {
     pub tx_hash: String,
     pub amount_in: U256,
-    pub amount_out: U256,
+    /// None when the receipt holds no transfer to the recipient
+    pub amount_out: Option<U256>,
     pub gas_used: u128,
     pub timestamp: i64,
 }
@@ -176,6 +185,8 @@ pub struct SwapRouter {
     pub router_address: Address,
     pub quoter_address: Address,
     pub wallet_address: Address,
+    /// Deadline and gas bumps of swaps and approvals (TxPolicy::from the gas config)
+    pub policy: TxPolicy,
 }
 
 impl SwapRouter {
@@ -191,6 +202,7 @@ impl SwapRouter {
             router_address,
             quoter_address,
             wallet_address,
+            policy: TxPolicy::default(),
         }
     }
 
@@ -209,12 +221,12 @@ impl SwapRouter {
             .quoteExactInputSingle(
                 token_in,
                 token_out,
-                fee,
+                Uint::<24, 1>::from(fee),
                 amount_in,
-                U256::ZERO, // No price limit
+                Uint::<160, 3>::ZERO, // No price limit
             )
             .call()
-            .await?._0;
+            .await?;
         
         // Calculate simple price impact (would need pool data for accurate calculation)
         let price_impact = 0.0; // Placeholder
@@ -226,7 +238,7 @@ impl SwapRouter {
     }
 
     /// Execute exact input single hop swap
-    pub async fn swap_exact_input_single<P: Provider + Clone>(
+    pub async fn swap_exact_input_single<P: Provider + Clone + 'static>(
         &self,
         provider: P,
         params: SwapParams,
@@ -243,32 +255,36 @@ impl SwapRouter {
         let swap_params = ISwapRouter::ExactInputSingleParams {
             tokenIn: params.token_in,
             tokenOut: params.token_out,
-            fee: params.fee,
+            fee: Uint::<24, 1>::from(params.fee),
             recipient: params.recipient,
             deadline,
             amountIn: params.amount_in,
             amountOutMinimum: params.amount_out_minimum,
-            sqrtPriceLimitX96: U256::ZERO, // No price limit
+            sqrtPriceLimitX96: Uint::<160, 3>::ZERO, // No price limit
         };
         
-        // Execute swap
-        let tx = router.exactInputSingle(swap_params).send().await?;
-        let tx_hash = format!("0x{}", hex::encode(tx.tx_hash()));
-        
-        // Wait for receipt
-        let receipt = tx.get_receipt().await?;
-        
+        // Execute swap, nonce and inclusion handled by the tracker shared with the other executors
+        let request = router.exactInputSingle(swap_params).into_transaction_request();
+        let receipt = TX_TRACKER.send(&provider, self.wallet_address, request, self.policy).await?.into_receipt()?;
+        let tx_hash = format!("0x{:x}", receipt.transaction_hash);
+        if !receipt.status() {
+            return Err(Error::Execution(format!("Router swap reverted: {}", tx_hash)));
+        }
+
+        // Actual amount received by the recipient, from the ERC20 transfer logs
+        let amount_out = crate::dex::pnl::received_amount(&receipt, params.token_out, params.recipient);
+
         Ok(SwapResult {
             tx_hash,
             amount_in: params.amount_in,
-            amount_out: params.amount_out_minimum, // Actual amount would be in logs
-            gas_used: receipt.gas_used,
+            amount_out,
+            gas_used: receipt.gas_used as u128,
             timestamp: Utc::now().timestamp(),
         })
     }
 
     /// Ensure token approval for router
-    async fn ensure_approval<P: Provider + Clone>(
+    async fn ensure_approval<P: Provider + Clone + 'static>(
         &self,
         provider: P,
         token: Address,
@@ -280,17 +296,16 @@ impl SwapRouter {
         let allowance = token_contract
             .allowance(self.wallet_address, self.router_address)
             .call()
-            .await?._0;
+            .await?;
         
         // Approve if needed
         if allowance < amount {
             tracing::info!("Approving {} for router", token);
-            let approve_tx = token_contract
-                .approve(self.router_address, U256::MAX)
-                .send()
-                .await?;
-            
-            let _receipt = approve_tx.get_receipt().await?;
+            let request = token_contract.approve(self.router_address, U256::MAX).into_transaction_request();
+            let receipt = TX_TRACKER.send(&provider, self.wallet_address, request, self.policy).await?.into_receipt()?;
+            if !receipt.status() {
+                return Err(Error::Execution(format!("Approval of {} reverted: 0x{:x}", token, receipt.transaction_hash)));
+            }
             tracing::info!("Approval successful");
         }
         
@@ -302,9 +317,9 @@ impl SwapRouter {
 
 /// Calculate minimum output with slippage
 pub fn calculate_minimum_out(expected_out: U256, slippage_percent: f64) -> U256 {
-    let slippage_factor = 1.0 - (slippage_percent / 100.0);
-    let min_out = expected_out.to::<f64>() * slippage_factor;
-    U256::from(min_out as u128)
+    // Kept share in millionths to stay in integer math
+    let kept = ((1.0 - slippage_percent / 100.0).clamp(0.0, 1.0) * 1_000_000.0) as u64;
+    expected_out * U256::from(kept) / U256::from(1_000_000)
 }
 
 /// Encode swap path for multi-hop swaps
@@ -313,7 +328,7 @@ pub fn encode_path(path: &SwapPath) -> Vec<u8> {
     
     for i in 0..path.tokens.len() {
         // Add token address (20 bytes)
-        encoded.extend_from_slice(&path.tokens[i].to_vec());
+        encoded.extend_from_slice(path.tokens[i].as_slice());
         
         // Add fee if not last token (3 bytes)
         if i < path.fees.len() {
@@ -330,7 +345,7 @@ pub async fn build_provider_with_wallet(
     rpc_url: &str,
     private_key: &str,
 ) -> Result<impl Provider + Clone> {
-    let signer = PrivateKeySigner::from_str(private_key)?;
+    let signer = PrivateKeySigner::from_str(private_key).map_err(|e| Error::Config(format!("Invalid private key: {}", e)))?;
     let wallet = EthereumWallet::from(signer);
     
     let provider = ProviderBuilder::new()
@@ -341,55 +356,167 @@ pub async fn build_provider_with_wallet(
     Ok(provider)
 }
 
-/// Flash swap parameters for arbitrage
+// ===== FLASH SWAP ARBITRAGE =====
+
+// HyperArb flash-swap entry point and the data its swap callback decodes
+alloy::sol! {
+    #[allow(missing_docs)]
+    #[sol(rpc)]
+    contract IFlashArb {
+        struct FlashArbData {
+            address buyPool;
+            address sellPool;
+            address quoteToken;
+            address baseToken;
+            uint256 minProfit;
+        }
+
+        function flashArb(bool zeroForOne, uint256 quoteIn, bytes calldata data) external returns (uint256 profit);
+    }
+}
+
+/// Flash swap on the buy pool: the pool sends the base out first and is repaid in quote from the callback
 #[derive(Debug, Clone)]
 pub struct FlashSwapParams {
     pub pool_address: Address,
     pub token0: Address,
     pub token1: Address,
+    /// Quote owed to the pool, on the quote token's side (the other amount is zero)
     pub amount0: U256,
     pub amount1: U256,
+    /// ABI-encoded FlashArbData handed to the callback by the pool
     pub callback_data: Vec<u8>,
 }
 
-/// Arbitrage execution parameters
+impl FlashSwapParams {
+    pub fn new(params: &ArbitrageParams) -> Self {
+        let quote_is_token0 = params.token_in < params.token_out;
+        let (token0, token1) = if quote_is_token0 { (params.token_in, params.token_out) } else { (params.token_out, params.token_in) };
+        let (amount0, amount1) = if quote_is_token0 { (params.amount, U256::ZERO) } else { (U256::ZERO, params.amount) };
+        Self {
+            pool_address: params.buy_pool,
+            token0,
+            token1,
+            amount0,
+            amount1,
+            callback_data: encode_flash_callback(params),
+        }
+    }
+
+    /// Quote -> base swaps token0 for token1 when the quote is token0
+    pub fn zero_for_one(&self) -> bool {
+        !self.amount0.is_zero()
+    }
+
+    pub fn amount_in(&self) -> U256 {
+        self.amount0.max(self.amount1)
+    }
+}
+
+/// Arbitrage execution parameters (raw token units)
 #[derive(Debug, Clone)]
 pub struct ArbitrageParams {
     pub buy_pool: Address,
     pub sell_pool: Address,
-    pub token_in: Address,
-    pub token_out: Address,
-    pub amount: U256,
-    pub expected_profit: U256,
+    pub token_in: Address,      // Quote token, owed to the buy pool and returned by the sell pool
+    pub token_out: Address,     // Base token, lent by the buy pool
+    pub amount: U256,           // Quote input of the buy swap
+    pub expected_profit: U256,  // Round-trip profit sized off-chain
+    pub min_profit: U256,       // The contract reverts below this
+}
+
+/// Callback data for HyperArb.flashArb
+pub fn encode_flash_callback(params: &ArbitrageParams) -> Vec<u8> {
+    IFlashArb::FlashArbData {
+        buyPool: params.buy_pool,
+        sellPool: params.sell_pool,
+        quoteToken: params.token_in,
+        baseToken: params.token_out,
+        minProfit: params.min_profit,
+    }
+    .abi_encode()
 }
 
-/// Execute arbitrage between two pools
-pub async fn execute_arbitrage<P: Provider + Clone>(
+/// Flash-swap arbitrage through HyperArb.flashArb, without inventory: the buy pool lends the base and is
+/// repaid from the sell pool's output. Candidates (e.g. sizes of the same round trip) expected below their
+/// minimum profit are dropped, the others are simulated in parallel on the latest block and the one with
+/// the highest simulated profit is sent, with its simulated gas. Returns the result (amount_out includes
+/// the profit received by the wallet) with the receipt
+pub async fn execute_arbitrage<P: Provider + Clone + 'static>(
     provider: P,
-    router_address: Address,
-    params: ArbitrageParams,
-) -> Result<SwapResult> {
-    // This is a simplified version - actual implementation would need:
-    // 1. Flash loan or initial capital
-    // 2. Buy from cheaper pool
-    // 3. Sell to expensive pool
-    // 4. Return flash loan if used
-    
+    wallet_address: Address,
+    contract_address: Address,
+    candidates: &[ArbitrageParams],
+    fees: &FeeQuote,
+    gas: &GasConfig,
+) -> Result<(SwapResult, TransactionReceipt)> {
+    let candidates: Vec<&ArbitrageParams> = candidates.iter().filter(|params| params.expected_profit >= params.min_profit).collect();
+    if candidates.is_empty() {
+        return Err(Error::Execution("No flash arbitrage candidate expected above its minimum profit".to_string()));
+    }
+
+    let contract = IFlashArb::new(contract_address, provider.clone());
+    let requests: Vec<TransactionRequest> = candidates
+        .iter()
+        .map(|params| {
+            let flash = FlashSwapParams::new(params);
+            contract
+                .flashArb(flash.zero_for_one(), flash.amount_in(), flash.callback_data.into())
+                .from(wallet_address)
+                .max_fee_per_gas(fees.max_fee_per_gas)
+                .max_priority_fee_per_gas(fees.max_priority_fee_per_gas)
+                .into_transaction_request()
+        })
+        .collect();
+
+    let simulator = Simulator::latest(provider.clone()).await?;
+    let simulations = simulator.simulate_many(requests.clone()).await;
+
+    // (candidate, simulated profit, gas used) of the most profitable candidate above its minimum
+    let mut best: Option<(usize, U256, u64)> = None;
+    for (index, (params, simulation)) in candidates.iter().zip(simulations).enumerate() {
+        match simulation.and_then(|simulation| Ok((simulation.decode_return::<IFlashArb::flashArbCall>()?, simulation.gas_used))) {
+            Ok((profit, gas_used)) if profit >= params.min_profit => {
+                tracing::debug!("Flash arbitrage of {}: simulated profit {} (expected {}), {} gas", params.amount, profit, params.expected_profit, gas_used);
+                if best.is_none_or(|(_, best_profit, _)| profit > best_profit) {
+                    best = Some((index, profit, gas_used));
+                }
+            }
+            Ok((profit, _)) => tracing::debug!("Flash arbitrage of {}: simulated profit {} below minimum {}", params.amount, profit, params.min_profit),
+            Err(e) => tracing::debug!("Flash arbitrage of {}: {}", params.amount, e),
+        }
+    }
+    let Some((index, simulated_profit, gas_used)) = best else {
+        return Err(Error::Execution(format!("No flash arbitrage candidate profitable in simulation at block {}", simulator.block_number())));
+    };
+    let params = candidates[index];
+
     tracing::info!(
-        "Executing arbitrage: {} -> {} with amount {}",
+        "Executing flash arbitrage: {} -> {} with amount {} | Expected profit: {} | Simulated: {} ({} of {} candidates)",
         params.buy_pool,
         params.sell_pool,
-        params.amount
+        params.amount,
+        params.expected_profit,
+        simulated_profit,
+        index + 1,
+        candidates.len()
     );
-    
-    // Placeholder for actual arbitrage logic
-    Ok(SwapResult {
-        tx_hash: "0x0".to_string(),
+
+    let request = requests[index].clone().with_gas_limit((gas_used as f64 * gas.gas_estimate_multiplier) as u64);
+    // Already simulated above
+    let policy = TxPolicy { simulate: false, ..TxPolicy::from(gas).capped(fees) };
+    let receipt = TX_TRACKER.send(&provider, wallet_address, request, policy).await?.into_receipt()?;
+
+    // The contract only transfers the profit to the caller
+    let profit = crate::dex::pnl::received_amount(&receipt, params.token_in, wallet_address);
+    let result = SwapResult {
+        tx_hash: format!("0x{:x}", receipt.transaction_hash),
         amount_in: params.amount,
-        amount_out: params.amount + params.expected_profit,
-        gas_used: 0,
+        amount_out: profit.map(|profit| params.amount + profit),
+        gas_used: receipt.gas_used as u128,
         timestamp: Utc::now().timestamp(),
-    })
+    };
+    Ok((result, receipt))
 }
 
 /// Get optimal swap route using pathfinding
@@ -415,18 +542,18 @@ pub async fn find_optimal_route<P: Provider + Clone>(
             .quoteExactInputSingle(
                 token_in,
                 token_out,
-                fee,
+                Uint::<24, 1>::from(fee),
                 amount_in,
-                U256::ZERO,
+                Uint::<160, 3>::ZERO,
             )
             .call()
             .await
         {
             Ok(result) => {
-                if result._0 > best_output {
-                    best_output = result._0;
+                if result > best_output {
+                    best_output = result;
                     best_path.fees = vec![fee];
-                    tracing::info!("Found better route through pool {} with output {}", pool, result._0);
+                    tracing::info!("Found better route through pool {} with output {}", pool, result);
                 }
             }
             Err(e) => {
@@ -451,15 +578,15 @@ pub async fn estimate_swap_gas<P: Provider + Clone>(
     let swap_params = ISwapRouter::ExactInputSingleParams {
         tokenIn: params.token_in,
         tokenOut: params.token_out,
-        fee: params.fee,
+        fee: Uint::<24, 1>::from(params.fee),
         recipient: params.recipient,
         deadline,
         amountIn: params.amount_in,
         amountOutMinimum: params.amount_out_minimum,
-        sqrtPriceLimitX96: U256::ZERO,
+        sqrtPriceLimitX96: Uint::<160, 3>::ZERO,
     };
     
     let gas_estimate = router.exactInputSingle(swap_params).estimate_gas().await?;
     
-    Ok(gas_estimate)
+    Ok(gas_estimate as u128)
 }
\ No newline at end of file
@@ -0,0 +1,70 @@
+// Typed errors returned by the library
+
+/// Library error, by failing subsystem
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    /// Missing or invalid configuration (env, TOML, addresses)
+    #[error("Config error: {0}")]
+    Config(String),
+    /// Node or HyperCore API call failed or returned unexpected data
+    #[error("RPC error: {0}")]
+    Rpc(String),
+    /// Reference price unavailable
+    #[error("Oracle error: {0}")]
+    Oracle(String),
+    /// Transaction could not be built, sent or confirmed
+    #[error("Execution error: {0}")]
+    Execution(String),
+    /// Local file could not be read or written (recordings, reports)
+    #[error("IO error: {0}")]
+    Io(String),
+    /// Database unreachable or query failed
+    #[error("Database error: {0}")]
+    Database(String),
+    #[error("Insufficient funds: {token} balance {available} < required {required}")]
+    InsufficientFunds { token: String, available: f64, required: f64 },
+}
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+impl From<alloy::transports::TransportError> for Error {
+    fn from(e: alloy::transports::TransportError) -> Self {
+        Error::Rpc(e.to_string())
+    }
+}
+
+impl From<alloy::contract::Error> for Error {
+    fn from(e: alloy::contract::Error) -> Self {
+        Error::Rpc(e.to_string())
+    }
+}
+
+impl From<std::io::Error> for Error {
+    fn from(e: std::io::Error) -> Self {
+        Error::Io(e.to_string())
+    }
+}
+
+impl From<alloy::primitives::hex::FromHexError> for Error {
+    fn from(e: alloy::primitives::hex::FromHexError) -> Self {
+        Error::Config(format!("Invalid address: {}", e))
+    }
+}
+
+impl From<alloy::providers::PendingTransactionError> for Error {
+    fn from(e: alloy::providers::PendingTransactionError) -> Self {
+        Error::Execution(e.to_string())
+    }
+}
+
+impl From<reqwest::Error> for Error {
+    fn from(e: reqwest::Error) -> Self {
+        Error::Rpc(e.to_string())
+    }
+}
+
+impl From<sea_orm::DbErr> for Error {
+    fn from(e: sea_orm::DbErr) -> Self {
+        Error::Database(e.to_string())
+    }
+}
@@ -0,0 +1,452 @@
+// Event bus publishing opportunity and trade events to external sinks
+
+use async_trait::async_trait;
+use serde::Serialize;
+use std::{
+    collections::{HashMap, VecDeque},
+    time::{Duration, Instant},
+};
+use tokio::sync::mpsc::{self, error::TrySendError};
+
+use crate::{
+    error::{Error, Result},
+    types::{ArbTarget, EventSinkConfig, EventSinkKind, EventsConfig},
+};
+
+/// Event channel capacity, events beyond it are dropped
+pub const EVENT_CHANNEL_CAPACITY: usize = 256;
+
+// ===== EVENTS =====
+
+/// Typed events published for targets with publish_events = true
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type")]
+pub enum ArbEvent {
+    OpportunityDetected {
+        vault: String,
+        dex: String,
+        pool: String,
+        pool_price: f64,
+        reference_price: f64,
+        spread_bps: f64,
+        net_bps: f64,
+    },
+    TradeSubmitted {
+        vault: String,
+        dex: String,
+        pool: String,
+        is_buy: bool,
+        spread_bps: f64,
+    },
+    TradeConfirmed {
+        vault: String,
+        dex: String,
+        pool: String,
+        is_buy: bool,
+        tx_hash: String,
+        net_pnl_usd: Option<f64>,
+    },
+    TradeFailed {
+        vault: String,
+        dex: String,
+        pool: String,
+        tx_hash: Option<String>,
+        error: String,
+    },
+    InventoryImbalance {
+        vault: String,
+        base_token: String,
+        base_percentage: f64,
+        quote_token: String,
+        quote_percentage: f64,
+        total_value_usd: f64,
+    },
+    OracleDivergence {
+        vault: String,
+        token: String,
+        reference: String,
+        reference_price: f64,
+        hypercore_price: f64,
+        divergence_bps: f64,
+    },
+}
+
+impl ArbEvent {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            ArbEvent::OpportunityDetected { .. } => "OpportunityDetected",
+            ArbEvent::TradeSubmitted { .. } => "TradeSubmitted",
+            ArbEvent::TradeConfirmed { .. } => "TradeConfirmed",
+            ArbEvent::TradeFailed { .. } => "TradeFailed",
+            ArbEvent::InventoryImbalance { .. } => "InventoryImbalance",
+            ArbEvent::OracleDivergence { .. } => "OracleDivergence",
+        }
+    }
+
+    /// Key identifying repeated events (same kind, vault and subject)
+    pub fn dedup_key(&self) -> String {
+        match self {
+            ArbEvent::OpportunityDetected { vault, pool, .. } | ArbEvent::TradeSubmitted { vault, pool, .. } => format!("{}:{}:{}", self.kind(), vault, pool),
+            ArbEvent::TradeConfirmed { tx_hash, .. } => format!("{}:{}", self.kind(), tx_hash),
+            ArbEvent::TradeFailed { vault, pool, error, .. } => format!("{}:{}:{}:{}", self.kind(), vault, pool, error),
+            ArbEvent::InventoryImbalance { vault, .. } => format!("{}:{}", self.kind(), vault),
+            ArbEvent::OracleDivergence { vault, token, .. } => format!("{}:{}:{}", self.kind(), vault, token),
+        }
+    }
+
+    /// One line human readable message for chat sinks
+    pub fn message(&self) -> String {
+        match self {
+            ArbEvent::OpportunityDetected {
+                vault,
+                dex,
+                pool,
+                pool_price,
+                reference_price,
+                spread_bps,
+                net_bps,
+            } => {
+                format!(
+                    "🎯 [{}] Opportunity on {} {} | Pool ${:.4} vs ref ${:.4} | Spread {:.2} bps | Net {:.2} bps",
+                    vault, dex, pool, pool_price, reference_price, spread_bps, net_bps
+                )
+            }
+            ArbEvent::TradeSubmitted { vault, dex, pool, is_buy, spread_bps } => {
+                format!("📤 [{}] {} submitted on {} {} | Spread {:.2} bps", vault, if *is_buy { "BUY" } else { "SELL" }, dex, pool, spread_bps)
+            }
+            ArbEvent::TradeConfirmed {
+                vault,
+                dex,
+                is_buy,
+                tx_hash,
+                net_pnl_usd,
+                ..
+            } => match net_pnl_usd {
+                Some(pnl) => format!("✅ [{}] {} confirmed on {} | {} | Net PnL ${:.4}", vault, if *is_buy { "BUY" } else { "SELL" }, dex, tx_hash, pnl),
+                None => format!("✅ [{}] {} confirmed on {} | {}", vault, if *is_buy { "BUY" } else { "SELL" }, dex, tx_hash),
+            },
+            ArbEvent::TradeFailed { vault, dex, pool, tx_hash, error } => {
+                format!("❌ [{}] Trade failed on {} {} | {} | {}", vault, dex, pool, tx_hash.as_deref().unwrap_or("not sent"), error)
+            }
+            ArbEvent::InventoryImbalance {
+                vault,
+                base_token,
+                base_percentage,
+                quote_token,
+                quote_percentage,
+                total_value_usd,
+            } => {
+                format!(
+                    "⚠️ [{}] Inventory imbalance | {} {:.1}% / {} {:.1}% | Total ${:.2}",
+                    vault, base_token, base_percentage, quote_token, quote_percentage, total_value_usd
+                )
+            }
+            ArbEvent::OracleDivergence {
+                vault,
+                token,
+                reference,
+                reference_price,
+                hypercore_price,
+                divergence_bps,
+            } => {
+                format!(
+                    "🔀 [{}] {} {} ${:.4} vs HyperCore ${:.4} | Divergence {:.2} bps",
+                    vault, token, reference, reference_price, hypercore_price, divergence_bps
+                )
+            }
+        }
+    }
+}
+
+/// Event with publishing metadata, as serialized to JSON sinks
+#[derive(Debug, Clone, Serialize)]
+pub struct EventEnvelope {
+    pub system: &'static str,
+    pub timestamp: String,
+    #[serde(flatten)]
+    pub event: ArbEvent,
+}
+
+impl EventEnvelope {
+    pub fn new(event: ArbEvent) -> Self {
+        Self {
+            system: "HyperArb",
+            timestamp: chrono::Utc::now().to_rfc3339(),
+            event,
+        }
+    }
+}
+
+// ===== SINKS =====
+
+#[async_trait]
+pub trait EventSink: Send + Sync {
+    fn name(&self) -> &'static str;
+    async fn publish(&self, envelope: &EventEnvelope) -> Result<()>;
+}
+
+/// Discord webhook
+pub struct DiscordSink {
+    client: webhook::client::WebhookClient,
+}
+
+#[async_trait]
+impl EventSink for DiscordSink {
+    fn name(&self) -> &'static str {
+        "discord"
+    }
+
+    async fn publish(&self, envelope: &EventEnvelope) -> Result<()> {
+        let content = envelope.event.message();
+        self.client
+            .send(|m| m.username(envelope.system).content(&content))
+            .await
+            .map_err(|e| Error::Rpc(format!("Discord webhook failed: {}", e)))?;
+        Ok(())
+    }
+}
+
+/// Slack incoming webhook
+pub struct SlackSink {
+    url: String,
+    client: reqwest::Client,
+}
+
+#[async_trait]
+impl EventSink for SlackSink {
+    fn name(&self) -> &'static str {
+        "slack"
+    }
+
+    async fn publish(&self, envelope: &EventEnvelope) -> Result<()> {
+        let response = self.client.post(&self.url).json(&serde_json::json!({ "text": envelope.event.message() })).send().await?;
+        if !response.status().is_success() {
+            return Err(Error::Rpc(format!("Slack webhook failed with status: {}", response.status())));
+        }
+        Ok(())
+    }
+}
+
+/// Generic HTTP POST of the JSON envelope
+pub struct HttpSink {
+    url: String,
+    client: reqwest::Client,
+}
+
+#[async_trait]
+impl EventSink for HttpSink {
+    fn name(&self) -> &'static str {
+        "http"
+    }
+
+    async fn publish(&self, envelope: &EventEnvelope) -> Result<()> {
+        let response = self.client.post(&self.url).json(envelope).send().await?;
+        if !response.status().is_success() {
+            return Err(Error::Rpc(format!("HTTP sink failed with status: {}", response.status())));
+        }
+        Ok(())
+    }
+}
+
+/// JSON lines on stdout
+pub struct StdoutSink;
+
+#[async_trait]
+impl EventSink for StdoutSink {
+    fn name(&self) -> &'static str {
+        "stdout"
+    }
+
+    async fn publish(&self, envelope: &EventEnvelope) -> Result<()> {
+        println!("{}", serde_json::to_string(envelope).map_err(|e| Error::Io(format!("Failed to serialize event: {}", e)))?);
+        Ok(())
+    }
+}
+
+pub fn build_sink(config: &EventSinkConfig) -> Box<dyn EventSink> {
+    match config.kind {
+        EventSinkKind::Discord => Box::new(DiscordSink {
+            client: webhook::client::WebhookClient::new(&config.url),
+        }),
+        EventSinkKind::Slack => Box::new(SlackSink {
+            url: config.url.clone(),
+            client: reqwest::Client::new(),
+        }),
+        EventSinkKind::Http => Box::new(HttpSink {
+            url: config.url.clone(),
+            client: reqwest::Client::new(),
+        }),
+        EventSinkKind::Stdout => Box::new(StdoutSink),
+    }
+}
+
+// ===== RATE LIMITING & DEDUP =====
+
+/// Sliding one minute window rate limiter with per-key dedup
+struct EventFilter {
+    max_per_minute: usize,
+    dedup_window: Duration,
+    sent: VecDeque<Instant>,
+    last_seen: HashMap<String, Instant>,
+}
+
+impl EventFilter {
+    fn new(config: &EventsConfig) -> Self {
+        Self {
+            max_per_minute: config.max_events_per_minute as usize,
+            dedup_window: Duration::from_secs(config.dedup_window_secs),
+            sent: VecDeque::new(),
+            last_seen: HashMap::new(),
+        }
+    }
+
+    /// Whether the event should be published now
+    fn allow(&mut self, event: &ArbEvent) -> bool {
+        self.allow_at(event, Instant::now())
+    }
+
+    fn allow_at(&mut self, event: &ArbEvent, now: Instant) -> bool {
+        // Dedup: drop identical events within the window
+        self.last_seen.retain(|_, seen| now.duration_since(*seen) < self.dedup_window);
+        let key = event.dedup_key();
+        if self.last_seen.contains_key(&key) {
+            return false;
+        }
+
+        // Rate limit: at most N events in the last minute
+        while self.sent.front().is_some_and(|t| now.duration_since(*t) >= Duration::from_secs(60)) {
+            self.sent.pop_front();
+        }
+        if self.sent.len() >= self.max_per_minute {
+            return false;
+        }
+
+        self.sent.push_back(now);
+        self.last_seen.insert(key, now);
+        true
+    }
+}
+
+// ===== BUS =====
+
+/// Handle to the background event publisher (cheap to clone)
+#[derive(Debug, Clone)]
+pub struct EventBus {
+    sender: Option<mpsc::Sender<ArbEvent>>,
+}
+
+impl EventBus {
+    /// Bus that discards every event (no [events] section or no sinks)
+    pub fn disabled() -> Self {
+        Self { sender: None }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.sender.is_some()
+    }
+
+    /// Queue an event for a target, ignored unless the target has publish_events = true
+    pub fn publish(&self, target: &ArbTarget, event: ArbEvent) {
+        if !target.publish_events {
+            return;
+        }
+        let Some(sender) = &self.sender else {
+            return;
+        };
+        match sender.try_send(event) {
+            Ok(()) => {}
+            Err(TrySendError::Full(_)) => tracing::warn!("📣 Event channel full, dropping event"),
+            Err(TrySendError::Closed(_)) => tracing::error!("📣 Event publisher stopped, dropping event"),
+        }
+    }
+}
+
+/// Build the configured sinks and spawn the publisher task
+pub fn spawn_event_bus(config: &EventsConfig, capacity: usize) -> EventBus {
+    if config.sinks.is_empty() {
+        return EventBus::disabled();
+    }
+    let sinks: Vec<Box<dyn EventSink>> = config.sinks.iter().map(build_sink).collect();
+    let (sender, receiver) = mpsc::channel(capacity);
+    tokio::spawn(publisher_task(sinks, EventFilter::new(config), receiver));
+    EventBus { sender: Some(sender) }
+}
+
+async fn publisher_task(sinks: Vec<Box<dyn EventSink>>, mut filter: EventFilter, mut receiver: mpsc::Receiver<ArbEvent>) {
+    while let Some(event) = receiver.recv().await {
+        if !filter.allow(&event) {
+            tracing::debug!("📣 Event {} filtered (dedup or rate limit)", event.kind());
+            continue;
+        }
+        let envelope = EventEnvelope::new(event);
+        for sink in &sinks {
+            if let Err(e) = sink.publish(&envelope).await {
+                tracing::warn!("📣 Failed to publish {} to {}: {}", envelope.event.kind(), sink.name(), e);
+            }
+        }
+    }
+    tracing::info!("📣 Event channel closed, publisher task exiting");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn filter(max_per_minute: u32, dedup_window_secs: u64) -> EventFilter {
+        EventFilter::new(&EventsConfig {
+            sinks: vec![],
+            max_events_per_minute: max_per_minute,
+            dedup_window_secs,
+            oracle_divergence_bps: 0.0,
+        })
+    }
+
+    fn opportunity(pool: &str, spread_bps: f64) -> ArbEvent {
+        ArbEvent::OpportunityDetected {
+            vault: "test".to_string(),
+            dex: "Hyperswap".to_string(),
+            pool: pool.to_string(),
+            pool_price: 40.0,
+            reference_price: 40.0,
+            spread_bps,
+            net_bps: spread_bps,
+        }
+    }
+
+    #[test]
+    fn dedup_key_ignores_prices() {
+        assert_eq!(opportunity("0xpool", 10.0).dedup_key(), opportunity("0xpool", 25.0).dedup_key());
+        assert_ne!(opportunity("0xpool", 10.0).dedup_key(), opportunity("0xother", 10.0).dedup_key());
+        let confirmed = |tx_hash: &str| ArbEvent::TradeConfirmed {
+            vault: "test".to_string(),
+            dex: "Hyperswap".to_string(),
+            pool: "0xpool".to_string(),
+            is_buy: true,
+            tx_hash: tx_hash.to_string(),
+            net_pnl_usd: None,
+        };
+        assert_ne!(confirmed("0x01").dedup_key(), confirmed("0x02").dedup_key());
+    }
+
+    #[test]
+    fn repeated_events_dropped_within_the_dedup_window() {
+        let mut filter = filter(100, 30);
+        let start = Instant::now();
+        assert!(filter.allow_at(&opportunity("0xpool", 10.0), start));
+        assert!(!filter.allow_at(&opportunity("0xpool", 12.0), start + Duration::from_secs(29)));
+        assert!(filter.allow_at(&opportunity("0xother", 10.0), start + Duration::from_secs(29)));
+        assert!(filter.allow_at(&opportunity("0xpool", 12.0), start + Duration::from_secs(30)));
+    }
+
+    #[test]
+    fn events_above_the_rate_dropped_for_a_minute() {
+        let mut filter = filter(2, 1);
+        let start = Instant::now();
+        assert!(filter.allow_at(&opportunity("0x01", 10.0), start));
+        assert!(filter.allow_at(&opportunity("0x02", 10.0), start + Duration::from_secs(1)));
+        assert!(!filter.allow_at(&opportunity("0x03", 10.0), start + Duration::from_secs(59)));
+        // The first event left the window
+        assert!(filter.allow_at(&opportunity("0x03", 10.0), start + Duration::from_secs(60)));
+        assert!(!filter.allow_at(&opportunity("0x04", 10.0), start + Duration::from_secs(60)));
+    }
+}
@@ -1,11 +1,39 @@
+/// Offline replay of spread history with simulated fills
+pub mod backtest;
+/// Read caches shared across target tasks
+pub mod cache;
+/// Runtime control state and status/control HTTP API
+pub mod control;
 /// Core functionality and precompiles
 pub mod core;
 /// Database operations and entity management
 pub mod data;
+/// Per-pool spread detection shared by the live loop and backtests
+pub mod detect;
+/// Typed library errors
+pub mod error;
+/// Event bus and sinks (webhooks, HTTP, stdout)
+pub mod events;
 /// DEX utilities and pool data
 pub mod dex;
+/// Prometheus metrics and /metrics server
+pub mod metrics;
+/// Mark-to-market portfolio and PnL reporting
+pub mod portfolio;
+/// Config hot-reload (file watcher and SIGHUP)
+pub mod reload;
+/// Historical spread recorder (rolling CSV/Parquet files)
+pub mod recorder;
+/// Multi-endpoint RPC failover and broadcast
+pub mod rpc;
+/// Local revm simulation against lazily fetched chain state
+pub mod sim;
 /// Solidity ABIs
 pub mod sol;
+/// Rolling spread statistics and mean-reversion signals
+pub mod stats;
+/// Nonce management and transaction tracking
+pub mod tx;
 /// Type definitions and configuration structures
 pub mod types;
 /// Utilities and helper functions
@@ -0,0 +1,130 @@
+// Prometheus metrics and the embedded /metrics server
+
+use axum::{Router, http::header, response::IntoResponse, routing::get};
+use prometheus::{CounterVec, Encoder, GaugeVec, HistogramOpts, HistogramVec, IntCounterVec, IntGaugeVec, Opts, Registry, TextEncoder};
+use std::{net::SocketAddr, sync::LazyLock, time::Duration};
+
+use crate::error::{Error, Result};
+
+/// Process wide metrics, registered on first use
+pub static METRICS: LazyLock<Metrics> = LazyLock::new(Metrics::new);
+
+/// Latency buckets in seconds (oracles and RPC calls)
+const LATENCY_BUCKETS: &[f64] = &[0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0];
+
+pub struct Metrics {
+    registry: Registry,
+    /// Spread vs reference per pool (bps)
+    pub spread_bps: GaugeVec,
+    /// Best net-of-fee spread across pools of a target (bps)
+    pub best_net_bps: GaugeVec,
+    /// Executable threshold in use before inventory skew (bps), adaptive when enabled
+    pub exec_threshold_bps: GaugeVec,
+    pub oracle_latency_seconds: HistogramVec,
+    pub oracle_errors: IntCounterVec,
+    pub rpc_calls: IntCounterVec,
+    pub rpc_latency_seconds: HistogramVec,
+    /// 1 while an RPC endpoint is in rotation, 0 while its circuit is open
+    pub rpc_endpoint_up: IntGaugeVec,
+    /// Blocks between the chain head and the last block a target processed (0 = up to date)
+    pub block_lag: IntGaugeVec,
+    /// Trades by status: attempted, succeeded, failed
+    pub trades: IntCounterVec,
+    pub gas_spent_hype: CounterVec,
+    /// Base token share of the vault value (0..1)
+    pub inventory_ratio: GaugeVec,
+}
+
+impl Metrics {
+    fn new() -> Self {
+        let registry = Registry::new_custom(Some("hyperarb".to_string()), None).expect("valid metrics registry");
+
+        let spread_bps = GaugeVec::new(Opts::new("spread_bps", "Pool spread vs reference price in bps"), &["target", "dex", "pool"]).expect("valid metric");
+        let best_net_bps = GaugeVec::new(Opts::new("best_net_bps", "Best net of pool fee spread across pools in bps"), &["target"]).expect("valid metric");
+        let exec_threshold_bps = GaugeVec::new(Opts::new("exec_threshold_bps", "Executable spread threshold before inventory skew in bps"), &["target"]).expect("valid metric");
+        let oracle_latency_seconds = HistogramVec::new(
+            HistogramOpts::new("oracle_latency_seconds", "Reference price fetch latency").buckets(LATENCY_BUCKETS.to_vec()),
+            &["reference"],
+        )
+        .expect("valid metric");
+        let oracle_errors = IntCounterVec::new(Opts::new("oracle_errors_total", "Reference price fetch errors"), &["reference"]).expect("valid metric");
+        let rpc_calls = IntCounterVec::new(Opts::new("rpc_calls_total", "RPC calls by method and outcome"), &["method", "status"]).expect("valid metric");
+        let rpc_latency_seconds = HistogramVec::new(HistogramOpts::new("rpc_latency_seconds", "RPC call latency").buckets(LATENCY_BUCKETS.to_vec()), &["method"]).expect("valid metric");
+        let rpc_endpoint_up = IntGaugeVec::new(Opts::new("rpc_endpoint_up", "RPC endpoint in rotation (1) or circuit open (0)"), &["endpoint"]).expect("valid metric");
+        let block_lag = IntGaugeVec::new(Opts::new("block_lag", "Blocks between the chain head and the last block processed by a target"), &["target"]).expect("valid metric");
+        let trades = IntCounterVec::new(Opts::new("trades_total", "Trades by status (attempted, succeeded, failed)"), &["target", "status"]).expect("valid metric");
+        let gas_spent_hype = CounterVec::new(Opts::new("gas_spent_hype_total", "Gas paid by swaps in HYPE"), &["target"]).expect("valid metric");
+        let inventory_ratio = GaugeVec::new(Opts::new("inventory_ratio", "Base token share of the vault value"), &["target"]).expect("valid metric");
+
+        registry.register(Box::new(spread_bps.clone())).expect("register spread_bps");
+        registry.register(Box::new(best_net_bps.clone())).expect("register best_net_bps");
+        registry.register(Box::new(exec_threshold_bps.clone())).expect("register exec_threshold_bps");
+        registry.register(Box::new(oracle_latency_seconds.clone())).expect("register oracle_latency_seconds");
+        registry.register(Box::new(oracle_errors.clone())).expect("register oracle_errors");
+        registry.register(Box::new(rpc_calls.clone())).expect("register rpc_calls");
+        registry.register(Box::new(rpc_latency_seconds.clone())).expect("register rpc_latency_seconds");
+        registry.register(Box::new(rpc_endpoint_up.clone())).expect("register rpc_endpoint_up");
+        registry.register(Box::new(block_lag.clone())).expect("register block_lag");
+        registry.register(Box::new(trades.clone())).expect("register trades");
+        registry.register(Box::new(gas_spent_hype.clone())).expect("register gas_spent_hype");
+        registry.register(Box::new(inventory_ratio.clone())).expect("register inventory_ratio");
+
+        Self {
+            registry,
+            spread_bps,
+            best_net_bps,
+            exec_threshold_bps,
+            oracle_latency_seconds,
+            oracle_errors,
+            rpc_calls,
+            rpc_latency_seconds,
+            rpc_endpoint_up,
+            block_lag,
+            trades,
+            gas_spent_hype,
+            inventory_ratio,
+        }
+    }
+
+    /// Record an oracle fetch
+    pub fn observe_oracle(&self, reference: &str, elapsed: Duration, ok: bool) {
+        self.oracle_latency_seconds.with_label_values(&[reference]).observe(elapsed.as_secs_f64());
+        if !ok {
+            self.oracle_errors.with_label_values(&[reference]).inc();
+        }
+    }
+
+    /// Record an RPC call
+    pub fn observe_rpc(&self, method: &str, elapsed: Duration, ok: bool) {
+        self.rpc_latency_seconds.with_label_values(&[method]).observe(elapsed.as_secs_f64());
+        self.rpc_calls.with_label_values(&[method, if ok { "ok" } else { "error" }]).inc();
+    }
+
+    /// Record a trade status (attempted, succeeded, failed)
+    pub fn inc_trade(&self, target: &str, status: &str) {
+        self.trades.with_label_values(&[target, status]).inc();
+    }
+
+    /// Text exposition of all metrics
+    pub fn render(&self) -> String {
+        let mut buffer = Vec::new();
+        if let Err(e) = TextEncoder::new().encode(&self.registry.gather(), &mut buffer) {
+            tracing::error!("Failed to encode metrics: {}", e);
+        }
+        String::from_utf8(buffer).unwrap_or_default()
+    }
+}
+
+async fn metrics_handler() -> impl IntoResponse {
+    ([(header::CONTENT_TYPE, "text/plain; version=0.0.4")], METRICS.render())
+}
+
+/// Serve /metrics on the given address (runs until the listener fails)
+pub async fn serve(address: &str) -> Result<()> {
+    let address: SocketAddr = address.parse().map_err(|e| Error::Config(format!("Invalid listen address {}: {}", address, e)))?;
+    let app = Router::new().route("/metrics", get(metrics_handler));
+    let listener = tokio::net::TcpListener::bind(address).await?;
+    tracing::info!("📈 Metrics server listening on http://{}/metrics", address);
+    axum::serve(listener, app).await?;
+    Ok(())
+}
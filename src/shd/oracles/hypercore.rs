@@ -1,6 +1,6 @@
-use eyre::Result;
 use super::super::{
     core::api::HyperLiquidAPI,
+    error::{Error, Result},
     types::BotConfig,
 };
 
@@ -21,17 +21,17 @@ impl Hypercore {
     pub async fn get_price(&self, symbol: &str) -> Result<f64> {
         match symbol.to_uppercase().as_str() {
             "BTC" => {
-                let price = self.api.get_btc_price().await?;
+                let price = self.api.get_btc_price().await.map_err(|e| Error::Oracle(e.to_string()))?;
                 tracing::info!("Hypercore API BTC price: ${:.2}", price);
                 Ok(price)
             }
             "ETH" => {
-                let price = self.api.get_eth_price().await?;
+                let price = self.api.get_eth_price().await.map_err(|e| Error::Oracle(e.to_string()))?;
                 tracing::info!("Hypercore API ETH price: ${:.2}", price);
                 Ok(price)
             }
             "HYPE" | "WHYPE" => {
-                let price = self.api.get_hype_price().await?;
+                let price = self.api.get_hype_price().await.map_err(|e| Error::Oracle(e.to_string()))?;
                 tracing::info!("Hypercore API HYPE price: ${:.2}", price);
                 Ok(price)
             }
@@ -42,7 +42,7 @@ impl Hypercore {
                         tracing::info!("Hypercore API {} price: ${:.2}", symbol, price);
                         Ok(price)
                     }
-                    Err(_) => Err(eyre::eyre!("Hypercore doesn't support {} price", symbol))
+                    Err(_) => Err(Error::Oracle(format!("Hypercore doesn't support {} price", symbol)))
                 }
             }
         }
@@ -1,7 +1,8 @@
-use eyre::Result;
 use reqwest::Client;
 use serde::Deserialize;
 
+use crate::error::{Error, Result};
+
 #[derive(Deserialize)]
 pub struct PriceUpdate {
     pub parsed: Vec<ParsedPriceUpdate>,
@@ -40,13 +41,13 @@ impl Pyth {
             "https://hermes.pyth.network/v2/updates/price/latest?ids[]={}",
             price_id
         );
-        let resp = self.client.get(&url).send().await?;
-        let data: PriceUpdate = resp.json().await?;
+        let resp = self.client.get(&url).send().await.map_err(|e| Error::Oracle(format!("Pyth request failed: {}", e)))?;
+        let data: PriceUpdate = resp.json().await.map_err(|e| Error::Oracle(format!("Failed to parse Pyth response: {}", e)))?;
 
         data.parsed
             .first()
             .map(|p| p.price.to_price_f64())
-            .ok_or_else(|| eyre::eyre!("No price data"))
+            .ok_or_else(|| Error::Oracle("No price data".to_string()))
     }
 }
 
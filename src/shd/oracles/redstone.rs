@@ -1,8 +1,9 @@
 use alloy::{network::Ethereum, primitives::Address, providers::RootProvider, sol};
-use eyre::Result;
 use serde::Deserialize;
 use std::sync::Arc;
 
+use crate::error::{Error, Result};
+
 // Redstone Oracle contract interface
 sol! {
     #[sol(rpc)]
@@ -67,25 +68,25 @@ impl Redstone {
             .timeout(std::time::Duration::from_secs(5))
             .send()
             .await
-            .map_err(|e| eyre::eyre!("Request failed: {}", e))?;
+            .map_err(|e| Error::Oracle(format!("Request failed: {}", e)))?;
 
         if !resp.status().is_success() {
-            return Err(eyre::eyre!("API returned status: {}", resp.status()));
+            return Err(Error::Oracle(format!("API returned status: {}", resp.status())));
         }
 
-        let text = resp.text().await?;
+        let text = resp.text().await.map_err(|e| Error::Oracle(format!("Failed to read response: {}", e)))?;
 
         // Parse response - Redstone returns array
-        let data: Vec<RedstoneApiResponse> = serde_json::from_str(&text).map_err(|e| eyre::eyre!("Failed to parse response: {}, raw: {}", e, text))?;
+        let data: Vec<RedstoneApiResponse> = serde_json::from_str(&text).map_err(|e| Error::Oracle(format!("Failed to parse response: {}, raw: {}", e, text)))?;
 
-        data.first().map(|p| p.value).ok_or_else(|| eyre::eyre!("No price data for {}", symbol))
+        data.first().map(|p| p.value).ok_or_else(|| Error::Oracle(format!("No price data for {}", symbol)))
     }
 
     // Fetch price from onchain oracle
     pub async fn get_price_onchain(&self, feed_id: &str) -> Result<f64> {
-        let oracle_addr = self.oracle_address.ok_or_else(|| eyre::eyre!("Onchain oracle not configured"))?;
+        let oracle_addr = self.oracle_address.ok_or_else(|| Error::Config("Onchain oracle not configured".to_string()))?;
 
-        let provider = self.provider.as_ref().ok_or_else(|| eyre::eyre!("Provider not configured"))?;
+        let provider = self.provider.as_ref().ok_or_else(|| Error::Config("Provider not configured".to_string()))?;
 
         // Convert feed_id to bytes32
         let feed_bytes = feed_id.as_bytes();
@@ -93,7 +94,7 @@ impl Redstone {
         bytes32[..feed_bytes.len().min(32)].copy_from_slice(&feed_bytes[..feed_bytes.len().min(32)]);
 
         let oracle = IRedstoneOracle::new(oracle_addr, provider.clone());
-        let price = oracle.getPrice(bytes32.into()).call().await?;
+        let price = oracle.getPrice(bytes32.into()).call().await.map_err(|e| Error::Oracle(format!("Onchain oracle call failed: {}", e)))?;
 
         // Redstone returns price with 8 decimals typically
         Ok(price.to::<u128>() as f64 / 1e8)
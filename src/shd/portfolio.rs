@@ -0,0 +1,269 @@
+// Mark-to-market portfolio and PnL accounting per vault
+
+use chrono::{Duration, NaiveDateTime, Utc};
+use sea_orm::{ColumnTrait, Database, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder};
+use std::collections::{BTreeMap, HashMap};
+
+use crate::{
+    data::{entity::arb_trade, instance_name},
+    dex::pnl::TradePnl,
+    error::Result,
+    types::ArbTarget,
+};
+
+// ===== VAULT PORTFOLIO =====
+
+/// Running portfolio state of a single vault since the bot started
+#[derive(Debug, Clone)]
+pub struct VaultPortfolio {
+    pub vault_name: String,
+    pub base_token: String,
+    pub quote_token: String,
+    pub starting_base: f64,
+    pub starting_quote: f64,
+    pub starting_reference_price: f64,
+    pub current_base: f64,
+    pub current_quote: f64,
+    pub reference_price: f64,
+    /// Sum of per-trade net PnL (at the reference price at execution, net of gas)
+    pub realised_pnl_usd: f64,
+    pub fees_paid_usd: f64,
+    pub gas_paid_usd: f64,
+    pub trade_count: u64,
+    pub volume_usd: f64,
+}
+
+impl VaultPortfolio {
+    pub fn new(target: &ArbTarget, base_balance: f64, quote_balance: f64, reference_price: f64) -> Self {
+        Self {
+            vault_name: target.vault_name.clone(),
+            base_token: target.base_token.clone(),
+            quote_token: target.quote_token.clone(),
+            starting_base: base_balance,
+            starting_quote: quote_balance,
+            starting_reference_price: reference_price,
+            current_base: base_balance,
+            current_quote: quote_balance,
+            reference_price,
+            realised_pnl_usd: 0.0,
+            fees_paid_usd: 0.0,
+            gas_paid_usd: 0.0,
+            trade_count: 0,
+            volume_usd: 0.0,
+        }
+    }
+
+    /// Account a realised trade
+    pub fn apply_trade(&mut self, pnl: &TradePnl) {
+        self.realised_pnl_usd += pnl.net_pnl_usd;
+        self.fees_paid_usd += pnl.fee_usd;
+        self.gas_paid_usd += pnl.gas_cost_usd;
+        self.volume_usd += pnl.quote_amount;
+        self.trade_count += 1;
+    }
+
+    /// Update balances and reference price
+    pub fn mark(&mut self, base_balance: f64, quote_balance: f64, reference_price: f64) {
+        self.current_base = base_balance;
+        self.current_quote = quote_balance;
+        self.reference_price = reference_price;
+    }
+
+    pub fn starting_value_usd(&self) -> f64 {
+        self.starting_base * self.starting_reference_price + self.starting_quote
+    }
+
+    pub fn current_value_usd(&self) -> f64 {
+        self.current_base * self.reference_price + self.current_quote
+    }
+
+    /// Total change in portfolio value since start (gas is paid in HYPE, outside the marked balances)
+    pub fn total_pnl_usd(&self) -> f64 {
+        self.current_value_usd() - self.starting_value_usd()
+    }
+
+    /// Total change in portfolio value since start, net of the gas paid
+    pub fn net_pnl_usd(&self) -> f64 {
+        self.total_pnl_usd() - self.gas_paid_usd
+    }
+
+    /// Revaluation of the held inventory at the current reference price (net total minus realised)
+    pub fn unrealised_pnl_usd(&self) -> f64 {
+        self.net_pnl_usd() - self.realised_pnl_usd
+    }
+
+    /// Base inventory change since start (in base units)
+    pub fn inventory_drift(&self) -> f64 {
+        self.current_base - self.starting_base
+    }
+
+    /// Change of the base share of portfolio value since start, in percentage points
+    pub fn inventory_drift_pct(&self) -> f64 {
+        let start = self.starting_value_usd();
+        let current = self.current_value_usd();
+        if start <= 0.0 || current <= 0.0 {
+            return 0.0;
+        }
+        let start_pct = self.starting_base * self.starting_reference_price / start * 100.0;
+        let current_pct = self.current_base * self.reference_price / current * 100.0;
+        current_pct - start_pct
+    }
+
+    pub fn log_summary(&self) {
+        tracing::info!(
+            "📒 Portfolio {} | Value: ${:.2} (start ${:.2}) | Realised: ${:.2} | Unrealised: ${:.2} | Fees: ${:.2} | Gas: ${:.2} | Trades: {} | Volume: ${:.2} | Drift: {:+.6} {} ({:+.1} pp)",
+            self.vault_name,
+            self.current_value_usd(),
+            self.starting_value_usd(),
+            self.realised_pnl_usd,
+            self.unrealised_pnl_usd(),
+            self.fees_paid_usd,
+            self.gas_paid_usd,
+            self.trade_count,
+            self.volume_usd,
+            self.inventory_drift(),
+            self.base_token,
+            self.inventory_drift_pct()
+        );
+    }
+}
+
+/// Portfolios of all vaults, keyed by vault name
+#[derive(Debug, Clone, Default)]
+pub struct Portfolio {
+    pub vaults: HashMap<String, VaultPortfolio>,
+}
+
+impl Portfolio {
+    /// Mark a vault, creating it with the current balances as starting inventory on first call
+    pub fn mark(&mut self, target: &ArbTarget, base_balance: f64, quote_balance: f64, reference_price: f64) -> &VaultPortfolio {
+        self.vaults
+            .entry(target.vault_name.clone())
+            .and_modify(|v| v.mark(base_balance, quote_balance, reference_price))
+            .or_insert_with(|| VaultPortfolio::new(target, base_balance, quote_balance, reference_price))
+    }
+
+    /// Account a realised trade (ignored until the vault has been marked once)
+    pub fn apply_trade(&mut self, vault_name: &str, pnl: &TradePnl) {
+        if let Some(vault) = self.vaults.get_mut(vault_name) {
+            vault.apply_trade(pnl);
+        }
+    }
+}
+
+// ===== REPORT =====
+
+/// Aggregated trades over a period (day or week)
+#[derive(Debug, Clone, Default)]
+pub struct PeriodBreakdown {
+    pub trades: u64,
+    pub reverted: u64,
+    pub volume_usd: f64,
+    pub gross_pnl_usd: f64,
+    pub fees_usd: f64,
+    pub gas_usd: f64,
+    pub net_pnl_usd: f64,
+    pub slippage_bps_sum: f64,
+}
+
+impl PeriodBreakdown {
+    fn add(&mut self, pnl: &TradePnl) {
+        self.trades += 1;
+        if !pnl.success {
+            self.reverted += 1;
+        }
+        self.volume_usd += pnl.quote_amount;
+        self.gross_pnl_usd += pnl.gross_pnl_usd;
+        self.fees_usd += pnl.fee_usd;
+        self.gas_usd += pnl.gas_cost_usd;
+        self.net_pnl_usd += pnl.net_pnl_usd;
+        self.slippage_bps_sum += pnl.slippage_bps;
+    }
+
+    pub fn avg_slippage_bps(&self) -> f64 {
+        let filled = self.trades - self.reverted;
+        if filled > 0 { self.slippage_bps_sum / filled as f64 } else { 0.0 }
+    }
+}
+
+/// Load persisted trades of a vault since `days` ago
+pub async fn load_trades(db: &DatabaseConnection, vault_name: &str, days: i64) -> Result<Vec<(NaiveDateTime, TradePnl)>> {
+    let since = (Utc::now() - Duration::days(days)).naive_utc();
+    let rows = arb_trade::Entity::find()
+        .filter(arb_trade::Column::InstanceName.eq(instance_name(vault_name)))
+        .filter(arb_trade::Column::CreatedAt.gte(since))
+        .order_by_asc(arb_trade::Column::CreatedAt)
+        .all(db)
+        .await?;
+
+    let mut trades = Vec::with_capacity(rows.len());
+    for row in rows {
+        match serde_json::from_value::<TradePnl>(row.values) {
+            Ok(pnl) => trades.push((row.created_at, pnl)),
+            Err(e) => tracing::debug!("Skipping trade {} with unexpected values: {}", row.id, e),
+        }
+    }
+    Ok(trades)
+}
+
+/// Group trades by period, `format` is a chrono format string (e.g. "%Y-%m-%d" or "%G-W%V")
+pub fn breakdown(trades: &[(NaiveDateTime, TradePnl)], format: &str) -> BTreeMap<String, PeriodBreakdown> {
+    let mut periods: BTreeMap<String, PeriodBreakdown> = BTreeMap::new();
+    for (created_at, pnl) in trades {
+        periods.entry(created_at.format(format).to_string()).or_default().add(pnl);
+    }
+    periods
+}
+
+fn log_breakdown(label: &str, periods: &BTreeMap<String, PeriodBreakdown>) {
+    tracing::info!("{} breakdown:", label);
+    for (period, b) in periods {
+        tracing::info!(
+            "  {} | Trades: {} ({} reverted) | Volume: ${:.2} | Gross: ${:.2} | Fees: ${:.2} | Gas: ${:.2} | Net: ${:.2} | Avg slippage: {:.2} bps",
+            period,
+            b.trades,
+            b.reverted,
+            b.volume_usd,
+            b.gross_pnl_usd,
+            b.fees_usd,
+            b.gas_usd,
+            b.net_pnl_usd,
+            b.avg_slippage_bps()
+        );
+    }
+}
+
+/// Print daily and weekly PnL breakdowns for every target from the persisted trade history
+pub async fn report(database_url: &str, targets: &[ArbTarget], days: i64) -> Result<()> {
+    let db = Database::connect(database_url).await?;
+    for target in targets {
+        let trades = load_trades(&db, &target.vault_name, days).await?;
+        tracing::info!(
+            "📒 Report {} ({}/{}) - last {} days - {} trades",
+            target.vault_name,
+            target.base_token,
+            target.quote_token,
+            days,
+            trades.len()
+        );
+        if trades.is_empty() {
+            continue;
+        }
+        log_breakdown("Daily", &breakdown(&trades, "%Y-%m-%d"));
+        log_breakdown("Weekly", &breakdown(&trades, "%G-W%V"));
+
+        let total = trades.iter().fold(PeriodBreakdown::default(), |mut acc, (_, pnl)| {
+            acc.add(pnl);
+            acc
+        });
+        tracing::info!(
+            "  Total | Trades: {} | Volume: ${:.2} | Fees: ${:.2} | Gas: ${:.2} | Net: ${:.2}",
+            total.trades,
+            total.volume_usd,
+            total.fees_usd,
+            total.gas_usd,
+            total.net_pnl_usd
+        );
+    }
+    Ok(())
+}
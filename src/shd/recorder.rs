@@ -0,0 +1,582 @@
+// Historical spread recorder: one row per block, target and pool in rolling CSV or Parquet files
+
+use alloy::{
+    eips::BlockNumberOrTag,
+    primitives::{Address, U256},
+    providers::Provider,
+    rpc::types::Filter,
+    sol_types::SolEvent,
+};
+use arrow_array::{
+    Array, ArrayRef, ArrowPrimitiveType, BooleanArray, Float64Array, Int32Array, Int64Array, PrimitiveArray, RecordBatch, StringArray, UInt8Array, UInt64Array,
+    cast::AsArray,
+    types::{Float64Type, Int32Type, Int64Type, UInt8Type, UInt64Type},
+};
+use arrow_schema::{DataType, Field, Schema};
+use chrono::DateTime;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    str::FromStr,
+    sync::{
+        Arc, Mutex,
+        mpsc::{self, Receiver, RecvTimeoutError, SyncSender, TrySendError},
+    },
+    thread::JoinHandle,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    core::api::HyperLiquidAPI,
+    dex::{
+        pnl::Swap,
+        pool_data::{PoolInfo, calculate_pool_prices, get_pool_info},
+    },
+    error::{Error, Result},
+    types::{ArbTarget, BotConfig, PriceReference, RecordFormat, RecorderConfig},
+    utils::misc::{append_csv, read_csv, read_parquet, save_parquet},
+};
+
+/// Row batches (one per target pass) queued for the writer
+pub const RECORDER_CHANNEL_CAPACITY: usize = 1024;
+
+// ===== RECORD =====
+
+/// State and spread of one pool at one block
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SpreadRecord {
+    pub block: u64,
+    pub timestamp: i64, // Observation time (unix seconds)
+    pub vault: String,
+    pub dex: String,
+    pub pool: String,
+    pub fee_bps: f64,
+    pub pool_price: f64, // Base in quote
+    pub tick: i32,
+    pub liquidity: String,      // In-range liquidity (uint128, decimal)
+    pub sqrt_price_x96: String, // uint160, decimal
+    pub base_is_token0: bool,
+    pub token0_decimals: u8,
+    pub token1_decimals: u8,
+    pub reference: String, // Oracle of the target, spread_bps is measured against it
+    pub reference_price: f64,
+    pub hypercore_price: Option<f64>,
+    pub pyth_price: Option<f64>,
+    pub redstone_price: Option<f64>,
+    pub spread_bps: f64,
+}
+
+impl SpreadRecord {
+    pub fn set_oracle_price(&mut self, reference: &PriceReference, price: f64) {
+        match reference {
+            PriceReference::Hypercore => self.hypercore_price = Some(price),
+            PriceReference::Pyth => self.pyth_price = Some(price),
+            PriceReference::Redstone => self.redstone_price = Some(price),
+        }
+    }
+
+    pub fn oracle_price(&self, reference: &PriceReference) -> Option<f64> {
+        match reference {
+            PriceReference::Hypercore => self.hypercore_price,
+            PriceReference::Pyth => self.pyth_price,
+            PriceReference::Redstone => self.redstone_price,
+        }
+    }
+
+    /// Row of one pool, sharing the block, time and reference prices of a pass template
+    pub fn for_pool(template: &SpreadRecord, dex: &str, pool: &str, pool_info: &PoolInfo, pool_price: f64, base_is_token0: bool) -> Self {
+        SpreadRecord {
+            dex: dex.to_string(),
+            pool: pool.to_string(),
+            fee_bps: pool_info.fee as f64 / 100.0,
+            pool_price,
+            tick: pool_info.tick,
+            liquidity: pool_info.liquidity.to_string(),
+            sqrt_price_x96: pool_info.sqrt_price_x96.to_string(),
+            base_is_token0,
+            token0_decimals: pool_info.token0_decimals,
+            token1_decimals: pool_info.token1_decimals,
+            spread_bps: (pool_price - template.reference_price) / template.reference_price * 10_000.0,
+            ..template.clone()
+        }
+    }
+
+    /// Pool state of the row, for the V3 maths (token addresses come from the target)
+    pub fn pool_info(&self, base_token: Address, quote_token: Address) -> Result<PoolInfo> {
+        let parse = |value: &str| U256::from_str(value).map_err(|e| Error::Io(format!("Invalid pool state {} in spread record: {}", value, e)));
+        let (token0, token1) = if self.base_is_token0 { (base_token, quote_token) } else { (quote_token, base_token) };
+        Ok(PoolInfo {
+            address: Address::from_str(&self.pool)?,
+            token0,
+            token1,
+            fee: (self.fee_bps * 100.0).round() as u32,
+            liquidity: parse(&self.liquidity)?,
+            sqrt_price_x96: parse(&self.sqrt_price_x96)?,
+            tick: self.tick,
+            token0_decimals: self.token0_decimals,
+            token1_decimals: self.token1_decimals,
+        })
+    }
+}
+
+/// Columnar batch of records (Parquet)
+pub fn record_batch(rows: &[SpreadRecord]) -> Result<RecordBatch> {
+    let strings = |f: fn(&SpreadRecord) -> &str| Arc::new(StringArray::from_iter_values(rows.iter().map(f))) as ArrayRef;
+    let floats = |f: fn(&SpreadRecord) -> f64| Arc::new(Float64Array::from_iter_values(rows.iter().map(f))) as ArrayRef;
+    let optional = |f: fn(&SpreadRecord) -> Option<f64>| Arc::new(rows.iter().map(f).collect::<Float64Array>()) as ArrayRef;
+
+    let schema = Schema::new(vec![
+        Field::new("block", DataType::UInt64, false),
+        Field::new("timestamp", DataType::Int64, false),
+        Field::new("vault", DataType::Utf8, false),
+        Field::new("dex", DataType::Utf8, false),
+        Field::new("pool", DataType::Utf8, false),
+        Field::new("fee_bps", DataType::Float64, false),
+        Field::new("pool_price", DataType::Float64, false),
+        Field::new("tick", DataType::Int32, false),
+        Field::new("liquidity", DataType::Utf8, false),
+        Field::new("sqrt_price_x96", DataType::Utf8, false),
+        Field::new("base_is_token0", DataType::Boolean, false),
+        Field::new("token0_decimals", DataType::UInt8, false),
+        Field::new("token1_decimals", DataType::UInt8, false),
+        Field::new("reference", DataType::Utf8, false),
+        Field::new("reference_price", DataType::Float64, false),
+        Field::new("hypercore_price", DataType::Float64, true),
+        Field::new("pyth_price", DataType::Float64, true),
+        Field::new("redstone_price", DataType::Float64, true),
+        Field::new("spread_bps", DataType::Float64, false),
+    ]);
+    let columns = vec![
+        Arc::new(UInt64Array::from_iter_values(rows.iter().map(|r| r.block))) as ArrayRef,
+        Arc::new(Int64Array::from_iter_values(rows.iter().map(|r| r.timestamp))),
+        strings(|r| &r.vault),
+        strings(|r| &r.dex),
+        strings(|r| &r.pool),
+        floats(|r| r.fee_bps),
+        floats(|r| r.pool_price),
+        Arc::new(Int32Array::from_iter_values(rows.iter().map(|r| r.tick))),
+        strings(|r| &r.liquidity),
+        strings(|r| &r.sqrt_price_x96),
+        Arc::new(rows.iter().map(|r| Some(r.base_is_token0)).collect::<BooleanArray>()),
+        Arc::new(UInt8Array::from_iter_values(rows.iter().map(|r| r.token0_decimals))),
+        Arc::new(UInt8Array::from_iter_values(rows.iter().map(|r| r.token1_decimals))),
+        strings(|r| &r.reference),
+        floats(|r| r.reference_price),
+        optional(|r| r.hypercore_price),
+        optional(|r| r.pyth_price),
+        optional(|r| r.redstone_price),
+        floats(|r| r.spread_bps),
+    ];
+    RecordBatch::try_new(Arc::new(schema), columns).map_err(|e| Error::Io(format!("Failed to build spread batch: {}", e)))
+}
+
+/// Records of a columnar batch (the Parquet schema of `record_batch`)
+pub fn records_from_batch(batch: &RecordBatch) -> Result<Vec<SpreadRecord>> {
+    fn primitive<'a, T: ArrowPrimitiveType>(batch: &'a RecordBatch, name: &str) -> Result<&'a PrimitiveArray<T>> {
+        batch
+            .column_by_name(name)
+            .and_then(|c| c.as_primitive_opt::<T>())
+            .ok_or_else(|| Error::Io(format!("Missing or invalid column {} in spread batch", name)))
+    }
+    fn strings<'a>(batch: &'a RecordBatch, name: &str) -> Result<&'a StringArray> {
+        batch
+            .column_by_name(name)
+            .and_then(|c| c.as_string_opt::<i32>())
+            .ok_or_else(|| Error::Io(format!("Missing or invalid column {} in spread batch", name)))
+    }
+    let optional = |column: &PrimitiveArray<Float64Type>, i: usize| column.is_valid(i).then(|| column.value(i));
+
+    let block = primitive::<UInt64Type>(batch, "block")?;
+    let timestamp = primitive::<Int64Type>(batch, "timestamp")?;
+    let (vault, dex, pool) = (strings(batch, "vault")?, strings(batch, "dex")?, strings(batch, "pool")?);
+    let (fee_bps, pool_price) = (primitive::<Float64Type>(batch, "fee_bps")?, primitive::<Float64Type>(batch, "pool_price")?);
+    let tick = primitive::<Int32Type>(batch, "tick")?;
+    let (liquidity, sqrt_price_x96) = (strings(batch, "liquidity")?, strings(batch, "sqrt_price_x96")?);
+    let base_is_token0 = batch
+        .column_by_name("base_is_token0")
+        .and_then(|c| c.as_boolean_opt())
+        .ok_or_else(|| Error::Io("Missing or invalid column base_is_token0 in spread batch".to_string()))?;
+    let (token0_decimals, token1_decimals) = (primitive::<UInt8Type>(batch, "token0_decimals")?, primitive::<UInt8Type>(batch, "token1_decimals")?);
+    let (reference, reference_price) = (strings(batch, "reference")?, primitive::<Float64Type>(batch, "reference_price")?);
+    let hypercore_price = primitive::<Float64Type>(batch, "hypercore_price")?;
+    let pyth_price = primitive::<Float64Type>(batch, "pyth_price")?;
+    let redstone_price = primitive::<Float64Type>(batch, "redstone_price")?;
+    let spread_bps = primitive::<Float64Type>(batch, "spread_bps")?;
+
+    Ok((0..batch.num_rows())
+        .map(|i| SpreadRecord {
+            block: block.value(i),
+            timestamp: timestamp.value(i),
+            vault: vault.value(i).to_string(),
+            dex: dex.value(i).to_string(),
+            pool: pool.value(i).to_string(),
+            fee_bps: fee_bps.value(i),
+            pool_price: pool_price.value(i),
+            tick: tick.value(i),
+            liquidity: liquidity.value(i).to_string(),
+            sqrt_price_x96: sqrt_price_x96.value(i).to_string(),
+            base_is_token0: base_is_token0.value(i),
+            token0_decimals: token0_decimals.value(i),
+            token1_decimals: token1_decimals.value(i),
+            reference: reference.value(i).to_string(),
+            reference_price: reference_price.value(i),
+            hypercore_price: optional(hypercore_price, i),
+            pyth_price: optional(pyth_price, i),
+            redstone_price: optional(redstone_price, i),
+            spread_bps: spread_bps.value(i),
+        })
+        .collect())
+}
+
+// ===== WRITER =====
+
+/// Rolling files of the configured directory
+struct RecordWriter {
+    config: RecorderConfig,
+    period: Option<i64>,
+    buffer: Vec<SpreadRecord>,
+    last_flush: Instant,
+}
+
+impl RecordWriter {
+    /// Start of the roll period of a timestamp
+    fn period_of(&self, timestamp: i64) -> i64 {
+        timestamp - timestamp.rem_euclid(self.config.roll_secs as i64)
+    }
+
+    fn file(&self, period: i64, part: Option<usize>) -> String {
+        let start = DateTime::from_timestamp(period, 0).map(|t| t.format("%Y%m%d_%H%M%S").to_string()).unwrap_or_else(|| period.to_string());
+        let part = part.map(|p| format!("_{:03}", p)).unwrap_or_default();
+        format!("{}/spreads_{}{}.{}", self.config.directory.trim_end_matches('/'), start, part, self.config.format.extension())
+    }
+
+    /// Time left before the buffered rows are due
+    fn flush_due_in(&self) -> Duration {
+        Duration::from_secs(self.config.flush_secs).saturating_sub(self.last_flush.elapsed())
+    }
+
+    fn write(&mut self, rows: Vec<SpreadRecord>) -> Result<()> {
+        for row in rows {
+            let period = self.period_of(row.timestamp);
+            if self.period != Some(period) {
+                self.flush()?;
+                self.period = Some(period);
+            }
+            self.buffer.push(row);
+        }
+        match self.config.format {
+            RecordFormat::Csv => self.flush(),
+            RecordFormat::Parquet if self.buffer.len() >= self.config.flush_rows || self.flush_due_in().is_zero() => self.flush(),
+            RecordFormat::Parquet => Ok(()),
+        }
+    }
+
+    /// Write the buffered rows of the current period (CSV appended, Parquet as the next free part file)
+    fn flush(&mut self) -> Result<()> {
+        // Reset first so a failing write is retried at the next interval rather than in a loop
+        self.last_flush = Instant::now();
+        let Some(period) = self.period else {
+            return Ok(());
+        };
+        if self.buffer.is_empty() {
+            return Ok(());
+        }
+        match self.config.format {
+            RecordFormat::Csv => append_csv(&self.buffer, &self.file(period, None))?,
+            RecordFormat::Parquet => {
+                // Parquet files cannot be appended to, each flush writes the next free part of the period
+                let mut part = 0;
+                let mut file = self.file(period, Some(part));
+                while std::path::Path::new(&file).exists() {
+                    part += 1;
+                    file = self.file(period, Some(part));
+                }
+                save_parquet(&record_batch(&self.buffer)?, &file)?;
+                tracing::debug!("📼 Wrote {} spread rows to {}", self.buffer.len(), file);
+            }
+        }
+        self.buffer.clear();
+        Ok(())
+    }
+
+    /// Write rows as they arrive and buffered rows every flush_secs, until the channel is closed
+    fn run(mut self, receiver: Receiver<Vec<SpreadRecord>>) {
+        loop {
+            let result = match receiver.recv_timeout(self.flush_due_in()) {
+                Ok(rows) => self.write(rows),
+                Err(RecvTimeoutError::Timeout) => self.flush(),
+                Err(RecvTimeoutError::Disconnected) => break,
+            };
+            if let Err(e) = result {
+                tracing::error!("📼 Failed to record spreads: {}", e);
+            }
+        }
+        if let Err(e) = self.flush() {
+            tracing::error!("📼 Failed to flush spreads: {}", e);
+        }
+    }
+}
+
+// ===== RECORDER =====
+
+/// Handle to the background spread writer (cheap to clone)
+#[derive(Debug, Clone, Default)]
+pub struct SpreadRecorder {
+    sender: Arc<Mutex<Option<SyncSender<Vec<SpreadRecord>>>>>,
+    writer: Arc<Mutex<Option<JoinHandle<()>>>>,
+}
+
+impl SpreadRecorder {
+    /// Recorder that discards every row (no [recorder] section)
+    pub fn disabled() -> Self {
+        Self::default()
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.sender.lock().is_ok_and(|sender| sender.is_some())
+    }
+
+    /// Queue the rows of a target pass
+    pub fn record(&self, rows: Vec<SpreadRecord>) {
+        if rows.is_empty() {
+            return;
+        }
+        let Ok(sender) = self.sender.lock() else {
+            return;
+        };
+        let Some(sender) = sender.as_ref() else {
+            return;
+        };
+        match sender.try_send(rows) {
+            Ok(()) => {}
+            Err(TrySendError::Full(_)) => tracing::warn!("📼 Recorder channel full, dropping spread rows"),
+            Err(TrySendError::Disconnected(_)) => tracing::error!("📼 Recorder stopped, dropping spread rows"),
+        }
+    }
+
+    /// Close the channel and wait for the writer to flush its buffered rows (blocking). Later rows are discarded
+    pub fn shutdown(&self) {
+        drop(self.sender.lock().ok().and_then(|mut sender| sender.take()));
+        let writer = self.writer.lock().ok().and_then(|mut writer| writer.take());
+        if let Some(writer) = writer
+            && writer.join().is_err()
+        {
+            tracing::error!("📼 Spread writer panicked, buffered rows are lost");
+        }
+    }
+}
+
+/// Create the output directory and start the writer thread (file writes stay off the async runtime)
+pub fn spawn_spread_recorder(config: &RecorderConfig, capacity: usize) -> Result<SpreadRecorder> {
+    std::fs::create_dir_all(&config.directory)?;
+    let (sender, receiver) = mpsc::sync_channel::<Vec<SpreadRecord>>(capacity);
+    let writer = RecordWriter {
+        config: config.clone(),
+        period: None,
+        buffer: Vec::new(),
+        last_flush: Instant::now(),
+    };
+    let handle = std::thread::Builder::new().name("spread-recorder".to_string()).spawn(move || writer.run(receiver))?;
+    Ok(SpreadRecorder {
+        sender: Arc::new(Mutex::new(Some(sender))),
+        writer: Arc::new(Mutex::new(Some(handle))),
+    })
+}
+
+// ===== HISTORY =====
+
+/// Every record of the recorder files (`spreads_*.csv` and `spreads_*.parquet`) in a directory, by block
+pub fn read_records(directory: &str) -> Result<Vec<SpreadRecord>> {
+    let mut files: Vec<_> = std::fs::read_dir(directory)?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.file_name().and_then(|n| n.to_str()).is_some_and(|n| n.starts_with("spreads_")))
+        .collect();
+    files.sort();
+
+    let mut records = Vec::new();
+    for path in files {
+        let file = path.to_string_lossy().to_string();
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("csv") => records.extend(read_csv::<SpreadRecord>(&file)?),
+            Some("parquet") => {
+                for batch in read_parquet(&file)? {
+                    records.extend(records_from_batch(&batch)?);
+                }
+            }
+            _ => continue,
+        }
+        tracing::debug!("📼 Read {}", file);
+    }
+    records.sort_by_key(|r| r.block);
+    Ok(records)
+}
+
+// Block range of each eth_getLogs request
+const LOG_CHUNK_BLOCKS: u64 = 1000;
+
+/// Rebuild the records of a target from the pool `Swap` logs of a block range, priced against HyperCore
+/// 1m candles (the only reference with a history API). A row is emitted for every block with a swap and
+/// every candle close. Approximations: pools appear from their first swap in the range, liquidity only changes
+/// with swaps (mints and burns are not replayed) and timestamps are interpolated between the range ends
+pub async fn fetch_records<P: Provider + Clone>(provider: P, target: &ArbTarget, config: &BotConfig, from_block: u64, to_block: u64) -> Result<Vec<SpreadRecord>> {
+    if to_block <= from_block {
+        return Err(Error::Config(format!("Empty block range {}..{}", from_block, to_block)));
+    }
+    let timestamp_of = async |number: u64| -> Result<i64> {
+        let block = provider
+            .get_block_by_number(BlockNumberOrTag::Number(number))
+            .await?
+            .ok_or_else(|| Error::Rpc(format!("Block {} not found", number)))?;
+        Ok(block.header.timestamp as i64)
+    };
+    let (start, end) = (timestamp_of(from_block).await?, timestamp_of(to_block).await?);
+    let secs_per_block = (end - start) as f64 / (to_block - from_block) as f64;
+    let block_time = |block: u64| start + ((block - from_block) as f64 * secs_per_block) as i64;
+
+    // Reference: candle closes by open time
+    let coin = match target.base_token.to_uppercase().as_str() {
+        "WHYPE" => "HYPE".to_string(),
+        symbol => symbol.to_string(),
+    };
+    let candles: BTreeMap<i64, f64> = HyperLiquidAPI::new(&config.global.hyperliquid_api_endpoint)
+        .get_candles(&coin, "1m", start * 1000 - 60_000, end * 1000)
+        .await
+        .map_err(|e| Error::Oracle(format!("Failed to fetch {} candles: {}", coin, e)))?
+        .into_iter()
+        .filter_map(|candle| candle.close.parse::<f64>().ok().map(|close| (candle.close_time_ms, close)))
+        .collect();
+    tracing::info!("🕯️ {} {} candles from HyperCore for {}", candles.len(), coin, target.vault_name);
+
+    // Pool state after each swap, in chain order
+    let base_token = Address::from_str(&target.base_token_address)?;
+    let mut pools: HashMap<Address, (&str, PoolInfo)> = HashMap::new();
+    let mut swaps: Vec<(u64, u64, Address, Swap)> = Vec::new();
+    let listed = target.hyperswap_pools.iter().map(|p| ("Hyperswap", p)).chain(target.prjx_pools.iter().map(|p| ("ProjectX", p)));
+    for (dex, pool) in listed.filter(|(_, p)| !p.is_empty()) {
+        let address = Address::from_str(pool)?;
+        pools.insert(address, (dex, get_pool_info(provider.clone(), address).await?));
+        for chunk_start in (from_block..=to_block).step_by(LOG_CHUNK_BLOCKS as usize) {
+            let filter = Filter::new()
+                .address(address)
+                .event_signature(Swap::SIGNATURE_HASH)
+                .from_block(chunk_start)
+                .to_block((chunk_start + LOG_CHUNK_BLOCKS - 1).min(to_block));
+            for log in provider.get_logs(&filter).await? {
+                if let (Some(block), Some(index), Ok(swap)) = (log.block_number, log.log_index, log.log_decode::<Swap>()) {
+                    swaps.push((block, index, address, swap.inner.data));
+                }
+            }
+        }
+        tracing::info!(
+            "🔎 {} {}: {} swaps in blocks {}..{}",
+            dex,
+            &pool[..10],
+            swaps.iter().filter(|s| s.2 == address).count(),
+            from_block,
+            to_block
+        );
+    }
+    swaps.sort_by_key(|(block, index, _, _)| (*block, *index));
+
+    // Snapshot blocks: every swap, and every candle mapped back to a block
+    let mut blocks: BTreeSet<u64> = swaps.iter().map(|(block, ..)| *block).collect();
+    if secs_per_block > 0.0 {
+        blocks.extend(
+            candles
+                .keys()
+                .filter(|close| **close >= start * 1000)
+                .map(|close| from_block + ((close / 1000 - start) as f64 / secs_per_block) as u64)
+                .filter(|block| *block <= to_block),
+        );
+    }
+
+    let mut records = Vec::new();
+    let mut known: BTreeMap<Address, PoolInfo> = BTreeMap::new();
+    let mut pending = swaps.into_iter().peekable();
+    for block in blocks {
+        while let Some((_, _, address, swap)) = pending.next_if(|(swap_block, ..)| *swap_block <= block) {
+            if let Some((_, pool_info)) = pools.get(&address) {
+                let state = known.entry(address).or_insert_with(|| pool_info.clone());
+                state.sqrt_price_x96 = U256::from(swap.sqrtPriceX96);
+                state.liquidity = U256::from(swap.liquidity);
+                state.tick = swap.tick.as_i32();
+            }
+        }
+        let timestamp = block_time(block);
+        // Close of the last candle closed at the block, a candle still open would leak its future close
+        let Some((_, reference_price)) = candles.range(..=timestamp * 1000).next_back() else {
+            continue;
+        };
+        let mut template = SpreadRecord {
+            block,
+            timestamp,
+            vault: target.vault_name.clone(),
+            reference: PriceReference::Hypercore.to_string(),
+            reference_price: *reference_price,
+            ..Default::default()
+        };
+        template.set_oracle_price(&PriceReference::Hypercore, *reference_price);
+        for (address, state) in &known {
+            let price = calculate_pool_prices(state);
+            let base_is_token0 = state.token0 == base_token;
+            let pool_price = if base_is_token0 { price.token0_price } else { price.token1_price };
+            let dex = pools.get(address).map(|(dex, _)| *dex).unwrap_or_default();
+            records.push(SpreadRecord::for_pool(&template, dex, &address.to_string(), state, pool_price, base_is_token0));
+        }
+    }
+    Ok(records)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(name: &str, flush_rows: usize, flush_secs: u64) -> RecorderConfig {
+        let directory = std::env::temp_dir().join(format!("hyper-arb-recorder-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&directory);
+        RecorderConfig {
+            directory: directory.to_string_lossy().to_string(),
+            format: RecordFormat::Parquet,
+            roll_secs: 86400,
+            flush_rows,
+            flush_secs,
+            oracles: Vec::new(),
+        }
+    }
+
+    fn rows(block: u64) -> Vec<SpreadRecord> {
+        vec![SpreadRecord {
+            block,
+            timestamp: 1_700_000_000,
+            pool: Address::ZERO.to_string(),
+            liquidity: "0".to_string(),
+            sqrt_price_x96: "0".to_string(),
+            ..Default::default()
+        }]
+    }
+
+    #[test]
+    fn parquet_rows_flushed_on_interval() {
+        let config = config("interval", 5000, 1);
+        let recorder = spawn_spread_recorder(&config, 16).unwrap();
+        recorder.record(rows(1));
+        std::thread::sleep(Duration::from_millis(1500));
+        assert_eq!(read_records(&config.directory).unwrap().len(), 1);
+        recorder.shutdown();
+        std::fs::remove_dir_all(&config.directory).unwrap();
+    }
+
+    #[test]
+    fn shutdown_flushes_buffered_rows() {
+        let config = config("shutdown", 5000, 3600);
+        let recorder = spawn_spread_recorder(&config, 16).unwrap();
+        recorder.record(rows(1));
+        recorder.record(rows(2));
+        recorder.clone().shutdown();
+        assert!(!recorder.is_enabled());
+        recorder.record(rows(3));
+        let blocks: Vec<u64> = read_records(&config.directory).unwrap().iter().map(|r| r.block).collect();
+        assert_eq!(blocks, vec![1, 2]);
+        std::fs::remove_dir_all(&config.directory).unwrap();
+    }
+}
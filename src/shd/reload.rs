@@ -0,0 +1,231 @@
+// Hot-reload of the bot configuration on file change or SIGHUP
+
+use notify::{RecursiveMode, Watcher};
+use std::{
+    path::{Path, PathBuf},
+    sync::{Arc, RwLock},
+    time::Duration,
+};
+use tokio::{
+    signal::unix::{SignalKind, signal},
+    sync::mpsc,
+};
+
+use crate::{
+    error::{Error, Result},
+    types::{BotConfig, EnvConfig, try_load_bot_config},
+};
+
+/// Time to wait after a change so editors finish writing the file
+const RELOAD_DEBOUNCE: Duration = Duration::from_millis(500);
+
+// ===== SHARED CONFIG =====
+
+/// Current configuration, swapped atomically on reload (cheap to clone)
+#[derive(Debug, Clone)]
+pub struct SharedConfig {
+    inner: Arc<RwLock<Arc<BotConfig>>>,
+}
+
+impl SharedConfig {
+    pub fn new(config: BotConfig) -> Self {
+        Self {
+            inner: Arc::new(RwLock::new(Arc::new(config))),
+        }
+    }
+
+    /// Snapshot of the current configuration, unaffected by later reloads
+    pub fn current(&self) -> Arc<BotConfig> {
+        self.inner.read().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// Replace the configuration, returns the previous one
+    pub fn swap(&self, config: BotConfig) -> Arc<BotConfig> {
+        let mut guard = self.inner.write().unwrap_or_else(|e| e.into_inner());
+        std::mem::replace(&mut *guard, Arc::new(config))
+    }
+}
+
+// ===== DIFF =====
+
+/// Targets changed by a reload, by vault name
+#[derive(Debug, Clone, Default)]
+pub struct TargetDiff {
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+    pub changed: Vec<String>,
+}
+
+impl TargetDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+pub fn diff_targets(old: &BotConfig, new: &BotConfig) -> TargetDiff {
+    let mut diff = TargetDiff::default();
+    for target in &new.targets {
+        match old.targets.iter().find(|t| t.vault_name == target.vault_name) {
+            None => diff.added.push(target.vault_name.clone()),
+            Some(previous) if serde_json::to_value(previous).ok() != serde_json::to_value(target).ok() => diff.changed.push(target.vault_name.clone()),
+            Some(_) => {}
+        }
+    }
+    for target in &old.targets {
+        if !new.targets.iter().any(|t| t.vault_name == target.vault_name) {
+            diff.removed.push(target.vault_name.clone());
+        }
+    }
+    diff
+}
+
+/// Sections only read at startup keep their running value until a restart, so that code reading the
+/// config never disagrees with the long-lived providers, listeners and sinks. Returns the sections held back
+fn keep_restart_only(old: &BotConfig, new: &mut BotConfig) -> Vec<&'static str> {
+    let mut sections = Vec::new();
+    let global = &mut new.global;
+    if old.global.rpc_endpoint != global.rpc_endpoint || old.global.rpc_endpoints != global.rpc_endpoints {
+        sections.push("global.rpc_endpoint(s)");
+        global.rpc_endpoint = old.global.rpc_endpoint.clone();
+        global.rpc_endpoints = old.global.rpc_endpoints.clone();
+    }
+    if old.global.broadcast_rpc_endpoint != global.broadcast_rpc_endpoint || old.global.broadcast_rpc_endpoints != global.broadcast_rpc_endpoints {
+        sections.push("global.broadcast_rpc_endpoint(s)");
+        global.broadcast_rpc_endpoint = old.global.broadcast_rpc_endpoint.clone();
+        global.broadcast_rpc_endpoints = old.global.broadcast_rpc_endpoints.clone();
+    }
+    if old.global.websocket_endpoint != global.websocket_endpoint {
+        sections.push("global.websocket_endpoint");
+        global.websocket_endpoint = old.global.websocket_endpoint.clone();
+    }
+    if old.global.metrics_listen_address != global.metrics_listen_address {
+        sections.push("global.metrics_listen_address");
+        global.metrics_listen_address = old.global.metrics_listen_address.clone();
+    }
+    if old.global.control_listen_address != global.control_listen_address {
+        sections.push("global.control_listen_address");
+        global.control_listen_address = old.global.control_listen_address.clone();
+    }
+    if serde_json::to_value(&old.events).ok() != serde_json::to_value(&new.events).ok() {
+        sections.push("events");
+        new.events = old.events.clone();
+    }
+    if serde_json::to_value(&old.recorder).ok() != serde_json::to_value(&new.recorder).ok() {
+        sections.push("recorder");
+        new.recorder = old.recorder.clone();
+    }
+    sections
+}
+
+// ===== RELOAD =====
+
+/// Re-parse and validate the config file, swapping it in only if valid
+pub fn reload(path: &str, env: &EnvConfig, shared: &SharedConfig) -> Result<TargetDiff> {
+    let mut config = try_load_bot_config(path, Some(env))?;
+    let current = shared.current();
+    let diff = diff_targets(&current, &config);
+    for section in keep_restart_only(&current, &mut config) {
+        tracing::warn!("🔄 Config change in '{}' ignored until a restart, keeping the running value", section);
+    }
+    shared.swap(config);
+    Ok(diff)
+}
+
+#[derive(Debug, Clone, Copy)]
+enum ReloadTrigger {
+    FileChanged,
+    Hangup,
+}
+
+/// Watch the config file and SIGHUP, reloading the shared config on either
+pub fn spawn_config_watcher(path: &str, env: EnvConfig, shared: SharedConfig) -> Result<()> {
+    let (sender, mut receiver) = mpsc::channel::<ReloadTrigger>(16);
+
+    // Watch the parent directory: editors often replace the file rather than writing in place
+    let file = PathBuf::from(path);
+    let file_name = file.file_name().map(|n| n.to_os_string()).ok_or_else(|| Error::Config(format!("Invalid config path: {}", path)))?;
+    let directory = match file.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+        _ => Path::new(".").to_path_buf(),
+    };
+    let file_sender = sender.clone();
+    let mut watcher = notify::recommended_watcher(move |res: notify::Result<notify::Event>| {
+        if let Ok(event) = res
+            && (event.kind.is_modify() || event.kind.is_create())
+            && event.paths.iter().any(|p| p.file_name() == Some(file_name.as_os_str()))
+        {
+            let _ = file_sender.try_send(ReloadTrigger::FileChanged);
+        }
+    })
+    .map_err(|e| Error::Io(format!("Failed to watch {}: {}", path, e)))?;
+    watcher
+        .watch(&directory, RecursiveMode::NonRecursive)
+        .map_err(|e| Error::Io(format!("Failed to watch {}: {}", directory.display(), e)))?;
+
+    let mut hangup = signal(SignalKind::hangup())?;
+    tokio::spawn(async move {
+        while hangup.recv().await.is_some() {
+            let _ = sender.send(ReloadTrigger::Hangup).await;
+        }
+    });
+
+    tracing::info!("🔄 Watching {} for changes (or send SIGHUP to reload)", path);
+    let path = path.to_string();
+    tokio::spawn(async move {
+        // Keep the watcher alive for the lifetime of the task
+        let _watcher = watcher;
+        while let Some(trigger) = receiver.recv().await {
+            tokio::time::sleep(RELOAD_DEBOUNCE).await;
+            while receiver.try_recv().is_ok() {}
+            tracing::info!("🔄 Reloading {} ({:?})", path, trigger);
+            match reload(&path, &env, &shared) {
+                Ok(diff) if diff.is_empty() => tracing::info!("🔄 Config reloaded, no target changes"),
+                Ok(diff) => tracing::info!("🔄 Config reloaded | Added: {:?} | Removed: {:?} | Changed: {:?}", diff.added, diff.removed, diff.changed),
+                Err(e) => tracing::error!("🔄 Rejected config reload, keeping current config: {}", e),
+            }
+        }
+    });
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::tests::{config, target};
+
+    fn with_targets(targets: &[(&str, f64)]) -> BotConfig {
+        let mut config = config();
+        config.targets = targets
+            .iter()
+            .map(|(name, min_exec)| target(&format!("vault_name = \"{}\"\nmin_executable_spread_bps = {}", name, min_exec)))
+            .collect();
+        config
+    }
+
+    #[test]
+    fn diff_targets_by_vault_name() {
+        let old = with_targets(&[("kept", 5.0), ("changed", 5.0), ("removed", 5.0)]);
+        let new = with_targets(&[("kept", 5.0), ("changed", 8.0), ("added", 5.0)]);
+        let diff = diff_targets(&old, &new);
+        assert_eq!(diff.added, ["added"]);
+        assert_eq!(diff.removed, ["removed"]);
+        assert_eq!(diff.changed, ["changed"]);
+        assert!(diff_targets(&old, &old).is_empty());
+    }
+
+    #[test]
+    fn restart_only_sections_keep_their_running_value() {
+        let old = config();
+        let mut new = config();
+        new.global.rpc_endpoint = "http://localhost:9545".to_string();
+        new.global.metrics_listen_address = Some("0.0.0.0:9100".to_string());
+        new.gas.max_gas_price_gwei = 5.0;
+        let sections = keep_restart_only(&old, &mut new);
+        assert_eq!(sections, ["global.rpc_endpoint(s)", "global.metrics_listen_address"]);
+        assert_eq!(new.global.rpc_endpoint, old.global.rpc_endpoint);
+        assert_eq!(new.global.metrics_listen_address, None);
+        // Everything else reloads
+        assert_eq!(new.gas.max_gas_price_gwei, 5.0);
+        assert!(keep_restart_only(&old, &mut config()).is_empty());
+    }
+}
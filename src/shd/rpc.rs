@@ -0,0 +1,390 @@
+// Multi-endpoint RPC transport: scored failover for reads, parallel broadcast of signed transactions
+
+use alloy::{
+    network::Ethereum,
+    primitives::{Bytes, TxHash, keccak256},
+    providers::RootProvider,
+    rpc::{
+        client::RpcClient,
+        json_rpc::{RequestPacket, Response, ResponsePacket, ResponsePayload},
+    },
+    transports::{
+        TransportError, TransportErrorKind, TransportFut,
+        http::{Http, reqwest::Client},
+    },
+};
+use futures::{StreamExt, stream::FuturesUnordered};
+use std::{
+    collections::HashMap,
+    sync::{Arc, LazyLock, Mutex},
+    task::{Context, Poll},
+    time::{Duration, Instant},
+};
+use tower::Service;
+
+use crate::{
+    control::redact_url,
+    error::{Error, Result},
+    metrics::METRICS,
+    types::GlobalConfig,
+};
+
+/// Methods sent to every broadcast endpoint, all others are reads
+const BROADCAST_METHODS: [&str; 1] = ["eth_sendRawTransaction"];
+
+/// Errors of endpoints that already hold the transaction (received it from a peer)
+const KNOWN_TX_ERRORS: [&str; 3] = ["already known", "known transaction", "already imported"];
+
+/// Weight of the latest call in the latency and error rate averages
+const EWMA_ALPHA: f64 = 0.2;
+
+/// Health of every endpoint by URL, shared by all transports so per-call providers keep the history
+static HEALTH: LazyLock<Mutex<HashMap<String, EndpointHealth>>> = LazyLock::new(Default::default);
+
+// ===== HEALTH =====
+
+#[derive(Debug, Clone, Copy, Default)]
+struct EndpointHealth {
+    /// Moving average of the call latency (ms), 0 until the first call
+    latency_ms: f64,
+    /// Moving average of failed calls (0..1)
+    error_rate: f64,
+    consecutive_failures: u32,
+    /// Out of rotation until then (circuit open)
+    open_until: Option<Instant>,
+}
+
+impl EndpointHealth {
+    /// Lower is better, failing endpoints rank behind slower healthy ones
+    fn score(&self) -> f64 {
+        self.latency_ms * (1.0 + 10.0 * self.error_rate)
+    }
+
+    fn is_open(&self, now: Instant) -> bool {
+        self.open_until.is_some_and(|until| now < until)
+    }
+}
+
+fn health(url: &str) -> EndpointHealth {
+    HEALTH.lock().unwrap_or_else(|e| e.into_inner()).get(url).copied().unwrap_or_default()
+}
+
+fn record_success(url: &str, elapsed: Duration) {
+    let mut guard = HEALTH.lock().unwrap_or_else(|e| e.into_inner());
+    let health = guard.entry(url.to_string()).or_default();
+    let latency_ms = elapsed.as_secs_f64() * 1000.0;
+    health.latency_ms = if health.latency_ms == 0.0 {
+        latency_ms
+    } else {
+        health.latency_ms * (1.0 - EWMA_ALPHA) + latency_ms * EWMA_ALPHA
+    };
+    health.error_rate *= 1.0 - EWMA_ALPHA;
+    if health.open_until.take().is_some() {
+        tracing::info!("🔌 RPC {} back in rotation", redact_url(url));
+    }
+    health.consecutive_failures = 0;
+    METRICS.rpc_endpoint_up.with_label_values(&[&redact_url(url)]).set(1);
+}
+
+fn record_failure(url: &str, threshold: u32, cooldown: Duration) {
+    let mut guard = HEALTH.lock().unwrap_or_else(|e| e.into_inner());
+    let health = guard.entry(url.to_string()).or_default();
+    health.error_rate = health.error_rate * (1.0 - EWMA_ALPHA) + EWMA_ALPHA;
+    health.consecutive_failures += 1;
+    // Also re-opens a half-open circuit whose trial call failed
+    if health.consecutive_failures >= threshold {
+        health.open_until = Some(Instant::now() + cooldown);
+        tracing::warn!(
+            "🔌 RPC {} out of rotation for {}s after {} consecutive failures",
+            redact_url(url),
+            cooldown.as_secs(),
+            health.consecutive_failures
+        );
+        METRICS.rpc_endpoint_up.with_label_values(&[&redact_url(url)]).set(0);
+    }
+}
+
+// ===== TRANSPORT =====
+
+#[derive(Debug, Clone)]
+struct Endpoint {
+    url: String,
+    transport: Http<Client>,
+}
+
+/// Transport over several endpoints: reads go to the best healthy read endpoint and fail over on
+/// transport errors, raw transactions are sent to every broadcast endpoint at once
+#[derive(Debug, Clone)]
+pub struct MultiRpc {
+    read: Arc<Vec<Endpoint>>,
+    broadcast: Arc<Vec<Endpoint>>,
+    failure_threshold: u32,
+    cooldown: Duration,
+}
+
+impl MultiRpc {
+    pub fn new(global: &GlobalConfig) -> Result<Self> {
+        // Without a timeout a hung endpoint blocks its callers instead of failing over
+        let client = Client::builder()
+            .timeout(Duration::from_secs(global.rpc_timeout_secs))
+            .build()
+            .map_err(|e| Error::Config(format!("Failed to build RPC HTTP client: {}", e)))?;
+        let endpoints = |urls: Vec<String>| -> Result<Vec<Endpoint>> {
+            urls.into_iter()
+                .map(|url| {
+                    let parsed = url.parse().map_err(|e| Error::Config(format!("Invalid RPC URL '{}': {}", redact_url(&url), e)))?;
+                    Ok(Endpoint {
+                        url,
+                        transport: Http::with_client(client.clone(), parsed),
+                    })
+                })
+                .collect()
+        };
+        Ok(Self {
+            read: Arc::new(endpoints(global.read_endpoints())?),
+            broadcast: Arc::new(endpoints(global.broadcast_endpoints())?),
+            failure_threshold: global.rpc_failure_threshold,
+            cooldown: Duration::from_secs(global.rpc_cooldown_secs),
+        })
+    }
+
+    /// Endpoints in rotation by score, then those out of rotation as a last resort (soonest retry first)
+    fn ranked(endpoints: &[Endpoint]) -> Vec<Endpoint> {
+        let now = Instant::now();
+        let mut ranked: Vec<(Endpoint, EndpointHealth)> = endpoints.iter().map(|e| (e.clone(), health(&e.url))).collect();
+        ranked.sort_by(|(_, a), (_, b)| match (a.is_open(now), b.is_open(now)) {
+            (false, false) => a.score().total_cmp(&b.score()),
+            (false, true) => std::cmp::Ordering::Less,
+            (true, false) => std::cmp::Ordering::Greater,
+            (true, true) => a.open_until.cmp(&b.open_until),
+        });
+        ranked.into_iter().map(|(endpoint, _)| endpoint).collect()
+    }
+
+    async fn read(self, request: RequestPacket) -> Result<ResponsePacket, TransportError> {
+        let mut last_error = None;
+        for mut endpoint in Self::ranked(&self.read) {
+            let start = Instant::now();
+            match endpoint.transport.call(request.clone()).await {
+                Ok(response) => {
+                    record_success(&endpoint.url, start.elapsed());
+                    return Ok(response);
+                }
+                Err(e) => {
+                    tracing::warn!("RPC {} failed: {}", redact_url(&endpoint.url), e);
+                    record_failure(&endpoint.url, self.failure_threshold, self.cooldown);
+                    last_error = Some(e);
+                }
+            }
+        }
+        Err(last_error.unwrap_or_else(|| TransportErrorKind::custom_str("No RPC endpoint configured")))
+    }
+
+    /// Send to every broadcast endpoint and return the first accepted response, the other sends finish in
+    /// the background. Without any acceptance, "already known" answers count as accepted, then rejections
+    async fn broadcast(self, request: RequestPacket) -> Result<ResponsePacket, TransportError> {
+        let tx_hash = raw_transaction_hash(&request);
+        let mut sends: FuturesUnordered<_> = self
+            .broadcast
+            .iter()
+            .cloned()
+            .map(|mut endpoint| {
+                let request = request.clone();
+                async move {
+                    let start = Instant::now();
+                    let result = endpoint.transport.call(request).await;
+                    (endpoint.url, result, start.elapsed())
+                }
+            })
+            .collect();
+
+        let mut known = false;
+        let mut rejected = None;
+        let mut last_error = None;
+        while let Some((url, result, elapsed)) = sends.next().await {
+            match self.settle(tx_hash, &url, result, elapsed) {
+                Sent::Accepted(response) => {
+                    // Keep the remaining sends going (and their endpoints scored) without waiting for them
+                    tokio::spawn(async move {
+                        while let Some((url, result, elapsed)) = sends.next().await {
+                            self.settle(tx_hash, &url, result, elapsed);
+                        }
+                    });
+                    return Ok(response);
+                }
+                Sent::Known => known = true,
+                Sent::Rejected(response) => {
+                    rejected.get_or_insert(response);
+                }
+                Sent::Failed(e) => last_error = Some(e),
+            }
+        }
+
+        // Only "already known" answers: the transaction is in the mempool under its own hash
+        if known && let Some(response) = tx_hash.and_then(|hash| known_response(&request, hash)) {
+            return Ok(response);
+        }
+        match (rejected, last_error) {
+            (Some(response), _) => Ok(response),
+            (None, Some(e)) => Err(e),
+            (None, None) => Err(TransportErrorKind::custom_str("No broadcast endpoint configured")),
+        }
+    }
+
+    /// Classify the answer of a broadcast endpoint and record its health
+    fn settle(&self, tx_hash: Option<TxHash>, url: &str, result: Result<ResponsePacket, TransportError>, elapsed: Duration) -> Sent {
+        match result {
+            Ok(response) => {
+                record_success(url, elapsed);
+                if response.is_success() {
+                    // Every endpoint returns the hash of the same signed bytes
+                    if let (Some(expected), Some(ResponsePayload::Success(value))) = (tx_hash, response.single_payload())
+                        && serde_json::from_str::<TxHash>(value.get()).ok() != Some(expected)
+                    {
+                        tracing::warn!("📡 {} returned an unexpected hash for 0x{:x}", redact_url(url), expected);
+                    }
+                    Sent::Accepted(response)
+                } else if is_known(&response) {
+                    Sent::Known
+                } else {
+                    tracing::debug!("📡 {} rejected transaction: {:?}", redact_url(url), response.first_error_message());
+                    Sent::Rejected(response)
+                }
+            }
+            Err(e) => {
+                tracing::warn!("📡 Broadcast to {} failed: {}", redact_url(url), e);
+                record_failure(url, self.failure_threshold, self.cooldown);
+                Sent::Failed(e)
+            }
+        }
+    }
+}
+
+/// Answer of one broadcast endpoint
+enum Sent {
+    Accepted(ResponsePacket),
+    /// The endpoint already holds the transaction (received it from a peer)
+    Known,
+    Rejected(ResponsePacket),
+    Failed(TransportError),
+}
+
+fn is_known(response: &ResponsePacket) -> bool {
+    response.first_error_message().is_some_and(|m| KNOWN_TX_ERRORS.iter().any(|k| m.to_lowercase().contains(k)))
+}
+
+/// Successful eth_sendRawTransaction response carrying `hash`, for transactions endpoints already hold
+fn known_response(request: &RequestPacket, hash: TxHash) -> Option<ResponsePacket> {
+    let RequestPacket::Single(single) = request else {
+        return None;
+    };
+    let value = serde_json::value::to_raw_value(&hash).ok()?;
+    Some(ResponsePacket::Single(Response {
+        id: single.id().clone(),
+        payload: ResponsePayload::Success(value),
+    }))
+}
+
+fn is_broadcast(request: &RequestPacket) -> bool {
+    matches!(request, RequestPacket::Single(single) if BROADCAST_METHODS.contains(&single.method()))
+}
+
+/// Hash of the signed transaction carried by an eth_sendRawTransaction request
+fn raw_transaction_hash(request: &RequestPacket) -> Option<TxHash> {
+    let RequestPacket::Single(single) = request else {
+        return None;
+    };
+    let (raw,): (Bytes,) = serde_json::from_str(single.params()?.get()).ok()?;
+    Some(keccak256(&raw))
+}
+
+impl Service<RequestPacket> for MultiRpc {
+    type Response = ResponsePacket;
+    type Error = TransportError;
+    type Future = TransportFut<'static>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: RequestPacket) -> Self::Future {
+        let this = self.clone();
+        Box::pin(async move { if is_broadcast(&request) { this.broadcast(request).await } else { this.read(request).await } })
+    }
+}
+
+// ===== CONSTRUCTORS =====
+
+/// RPC client over the configured read and broadcast endpoints (wrap with a wallet to send transactions)
+pub fn client(global: &GlobalConfig) -> Result<RpcClient> {
+    Ok(RpcClient::new(MultiRpc::new(global)?, false))
+}
+
+/// Read-only provider over the configured endpoints
+pub fn provider(global: &GlobalConfig) -> Result<RootProvider<Ethereum>> {
+    Ok(RootProvider::new(client(global)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::rpc::json_rpc::{ErrorPayload, Id, Request};
+
+    fn endpoint(url: &str) -> Endpoint {
+        Endpoint {
+            url: url.to_string(),
+            transport: Http::new(url.parse().unwrap()),
+        }
+    }
+
+    fn send_raw(raw: &Bytes) -> RequestPacket {
+        RequestPacket::Single(Request::new("eth_sendRawTransaction", Id::Number(7), (raw.clone(),)).serialize().unwrap())
+    }
+
+    #[test]
+    fn ranked_by_score_with_open_circuits_last() {
+        let (slow, fast, failing) = ("http://ranked-slow", "http://ranked-fast", "http://ranked-failing");
+        record_success(slow, Duration::from_millis(200));
+        record_success(fast, Duration::from_millis(10));
+        record_success(failing, Duration::from_millis(1));
+        record_failure(failing, 1, Duration::from_secs(60));
+        let ranked = MultiRpc::ranked(&[endpoint(failing), endpoint(slow), endpoint(fast)]);
+        let urls: Vec<&str> = ranked.iter().map(|e| e.url.as_str()).collect();
+        assert_eq!(urls, [fast, slow, failing]);
+    }
+
+    #[test]
+    fn raw_transaction_hash_of_signed_bytes() {
+        let raw = Bytes::from_static(&[0x02, 0xf8, 0x01, 0x02, 0x03]);
+        assert_eq!(raw_transaction_hash(&send_raw(&raw)), Some(keccak256(&raw)));
+        let read = RequestPacket::Single(Request::new("eth_blockNumber", Id::Number(1), ()).serialize().unwrap());
+        assert_eq!(raw_transaction_hash(&read), None);
+    }
+
+    #[test]
+    fn already_known_answers_merge_into_the_transaction_hash() {
+        let raw = Bytes::from_static(&[0x02, 0xf8, 0x01]);
+        let request = send_raw(&raw);
+        let known = ResponsePacket::Single(Response {
+            id: Id::Number(7),
+            payload: ResponsePayload::Failure(ErrorPayload {
+                code: -32000,
+                message: "Already Known".into(),
+                data: None,
+            }),
+        });
+        assert!(is_known(&known));
+
+        let response = known_response(&request, keccak256(&raw)).unwrap();
+        assert!(response.is_success());
+        let ResponsePacket::Single(Response {
+            id,
+            payload: ResponsePayload::Success(value),
+        }) = response
+        else {
+            panic!("expected a single success");
+        };
+        assert_eq!(id, Id::Number(7));
+        assert_eq!(serde_json::from_str::<TxHash>(value.get()).unwrap(), keccak256(&raw));
+    }
+}
@@ -0,0 +1,402 @@
+// Transaction simulation before submission: local revm execution over lazily fetched state with the
+// `simulation` feature, eth_call/eth_estimateGas on the node otherwise
+
+use alloy::{
+    eips::{BlockId, BlockNumberOrTag},
+    primitives::Bytes,
+    providers::Provider,
+    rpc::types::TransactionRequest,
+    sol_types::{SolCall, decode_revert_reason},
+};
+
+use crate::error::{Error, Result};
+
+/// Outcome of a transaction run against the pinned block
+#[derive(Debug, Clone)]
+pub struct Simulation {
+    pub success: bool,
+    pub gas_used: u64,
+    /// Return data, or revert data when the transaction reverted
+    pub output: Bytes,
+    /// Decoded revert reason (Error(string), Panic(uint256) or raw revert data), or the halt reason
+    pub revert_reason: Option<String>,
+}
+
+impl Simulation {
+    fn reverted(gas_used: u64, output: Bytes) -> Self {
+        Self {
+            success: false,
+            gas_used,
+            revert_reason: Some(decode_revert_reason(&output).unwrap_or_else(|| format!("0x{}", hex::encode(&output)))),
+            output,
+        }
+    }
+
+    /// Decode the return value of `C` (e.g. the amount out of a swap)
+    pub fn decode_return<C: SolCall>(&self) -> Result<C::Return> {
+        if !self.success {
+            return Err(Error::Execution(format!("Simulation reverted: {}", self.revert_reason.as_deref().unwrap_or("unknown reason"))));
+        }
+        C::abi_decode_returns(&self.output).map_err(|e| Error::Execution(format!("Failed to decode simulated output: {}", e)))
+    }
+}
+
+/// Fork of the latest block, running transactions as if included in the next one
+#[derive(Debug, Clone)]
+pub struct Simulator<P> {
+    provider: P,
+    block_number: u64,
+    #[cfg(feature = "simulation")]
+    fork: local::Fork<P>,
+}
+
+impl<P: Provider + Clone + 'static> Simulator<P> {
+    /// Pin the latest block of `provider` (the live RPC, or a local anvil node in tests)
+    pub async fn latest(provider: P) -> Result<Self> {
+        let latest = provider
+            .get_block_by_number(BlockNumberOrTag::Latest)
+            .await?
+            .ok_or_else(|| Error::Rpc("Latest block not found".to_string()))?;
+        Ok(Self {
+            #[cfg(feature = "simulation")]
+            fork: local::Fork::new(provider.clone(), &latest.header, provider.get_chain_id().await?),
+            provider,
+            block_number: latest.header.number,
+        })
+    }
+
+    /// Block the simulations are based on
+    pub fn block_number(&self) -> u64 {
+        self.block_number
+    }
+
+    /// Run `request` (from, to, input, value, gas and fees as they will be signed). The nonce is not
+    /// checked, it is only assigned when sending
+    pub async fn simulate(&self, request: TransactionRequest) -> Result<Simulation> {
+        #[cfg(feature = "simulation")]
+        {
+            self.fork.run(request).await
+        }
+        #[cfg(not(feature = "simulation"))]
+        {
+            self.call(request).await
+        }
+    }
+
+    /// Run independent requests (e.g. candidate sizes of a trade) in parallel on the same block
+    pub async fn simulate_many(&self, requests: Vec<TransactionRequest>) -> Vec<Result<Simulation>> {
+        futures::future::join_all(requests.into_iter().map(|request| self.simulate(request))).await
+    }
+
+    /// Node fallback: eth_call for the output or revert data, eth_estimateGas for the gas
+    #[cfg_attr(feature = "simulation", allow(dead_code))]
+    async fn call(&self, request: TransactionRequest) -> Result<Simulation> {
+        let block = BlockId::number(self.block_number);
+        match self.provider.call(request.clone()).block(block).await {
+            Ok(output) => Ok(Simulation {
+                success: true,
+                gas_used: self.provider.estimate_gas(request).block(block).await?,
+                output,
+                revert_reason: None,
+            }),
+            Err(e) => match e.as_error_resp().and_then(|payload| payload.as_revert_data()) {
+                Some(data) => Ok(Simulation::reverted(0, data)),
+                None => Err(e.into()),
+            },
+        }
+    }
+}
+
+// ===== LOCAL REVM FORK =====
+
+#[cfg(feature = "simulation")]
+mod local {
+    use alloy::{
+        consensus::Header,
+        eips::{BlockId, BlockNumberOrTag},
+        primitives::{Address, B256, Bytes, TxKind, U256},
+        providers::Provider,
+        rpc::types::TransactionRequest,
+    };
+    use revm::{
+        Context, ExecuteEvm, MainBuilder, MainContext,
+        context::{BlockEnv, TxEnv, result::ExecutionResult},
+        database_interface::{DBErrorMarker, DatabaseRef, WrapDatabaseRef},
+        primitives::{KECCAK_EMPTY, hardfork::SpecId},
+        state::{AccountInfo, Bytecode},
+    };
+    use std::{
+        collections::HashMap,
+        future::IntoFuture,
+        sync::{Arc, Mutex},
+    };
+    use tokio::runtime::Handle;
+
+    use super::Simulation;
+    use crate::error::{Error, Result};
+
+    /// Gas limit of requests simulated without one (e.g. flash size candidates). revm checks the sender can
+    /// pay limit x fee, the block gas limit would reject wallets only funded for the real transaction
+    const UNGASSED_GAS_LIMIT: u64 = 3_000_000;
+
+    impl DBErrorMarker for Error {}
+
+    /// revm database reading accounts, code and storage from the node at a pinned block on first access.
+    /// Everything fetched is cached, so simulations sharing it (e.g. candidate sizes) only pay for new slots
+    #[derive(Debug)]
+    struct RpcDatabase<P> {
+        provider: P,
+        block: BlockId,
+        handle: Handle,
+        accounts: Mutex<HashMap<Address, AccountInfo>>,
+        storage: Mutex<HashMap<(Address, U256), U256>>,
+        code: Mutex<HashMap<B256, Bytecode>>,
+        block_hashes: Mutex<HashMap<u64, B256>>,
+    }
+
+    fn lock_error<T>(_: std::sync::PoisonError<T>) -> Error {
+        Error::Execution("Simulation state lock poisoned".to_string())
+    }
+
+    impl<P: Provider> DatabaseRef for RpcDatabase<P> {
+        type Error = Error;
+
+        fn basic_ref(&self, address: Address) -> Result<Option<AccountInfo>> {
+            if let Some(account) = self.accounts.lock().map_err(lock_error)?.get(&address) {
+                return Ok(Some(account.clone()));
+            }
+            let (balance, nonce, code) = self.handle.block_on(async {
+                tokio::try_join!(
+                    self.provider.get_balance(address).block_id(self.block).into_future(),
+                    self.provider.get_transaction_count(address).block_id(self.block).into_future(),
+                    self.provider.get_code_at(address).block_id(self.block).into_future(),
+                )
+            })?;
+
+            let account = if code.is_empty() {
+                AccountInfo::new(balance, nonce, KECCAK_EMPTY, Bytecode::default())
+            } else {
+                let bytecode = Bytecode::new_raw(code);
+                let code_hash = bytecode.hash_slow();
+                self.code.lock().map_err(lock_error)?.insert(code_hash, bytecode.clone());
+                AccountInfo::new(balance, nonce, code_hash, bytecode)
+            };
+            self.accounts.lock().map_err(lock_error)?.insert(address, account.clone());
+            Ok(Some(account))
+        }
+
+        fn code_by_hash_ref(&self, code_hash: B256) -> Result<Bytecode> {
+            // Code is loaded with its account, which revm always reads first
+            Ok(self.code.lock().map_err(lock_error)?.get(&code_hash).cloned().unwrap_or_default())
+        }
+
+        fn storage_ref(&self, address: Address, index: U256) -> Result<U256> {
+            if let Some(value) = self.storage.lock().map_err(lock_error)?.get(&(address, index)) {
+                return Ok(*value);
+            }
+            let value = self.handle.block_on(self.provider.get_storage_at(address, index).block_id(self.block).into_future())?;
+            self.storage.lock().map_err(lock_error)?.insert((address, index), value);
+            Ok(value)
+        }
+
+        fn block_hash_ref(&self, number: u64) -> Result<B256> {
+            if let Some(hash) = self.block_hashes.lock().map_err(lock_error)?.get(&number) {
+                return Ok(*hash);
+            }
+            let block = self
+                .handle
+                .block_on(self.provider.get_block_by_number(BlockNumberOrTag::Number(number)).into_future())?
+                .ok_or_else(|| Error::Rpc(format!("Block {} not found", number)))?;
+            self.block_hashes.lock().map_err(lock_error)?.insert(number, block.header.hash);
+            Ok(block.header.hash)
+        }
+    }
+
+    /// State and block environment of the fork, shared by its simulations
+    #[derive(Debug)]
+    pub(super) struct Fork<P> {
+        db: Arc<RpcDatabase<P>>,
+        block: BlockEnv,
+        chain_id: u64,
+    }
+
+    impl<P> Clone for Fork<P> {
+        fn clone(&self) -> Self {
+            Self {
+                db: self.db.clone(),
+                block: self.block.clone(),
+                chain_id: self.chain_id,
+            }
+        }
+    }
+
+    impl<P: Provider + 'static> Fork<P> {
+        /// Must be created inside the tokio runtime, simulations block on it from worker threads
+        pub(super) fn new(provider: P, latest: &Header, chain_id: u64) -> Self {
+            let block = BlockEnv {
+                number: latest.number + 1,
+                beneficiary: latest.beneficiary,
+                timestamp: latest.timestamp + 1,
+                gas_limit: latest.gas_limit,
+                basefee: latest.base_fee_per_gas.unwrap_or_default(),
+                prevrandao: Some(latest.mix_hash),
+                ..BlockEnv::default()
+            };
+            let db = RpcDatabase {
+                provider,
+                block: BlockId::number(latest.number),
+                handle: Handle::current(),
+                accounts: Mutex::default(),
+                storage: Mutex::default(),
+                code: Mutex::default(),
+                block_hashes: Mutex::default(),
+            };
+            Self { db: Arc::new(db), block, chain_id }
+        }
+
+        pub(super) async fn run(&self, request: TransactionRequest) -> Result<Simulation> {
+            let tx = self.tx_env(&request)?;
+            let fork = self.clone();
+            tokio::task::spawn_blocking(move || {
+                let mut evm = Context::mainnet()
+                    .with_db(WrapDatabaseRef(fork.db))
+                    .modify_cfg_chained(|cfg| {
+                        cfg.chain_id = fork.chain_id;
+                        cfg.spec = SpecId::CANCUN;
+                        cfg.disable_nonce_check = true;
+                    })
+                    .with_block(fork.block)
+                    .build_mainnet();
+                let outcome = evm.transact(tx).map_err(|e| Error::Execution(format!("Simulation failed: {}", e)))?;
+                Ok(match outcome.result {
+                    ExecutionResult::Success { gas_used, output, .. } => Simulation {
+                        success: true,
+                        gas_used,
+                        output: output.into_data(),
+                        revert_reason: None,
+                    },
+                    ExecutionResult::Revert { gas_used, output } => Simulation::reverted(gas_used, output),
+                    ExecutionResult::Halt { reason, gas_used } => Simulation {
+                        success: false,
+                        gas_used,
+                        output: Bytes::new(),
+                        revert_reason: Some(format!("{:?}", reason)),
+                    },
+                })
+            })
+            .await
+            .map_err(|e| Error::Execution(format!("Simulation task failed: {}", e)))?
+        }
+
+        fn tx_env(&self, request: &TransactionRequest) -> Result<TxEnv> {
+            let caller = request.from.ok_or_else(|| Error::Execution("Simulated transaction has no sender".to_string()))?;
+            Ok(TxEnv {
+                tx_type: if request.max_priority_fee_per_gas.is_some() { 2 } else { 0 },
+                caller,
+                gas_limit: request.gas.unwrap_or(UNGASSED_GAS_LIMIT.min(self.block.gas_limit)),
+                // Unpriced requests pay the base fee
+                gas_price: request.max_fee_per_gas.or(request.gas_price).unwrap_or(self.block.basefee as u128),
+                gas_priority_fee: request.max_priority_fee_per_gas,
+                kind: request.to.unwrap_or(TxKind::Create),
+                value: request.value.unwrap_or_default(),
+                data: request.input.input().cloned().unwrap_or_default(),
+                chain_id: Some(self.chain_id),
+                ..TxEnv::default()
+            })
+        }
+    }
+}
+
+// Against a local anvil node: cargo test --features simulation -- --ignored
+#[cfg(all(test, feature = "simulation"))]
+mod tests {
+    use super::*;
+    use alloy::{
+        network::Ethereum,
+        primitives::{Address, TxKind, U256, address},
+        providers::RootProvider,
+        sol_types::{Revert, SolError},
+    };
+    use std::{
+        io::{BufRead, BufReader},
+        process::{Child, Command, Stdio},
+    };
+
+    // First prefunded anvil account
+    const SENDER: Address = address!("0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266");
+    const REASON: &str = "HyperArb: insufficient profit";
+
+    /// Local anvil node, killed on drop
+    struct Anvil(Child);
+
+    impl Drop for Anvil {
+        fn drop(&mut self) {
+            let _ = self.0.kill();
+        }
+    }
+
+    /// Anvil on a free port with its endpoint
+    fn anvil() -> (Anvil, String) {
+        let port = std::net::TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port();
+        let mut child = Command::new("anvil").args(["--port", &port.to_string()]).stdout(Stdio::piped()).spawn().expect("anvil on PATH");
+        let stdout = child.stdout.take().unwrap();
+        let node = Anvil(child);
+        BufReader::new(stdout)
+            .lines()
+            .map_while(|line| line.ok())
+            .find(|line| line.contains("Listening on"))
+            .expect("anvil started");
+        (node, format!("http://127.0.0.1:{}", port))
+    }
+
+    // Init code reverting with Error(REASON): CODECOPY the payload appended after the 12 code bytes, then REVERT
+    fn reverting_init_code() -> Bytes {
+        let payload = Revert::from(REASON).abi_encode();
+        let len = payload.len() as u8;
+        let mut code = vec![0x60, len, 0x60, 0x0c, 0x60, 0x00, 0x39, 0x60, len, 0x60, 0x00, 0xfd];
+        code.extend_from_slice(&payload);
+        code.into()
+    }
+
+    async fn simulator(endpoint: &str) -> Simulator<RootProvider<Ethereum>> {
+        let provider = RootProvider::<Ethereum>::new_http(endpoint.parse().unwrap());
+        Simulator::latest(provider).await.unwrap()
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    #[ignore = "needs anvil (Foundry) on PATH"]
+    async fn transfer_succeeds_with_node_gas() {
+        let (_node, endpoint) = anvil();
+        let simulator = simulator(&endpoint).await;
+        let request = TransactionRequest::default().from(SENDER).to(Address::repeat_byte(0xde)).value(U256::from(1));
+
+        let simulation = simulator.simulate(request.clone()).await.unwrap();
+        assert!(simulation.success);
+        assert!(simulation.revert_reason.is_none());
+        assert_eq!(simulation.gas_used, 21_000);
+        // Same gas as eth_estimateGas on the node
+        assert_eq!(simulator.call(request).await.unwrap().gas_used, simulation.gas_used);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    #[ignore = "needs anvil (Foundry) on PATH"]
+    async fn revert_reason_decoded() {
+        let (_node, endpoint) = anvil();
+        let simulator = simulator(&endpoint).await;
+        let mut request = TransactionRequest::default().from(SENDER).input(reverting_init_code().into());
+        request.to = Some(TxKind::Create);
+
+        let simulation = simulator.simulate(request.clone()).await.unwrap();
+        assert!(!simulation.success);
+        assert!(simulation.revert_reason.as_deref().is_some_and(|reason| reason.contains(REASON)), "{:?}", simulation.revert_reason);
+        // Intrinsic cost of a contract creation plus the init code run
+        assert!(simulation.gas_used > 53_000);
+        assert_eq!(simulation.output, Bytes::from(Revert::from(REASON).abi_encode()));
+        assert!(simulation.decode_return::<crate::sol::IERC20::decimalsCall>().is_err());
+
+        // The node fallback decodes the same reason
+        let node = simulator.call(request).await.unwrap();
+        assert!(!node.success);
+        assert_eq!(node.revert_reason, simulation.revert_reason);
+    }
+}
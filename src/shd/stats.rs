@@ -0,0 +1,548 @@
+// Rolling spread statistics, z-score mean-reversion signals and adaptive thresholds
+
+use alloy::primitives::Address;
+use std::{
+    collections::{HashMap, VecDeque},
+    str::FromStr,
+};
+
+use crate::{
+    dex::{
+        pnl::TradePnl,
+        swap::{BestOpportunity, TradeOrder},
+    },
+    types::{AdaptiveThresholdConfig, MeanReversionConfig},
+};
+
+// ===== WINDOW =====
+
+/// Mean, dispersion and reversion speed of the spread over the window
+#[derive(Debug, Clone, Copy)]
+pub struct SpreadStats {
+    pub mean_bps: f64,
+    pub stdev_bps: f64,
+    /// Samples for a deviation to halve (AR(1) fit), None when the spread does not revert
+    pub half_life: Option<f64>,
+    pub samples: usize,
+}
+
+impl SpreadStats {
+    /// Deviation of `spread_bps` from the mean in standard deviations (0.0 on a flat window)
+    pub fn z_score(&self, spread_bps: f64) -> f64 {
+        if self.stdev_bps > 0.0 { (spread_bps - self.mean_bps) / self.stdev_bps } else { 0.0 }
+    }
+}
+
+/// Last spreads of a pool, one per block
+#[derive(Debug, Clone, Default)]
+pub struct SpreadWindow {
+    samples: VecDeque<f64>,
+}
+
+impl SpreadWindow {
+    /// Append a spread, dropping the oldest ones beyond `capacity`
+    pub fn push(&mut self, spread_bps: f64, capacity: usize) {
+        self.samples.push_back(spread_bps);
+        while self.samples.len() > capacity {
+            self.samples.pop_front();
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    /// Statistics of the window, None with fewer than two samples
+    pub fn stats(&self) -> Option<SpreadStats> {
+        let n = self.samples.len();
+        if n < 2 {
+            return None;
+        }
+        let mean = self.samples.iter().sum::<f64>() / n as f64;
+        let variance = self.samples.iter().map(|s| (s - mean).powi(2)).sum::<f64>() / (n - 1) as f64;
+
+        // Regress Δs_t on s_{t-1}: Δs = α + β·s_{t-1}, reverting when -1 < β < 0
+        let lagged: Vec<f64> = self.samples.iter().take(n - 1).copied().collect();
+        let deltas: Vec<f64> = self.samples.iter().zip(self.samples.iter().skip(1)).map(|(prev, next)| next - prev).collect();
+        let lag_mean = lagged.iter().sum::<f64>() / lagged.len() as f64;
+        let delta_mean = deltas.iter().sum::<f64>() / deltas.len() as f64;
+        let covariance: f64 = lagged.iter().zip(&deltas).map(|(x, y)| (x - lag_mean) * (y - delta_mean)).sum();
+        let lag_variance: f64 = lagged.iter().map(|x| (x - lag_mean).powi(2)).sum();
+        let half_life = if lag_variance > 0.0 {
+            let beta = covariance / lag_variance;
+            (beta < 0.0 && beta > -1.0).then(|| -std::f64::consts::LN_2 / (1.0 + beta).ln())
+        } else {
+            None
+        };
+
+        Some(SpreadStats {
+            mean_bps: mean,
+            stdev_bps: variance.sqrt(),
+            half_life,
+            samples: n,
+        })
+    }
+}
+
+// ===== SIGNALS =====
+
+/// Action on a pool for the current spread
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Signal {
+    Hold,
+    /// Open (or add to) a position, buying base when the spread is abnormally low
+    Enter {
+        is_buy: bool,
+    },
+    /// Close the open position (sell a long, buy back a short)
+    Exit {
+        is_buy: bool,
+    },
+}
+
+/// Signal for `spread_bps` given the window statistics and the open base position of the pool.
+/// Exits once the z-score is back within `exit_z`, entries need |z| >= `entry_z`, a fast enough
+/// reversion and a deviation from the mean that pays for the pool fee.
+pub fn signal(config: &MeanReversionConfig, stats: &SpreadStats, spread_bps: f64, fee_bps: f64, position_base: f64) -> Signal {
+    if stats.samples < config.min_samples || stats.stdev_bps <= 0.0 {
+        return Signal::Hold;
+    }
+    let z = stats.z_score(spread_bps);
+
+    if position_base > 0.0 && z >= -config.exit_z {
+        return Signal::Exit { is_buy: false };
+    }
+    if position_base < 0.0 && z <= config.exit_z {
+        return Signal::Exit { is_buy: true };
+    }
+
+    let reverts = config.max_half_life <= 0.0 || stats.half_life.is_some_and(|h| h <= config.max_half_life);
+    let pays_fee = (spread_bps - stats.mean_bps).abs() >= fee_bps;
+    if !reverts || !pays_fee {
+        return Signal::Hold;
+    }
+    if z <= -config.entry_z && position_base >= 0.0 {
+        Signal::Enter { is_buy: true }
+    } else if z >= config.entry_z && position_base <= 0.0 {
+        Signal::Enter { is_buy: false }
+    } else {
+        Signal::Hold
+    }
+}
+
+/// Order of a signal, None for Hold. Exits sell the base held, or spend enough quote (fee included) to buy
+/// back the base sold; entries spend trade_size_usd of quote, or sell its value of base at `reference_price`
+pub fn signal_order(config: &MeanReversionConfig, signal: Signal, position_base: f64, pool_price: f64, fee_bps: f64, reference_price: f64) -> Option<TradeOrder> {
+    match signal {
+        Signal::Hold => None,
+        Signal::Exit { is_buy } => {
+            let amount_in = if is_buy {
+                position_base.abs() * pool_price * (1.0 + fee_bps / 10_000.0)
+            } else {
+                position_base.abs()
+            };
+            Some(TradeOrder {
+                is_buy,
+                amount_in: Some(amount_in),
+                min_amount_out: None,
+            })
+        }
+        Signal::Enter { is_buy } => {
+            let amount_in = if is_buy { config.trade_size_usd } else { config.trade_size_usd / reference_price };
+            Some(TradeOrder {
+                is_buy,
+                amount_in: Some(amount_in),
+                min_amount_out: None,
+            })
+        }
+    }
+}
+
+/// Decision of mean-reversion mode on one pool
+#[derive(Debug, Clone, Copy)]
+pub enum ReversionAction {
+    Hold,
+    /// Entry skipped: the open exposure plus one trade would exceed max_position_usd
+    PositionLimit {
+        exposure_usd: f64,
+    },
+    Trade {
+        signal: Signal,
+        order: TradeOrder,
+    },
+}
+
+// ===== STATE =====
+
+/// Spread windows and open base positions of a target, by pool
+#[derive(Debug, Clone, Default)]
+pub struct MeanReversionState {
+    pub windows: HashMap<Address, SpreadWindow>,
+    /// Base tokens bought (positive) or sold (negative) by the strategy on each pool
+    pub positions: HashMap<Address, f64>,
+}
+
+impl MeanReversionState {
+    /// Positions rebuilt from the recorded fills of a target (oldest first), windows start empty
+    pub fn replay<'a>(trades: impl IntoIterator<Item = &'a TradePnl>) -> Self {
+        let mut state = Self::default();
+        for pnl in trades.into_iter().filter(|p| p.mean_reversion && p.success) {
+            match Address::from_str(&pnl.pool) {
+                Ok(pool) => state.fill(pool, pnl.is_buy, pnl.base_amount),
+                Err(e) => tracing::debug!("Skipping mean-reversion fill on {}: {}", pnl.pool, e),
+            }
+        }
+        state
+    }
+
+    /// Record the spread of a pool and return the statistics including it
+    pub fn observe(&mut self, pool: Address, spread_bps: f64, capacity: usize) -> Option<SpreadStats> {
+        let window = self.windows.entry(pool).or_default();
+        window.push(spread_bps, capacity);
+        window.stats()
+    }
+
+    pub fn position(&self, pool: Address) -> f64 {
+        self.positions.get(&pool).copied().unwrap_or_default()
+    }
+
+    /// Apply a fill of `base_amount` tokens in the trade direction
+    pub fn fill(&mut self, pool: Address, is_buy: bool, base_amount: f64) {
+        let position = self.positions.entry(pool).or_default();
+        *position += if is_buy { base_amount } else { -base_amount };
+    }
+
+    /// Total open position value in USD at `base_price`
+    pub fn exposure_usd(&self, base_price: f64) -> f64 {
+        self.positions.values().map(|p| p.abs() * base_price).sum()
+    }
+
+    /// Signal and order on a pool with its open position. Leftovers worth less than `min_trade_value_usd`
+    /// are cleared and count as flat, entries respect the position limit across pools
+    pub fn action(
+        &mut self, config: &MeanReversionConfig, pool: Address, stats: &SpreadStats, opportunity: &BestOpportunity, reference_price: f64, min_trade_value_usd: f64,
+    ) -> (f64, ReversionAction) {
+        let mut position = self.position(pool);
+        if position != 0.0 && position.abs() * reference_price < min_trade_value_usd {
+            self.positions.remove(&pool);
+            position = 0.0;
+        }
+        let signal = signal(config, stats, opportunity.spread_bps, opportunity.fee_bps, position);
+        if matches!(signal, Signal::Enter { .. }) {
+            let exposure_usd = self.exposure_usd(reference_price);
+            if exposure_usd + config.trade_size_usd > config.max_position_usd {
+                return (position, ReversionAction::PositionLimit { exposure_usd });
+            }
+        }
+        let action = match signal_order(config, signal, position, opportunity.pool_price, opportunity.fee_bps, reference_price) {
+            Some(order) => ReversionAction::Trade { signal, order },
+            None => ReversionAction::Hold,
+        };
+        (position, action)
+    }
+}
+
+// ===== ADAPTIVE THRESHOLD =====
+
+/// Prices needed for a sample standard deviation of the returns (two returns)
+pub const MIN_VOL_SAMPLES: usize = 3;
+
+/// Recent reference prices (by block) and fill slippages of a target
+#[derive(Debug, Clone, Default)]
+pub struct ThresholdState {
+    prices: VecDeque<(u64, f64)>,
+    slippages: VecDeque<f64>,
+}
+
+/// Executable threshold with the terms it was built from
+#[derive(Debug, Clone, Copy)]
+pub struct AdaptiveThreshold {
+    pub threshold_bps: f64,
+    pub realised_vol_bps: f64,
+    pub mean_slippage_bps: f64,
+}
+
+impl ThresholdState {
+    /// Record the reference price of `block`, one sample per block (passes skipped by the poll interval leave gaps)
+    pub fn observe_price(&mut self, block: u64, price: f64, capacity: usize) {
+        if price > 0.0 && self.prices.back().is_none_or(|(last, _)| block > *last) {
+            self.prices.push_back((block, price));
+        }
+        while self.prices.len() > capacity {
+            self.prices.pop_front();
+        }
+    }
+
+    pub fn observe_slippage(&mut self, slippage_bps: f64, capacity: usize) {
+        self.slippages.push_back(slippage_bps);
+        while self.slippages.len() > capacity {
+            self.slippages.pop_front();
+        }
+    }
+
+    /// Per-block standard deviation of the log returns (bps), None with fewer than `min_samples` prices (at least 3).
+    /// Returns over a gap of n blocks are scaled by 1/sqrt(n), the variance of a random walk growing with time
+    pub fn realised_vol_bps(&self, min_samples: usize) -> Option<f64> {
+        if self.prices.len() < min_samples.max(MIN_VOL_SAMPLES) {
+            return None;
+        }
+        let returns: Vec<f64> = self
+            .prices
+            .iter()
+            .zip(self.prices.iter().skip(1))
+            .map(|((prev_block, prev), (block, next))| (next / prev).ln() / ((block - prev_block) as f64).sqrt())
+            .collect();
+        let mean = returns.iter().sum::<f64>() / returns.len() as f64;
+        let variance = returns.iter().map(|r| (r - mean).powi(2)).sum::<f64>() / (returns.len() - 1) as f64;
+        Some(variance.sqrt() * 10_000.0)
+    }
+
+    /// Mean slippage of the recent fills (bps, positive = worse than expected), 0.0 without fills
+    pub fn mean_slippage_bps(&self) -> f64 {
+        if self.slippages.is_empty() {
+            0.0
+        } else {
+            self.slippages.iter().sum::<f64>() / self.slippages.len() as f64
+        }
+    }
+
+    /// Threshold moved from `base_bps` by the volatility and slippage terms, within the configured bounds.
+    /// None until enough prices were observed (the static threshold applies)
+    pub fn threshold(&self, config: &AdaptiveThresholdConfig, base_bps: f64) -> Option<AdaptiveThreshold> {
+        let realised_vol_bps = self.realised_vol_bps(config.min_samples)?;
+        let mean_slippage_bps = self.mean_slippage_bps();
+        let raw = base_bps + config.vol_coefficient * (realised_vol_bps - config.target_vol_bps) + config.slippage_coefficient * mean_slippage_bps;
+        Some(AdaptiveThreshold {
+            threshold_bps: raw.clamp(config.floor_bps, config.ceiling_bps),
+            realised_vol_bps,
+            mean_slippage_bps,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn window(samples: &[f64]) -> SpreadWindow {
+        let mut window = SpreadWindow::default();
+        for s in samples {
+            window.push(*s, samples.len());
+        }
+        window
+    }
+
+    fn config(max_half_life: f64) -> MeanReversionConfig {
+        MeanReversionConfig {
+            window: 100,
+            min_samples: 10,
+            entry_z: 2.0,
+            exit_z: 0.5,
+            max_half_life,
+            max_position_usd: 200.0,
+            trade_size_usd: 100.0,
+        }
+    }
+
+    fn stats(samples: usize, half_life: Option<f64>) -> SpreadStats {
+        SpreadStats {
+            mean_bps: 0.0,
+            stdev_bps: 10.0,
+            half_life,
+            samples,
+        }
+    }
+
+    #[test]
+    fn window_mean_and_sample_stdev() {
+        let stats = window(&[2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0]).stats().unwrap();
+        assert_eq!(stats.samples, 8);
+        assert!((stats.mean_bps - 5.0).abs() < 1e-12);
+        assert!((stats.stdev_bps - (32.0f64 / 7.0).sqrt()).abs() < 1e-12);
+        assert!((stats.z_score(9.0) - 4.0 / stats.stdev_bps).abs() < 1e-12);
+        assert!(window(&[1.0]).stats().is_none());
+    }
+
+    #[test]
+    fn window_drops_the_oldest_samples() {
+        let mut window = SpreadWindow::default();
+        for s in [100.0, 1.0, 2.0, 3.0] {
+            window.push(s, 3);
+        }
+        assert_eq!(window.len(), 3);
+        assert!((window.stats().unwrap().mean_bps - 2.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn half_life_of_a_reverting_series() {
+        // s_t = 0.9 * s_{t-1}: Δs = -0.1 * s_{t-1}, a deviation halves in ln 2 / -ln 0.9 samples
+        let samples: Vec<f64> = (0..50).map(|t| 100.0 * 0.9f64.powi(t)).collect();
+        let half_life = window(&samples).stats().unwrap().half_life.unwrap();
+        assert!((half_life - std::f64::consts::LN_2 / -0.9f64.ln()).abs() < 1e-9);
+
+        // A trending spread never reverts
+        let trend: Vec<f64> = (0..50).map(|t| t as f64).collect();
+        assert!(window(&trend).stats().unwrap().half_life.is_none());
+    }
+
+    #[test]
+    fn signal_entries() {
+        let config = config(0.0);
+        assert_eq!(signal(&config, &stats(20, None), -25.0, 5.0, 0.0), Signal::Enter { is_buy: true });
+        assert_eq!(signal(&config, &stats(20, None), 25.0, 5.0, 0.0), Signal::Enter { is_buy: false });
+        // Adding to a position in the same direction
+        assert_eq!(signal(&config, &stats(20, None), -25.0, 5.0, 1.0), Signal::Enter { is_buy: true });
+        // Within entry_z, not enough samples, or a deviation that does not pay the fee
+        assert_eq!(signal(&config, &stats(20, None), -15.0, 5.0, 0.0), Signal::Hold);
+        assert_eq!(signal(&config, &stats(9, None), -25.0, 5.0, 0.0), Signal::Hold);
+        assert_eq!(signal(&config, &stats(20, None), -25.0, 30.0, 0.0), Signal::Hold);
+    }
+
+    #[test]
+    fn signal_needs_a_fast_enough_reversion() {
+        let config = config(10.0);
+        assert_eq!(signal(&config, &stats(20, Some(5.0)), -25.0, 5.0, 0.0), Signal::Enter { is_buy: true });
+        assert_eq!(signal(&config, &stats(20, Some(20.0)), -25.0, 5.0, 0.0), Signal::Hold);
+        assert_eq!(signal(&config, &stats(20, None), -25.0, 5.0, 0.0), Signal::Hold);
+    }
+
+    #[test]
+    fn signal_exits() {
+        let config = config(0.0);
+        // Long closes once z is back above -exit_z, holds while still deviated
+        assert_eq!(signal(&config, &stats(20, None), -3.0, 5.0, 1.0), Signal::Exit { is_buy: false });
+        assert_eq!(signal(&config, &stats(20, None), -10.0, 5.0, 1.0), Signal::Hold);
+        // Short buys back, also when the spread flipped to the other side
+        assert_eq!(signal(&config, &stats(20, None), 3.0, 5.0, -1.0), Signal::Exit { is_buy: true });
+        assert_eq!(signal(&config, &stats(20, None), -25.0, 5.0, -1.0), Signal::Exit { is_buy: true });
+    }
+
+    #[test]
+    fn position_limit_counts_every_pool() {
+        let config = config(0.0);
+        let (a, b) = (Address::repeat_byte(1), Address::repeat_byte(2));
+        let mut state = MeanReversionState::default();
+        state.fill(a, true, 2.0);
+        state.fill(b, false, 1.0);
+        assert!((state.exposure_usd(50.0) - 150.0).abs() < 1e-12);
+        // A third entry would exceed max_position_usd
+        assert!(state.exposure_usd(50.0) + config.trade_size_usd > config.max_position_usd);
+
+        state.fill(a, false, 2.0);
+        assert_eq!(state.position(a), 0.0);
+        assert!(state.exposure_usd(50.0) + config.trade_size_usd <= config.max_position_usd);
+    }
+
+    #[test]
+    fn signal_orders() {
+        let config = config(0.0);
+        let enter = signal_order(&config, Signal::Enter { is_buy: false }, 0.0, 50.0, 30.0, 40.0).unwrap();
+        assert!(!enter.is_buy);
+        assert_eq!(enter.amount_in, Some(2.5));
+        let exit = signal_order(&config, Signal::Exit { is_buy: true }, -2.0, 50.0, 30.0, 40.0).unwrap();
+        assert!(exit.is_buy);
+        assert!((exit.amount_in.unwrap() - 100.3).abs() < 1e-9);
+        assert!(signal_order(&config, Signal::Hold, 0.0, 50.0, 30.0, 40.0).is_none());
+    }
+
+    #[test]
+    fn positions_replayed_from_tagged_fills() {
+        let pool = Address::repeat_byte(1);
+        let fill = |is_buy: bool, base_amount: f64, mean_reversion: bool, success: bool| TradePnl {
+            tx_hash: String::new(),
+            dex: "hyperswap".to_string(),
+            pool: format!("{:?}", pool),
+            is_buy,
+            success,
+            base_amount,
+            quote_amount: 0.0,
+            executed_price: 0.0,
+            expected_price: 0.0,
+            reference_price: 0.0,
+            slippage_bps: 0.0,
+            gross_pnl_usd: 0.0,
+            fee_usd: 0.0,
+            gas_paid_hype: 0.0,
+            gas_cost_usd: 0.0,
+            net_pnl_usd: 0.0,
+            mean_reversion,
+        };
+        let trades = [fill(true, 3.0, true, true), fill(false, 1.0, true, true), fill(true, 5.0, false, true), fill(true, 7.0, true, false)];
+        let state = MeanReversionState::replay(&trades);
+        assert!((state.position(pool) - 2.0).abs() < 1e-12);
+        assert!(state.windows.is_empty());
+    }
+
+    fn adaptive(floor_bps: f64, ceiling_bps: f64) -> AdaptiveThresholdConfig {
+        AdaptiveThresholdConfig {
+            floor_bps,
+            ceiling_bps,
+            vol_window: 10,
+            min_samples: 3,
+            target_vol_bps: 10.0,
+            vol_coefficient: 1.0,
+            slippage_window: 10,
+            slippage_coefficient: 1.0,
+        }
+    }
+
+    // Prices alternating +-`move_bps` every `step` blocks
+    fn oscillating(move_bps: f64, step: u64, samples: usize) -> ThresholdState {
+        let mut state = ThresholdState::default();
+        for i in 0..samples {
+            let price = if i % 2 == 0 { 100.0 } else { 100.0 * (1.0 + move_bps / 10_000.0) };
+            state.observe_price(1_000 + i as u64 * step, price, 10);
+        }
+        state
+    }
+
+    #[test]
+    fn realised_vol_needs_three_prices() {
+        let state = oscillating(10.0, 1, 2);
+        assert!(state.realised_vol_bps(2).is_none());
+        assert!(oscillating(10.0, 1, 3).realised_vol_bps(2).is_some());
+        assert!(oscillating(10.0, 1, 3).realised_vol_bps(4).is_none());
+    }
+
+    #[test]
+    fn realised_vol_is_per_block() {
+        // The same moves spread over 4 blocks are half the per-block volatility
+        let every_block = oscillating(20.0, 1, 8).realised_vol_bps(3).unwrap();
+        let every_4_blocks = oscillating(20.0, 4, 8).realised_vol_bps(3).unwrap();
+        assert!((every_block / every_4_blocks - 2.0).abs() < 1e-9);
+
+        // Repeated passes on one block add no sample
+        let mut state = oscillating(20.0, 1, 3);
+        state.observe_price(1_002, 150.0, 10);
+        assert!((state.realised_vol_bps(3).unwrap() - oscillating(20.0, 1, 3).realised_vol_bps(3).unwrap()).abs() < 1e-12);
+    }
+
+    #[test]
+    fn threshold_moves_with_vol_and_slippage() {
+        let mut state = oscillating(10.0, 1, 6);
+        let vol = state.realised_vol_bps(3).unwrap();
+        let threshold = state.threshold(&adaptive(0.0, 100.0), 20.0).unwrap();
+        assert!((threshold.threshold_bps - (20.0 + vol - 10.0)).abs() < 1e-9);
+
+        state.observe_slippage(4.0, 10);
+        state.observe_slippage(2.0, 10);
+        let threshold = state.threshold(&adaptive(0.0, 100.0), 20.0).unwrap();
+        assert_eq!(threshold.mean_slippage_bps, 3.0);
+        assert!((threshold.threshold_bps - (20.0 + vol - 10.0 + 3.0)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn threshold_clamped_to_bounds() {
+        // Calm market pulls the threshold below the floor, a volatile one above the ceiling
+        let calm = oscillating(0.0, 1, 6).threshold(&adaptive(15.0, 40.0), 20.0).unwrap();
+        assert_eq!(calm.threshold_bps, 15.0);
+        let volatile = oscillating(200.0, 1, 6).threshold(&adaptive(15.0, 40.0), 20.0).unwrap();
+        assert_eq!(volatile.threshold_bps, 40.0);
+        // Warming up: the static threshold applies
+        assert!(oscillating(200.0, 1, 2).threshold(&adaptive(15.0, 40.0), 20.0).is_none());
+    }
+}
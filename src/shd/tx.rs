@@ -0,0 +1,433 @@
+// Nonce management and transaction lifecycle tracking
+
+use alloy::{
+    eips::BlockNumberOrTag,
+    network::TransactionBuilder,
+    primitives::{Address, TxHash, U256},
+    providers::Provider,
+    rpc::types::{TransactionReceipt, TransactionRequest},
+};
+use std::{
+    collections::HashMap,
+    sync::{Arc, LazyLock},
+    time::{Duration, Instant},
+};
+use tokio::sync::{Mutex, OwnedMutexGuard};
+
+use crate::{
+    error::{Error, Result},
+    sim::Simulator,
+    types::GasConfig,
+};
+
+/// Process wide tracker, shared by every executor so nonces stay consistent per wallet
+pub static TX_TRACKER: LazyLock<TxTracker> = LazyLock::new(TxTracker::default);
+
+/// Receipt polling interval
+const TX_POLL_INTERVAL: Duration = Duration::from_millis(500);
+/// Gas limit of the 0-value self-transfer cancelling a transaction past its deadline
+const CANCEL_GAS_LIMIT: u64 = 21_000;
+/// How long receipts are polled once a nonce is consumed, endpoints can serve the nonce before the receipt
+const RECEIPT_GRACE: Duration = Duration::from_secs(3);
+
+// ===== NONCES =====
+
+/// Next nonce per wallet, incremented locally and never behind the pending state of the node
+#[derive(Debug, Default)]
+pub struct NonceManager {
+    /// Locked per wallet, so a pending count fetch only holds back sends from the same wallet
+    nonces: Mutex<HashMap<Address, Arc<Mutex<Option<u64>>>>>,
+}
+
+impl NonceManager {
+    async fn slot(&self, wallet: Address) -> Arc<Mutex<Option<u64>>> {
+        self.nonces.lock().await.entry(wallet).or_default().clone()
+    }
+
+    /// Reserve the next nonce of a wallet: the local one, or the pending count when a transaction was
+    /// sent from the wallet outside the tracker
+    pub async fn next<P: Provider>(&self, provider: &P, wallet: Address) -> Result<u64> {
+        let slot = self.slot(wallet).await;
+        let mut cached = slot.lock().await;
+        let pending = provider.get_transaction_count(wallet).pending().await?;
+        let nonce = cached.map_or(pending, |cached| cached.max(pending));
+        *cached = Some(nonce + 1);
+        Ok(nonce)
+    }
+
+    /// Forget the local nonce of a wallet, the next call re-syncs from the node
+    pub async fn reset(&self, wallet: Address) {
+        *self.slot(wallet).await.lock().await = None;
+    }
+}
+
+// ===== FEES =====
+
+/// EIP-1559 fees of a transaction
+#[derive(Debug, Clone, Copy)]
+pub struct FeeQuote {
+    /// Base fee expected in the next block
+    pub base_fee_per_gas: u128,
+    pub max_fee_per_gas: u128,
+    pub max_priority_fee_per_gas: u128,
+    /// Priority fee cap of the sender, replacements never tip above it
+    pub max_priority_fee_cap: u128,
+}
+
+impl FeeQuote {
+    /// Price paid per gas unit if included at the expected base fee
+    pub fn effective_gas_price(&self) -> u128 {
+        (self.base_fee_per_gas + self.max_priority_fee_per_gas).min(self.max_fee_per_gas)
+    }
+}
+
+/// Next block base fee and the median over the last `blocks` blocks of the tip paid at `percentile`
+pub async fn fee_history<P: Provider>(provider: &P, blocks: u64, percentile: f64) -> Result<(u128, u128)> {
+    let history = provider.get_fee_history(blocks, BlockNumberOrTag::Latest, &[percentile]).await?;
+    let base_fee = history.next_block_base_fee().ok_or_else(|| Error::Rpc("eth_feeHistory returned no base fee".to_string()))?;
+    let mut tips: Vec<u128> = history.reward.unwrap_or_default().iter().filter_map(|rewards| rewards.first().copied()).collect();
+    tips.sort_unstable();
+    Ok((base_fee, tips.get(tips.len() / 2).copied().unwrap_or_default()))
+}
+
+/// Fees of a swap: recent tip percentile (with the configured floor), scaled with the spread above the
+/// executable threshold and capped by the target, on top of the next base fee with headroom
+pub async fn quote_fees<P: Provider>(provider: &P, gas: &GasConfig, excess_spread_bps: f64, max_priority_fee_gwei: f64) -> Result<FeeQuote> {
+    let (base_fee, tip) = fee_history(provider, gas.priority_fee_blocks, gas.priority_fee_percentile).await?;
+    let tip = tip.max((gas.min_priority_fee_gwei * 1e9) as u128);
+    let scale = if gas.priority_fee_scale_bps > 0.0 {
+        1.0 + excess_spread_bps.max(0.0) / gas.priority_fee_scale_bps
+    } else {
+        1.0
+    };
+    let cap = (max_priority_fee_gwei * 1e9) as u128;
+    let priority_fee = ((tip as f64 * scale) as u128).min(cap);
+    Ok(FeeQuote {
+        base_fee_per_gas: base_fee,
+        max_fee_per_gas: (base_fee as f64 * gas.gas_price_multiplier) as u128 + priority_fee,
+        max_priority_fee_per_gas: priority_fee,
+        max_priority_fee_cap: cap,
+    })
+}
+
+/// Fees of a tracked transaction, kept in its original type across replacements
+#[derive(Debug, Clone, Copy)]
+enum TxFees {
+    Legacy(u128),
+    Eip1559 { max_fee: u128, priority_fee: u128 },
+}
+
+impl TxFees {
+    fn apply(self, request: TransactionRequest) -> TransactionRequest {
+        match self {
+            TxFees::Legacy(gas_price) => request.with_gas_price(gas_price),
+            TxFees::Eip1559 { max_fee, priority_fee } => request.with_max_fee_per_gas(max_fee).with_max_priority_fee_per_gas(priority_fee),
+        }
+    }
+
+    /// Raised by `percent`, and by at least 1 wei so zero tips still count as a replacement
+    fn bumped(self, percent: u64) -> Self {
+        let bump = |value: u128| (value * (100 + percent as u128) / 100).max(value + 1);
+        match self {
+            TxFees::Legacy(gas_price) => TxFees::Legacy(bump(gas_price)),
+            TxFees::Eip1559 { max_fee, priority_fee } => {
+                let priority_fee = bump(priority_fee);
+                TxFees::Eip1559 {
+                    max_fee: bump(max_fee).max(priority_fee),
+                    priority_fee,
+                }
+            }
+        }
+    }
+
+    /// Bumped fees, None when the bumped priority fee would exceed `max_priority_fee` (None = uncapped):
+    /// nodes only accept a replacement when both the fee cap and the tip rise by the bump percent
+    fn bumped_within(self, percent: u64, max_priority_fee: Option<u128>) -> Option<Self> {
+        let bumped = self.bumped(percent);
+        match (bumped, max_priority_fee) {
+            (TxFees::Eip1559 { priority_fee, .. }, Some(cap)) if priority_fee > cap => None,
+            _ => Some(bumped),
+        }
+    }
+}
+
+impl std::fmt::Display for TxFees {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TxFees::Legacy(gas_price) => write!(f, "gas price {} wei", gas_price),
+            TxFees::Eip1559 { max_fee, priority_fee } => write!(f, "max fee {} wei, tip {} wei", max_fee, priority_fee),
+        }
+    }
+}
+
+// ===== LIFECYCLE =====
+
+/// Inclusion deadline and gas bump policy
+#[derive(Debug, Clone, Copy)]
+pub struct TxPolicy {
+    pub deadline: Duration,
+    pub bump_after: Duration,
+    pub bump_percent: u64,
+    pub max_bumps: u32,
+    /// Simulate the request on the latest block before sending, failing without a nonce used if it reverts
+    pub simulate: bool,
+    /// Priority fee cap of replacements and cancellations (None = uncapped)
+    pub max_priority_fee_per_gas: Option<u128>,
+}
+
+impl TxPolicy {
+    /// Replacements keep the priority fee under the cap of the quote
+    pub fn capped(self, fees: &FeeQuote) -> Self {
+        Self {
+            max_priority_fee_per_gas: Some(fees.max_priority_fee_cap),
+            ..self
+        }
+    }
+}
+
+impl Default for TxPolicy {
+    fn default() -> Self {
+        Self {
+            deadline: Duration::from_secs(60),
+            bump_after: Duration::from_secs(10),
+            bump_percent: 15,
+            max_bumps: 3,
+            simulate: false,
+            max_priority_fee_per_gas: None,
+        }
+    }
+}
+
+impl From<&GasConfig> for TxPolicy {
+    fn from(gas: &GasConfig) -> Self {
+        Self {
+            deadline: Duration::from_secs(gas.tx_deadline_secs),
+            bump_after: Duration::from_secs(gas.tx_bump_after_secs),
+            bump_percent: gas.tx_gas_bump_percent,
+            max_bumps: gas.tx_max_bumps,
+            simulate: gas.simulate_before_send,
+            max_priority_fee_per_gas: None,
+        }
+    }
+}
+
+/// Final status of a tracked transaction
+#[derive(Debug, Clone)]
+pub enum TxOutcome {
+    /// Included and succeeded
+    Confirmed(TransactionReceipt),
+    /// Included but reverted
+    Reverted(TransactionReceipt),
+    /// Nonce consumed by a transaction we did not send (replaced or cancelled externally)
+    Dropped { nonce: u64, hashes: Vec<TxHash> },
+    /// Not included before the deadline, then cancelled by a self-transfer at its nonce (the nonce stays
+    /// reserved when the cancellation is not included either)
+    TimedOut { nonce: u64, hashes: Vec<TxHash> },
+}
+
+impl TxOutcome {
+    /// Receipt of an included transaction (reverted included), error otherwise
+    pub fn into_receipt(self) -> Result<TransactionReceipt> {
+        match self {
+            TxOutcome::Confirmed(receipt) | TxOutcome::Reverted(receipt) => Ok(receipt),
+            TxOutcome::Dropped { nonce, hashes } => Err(Error::Execution(format!("Transaction dropped (nonce {}, sent as {:?})", nonce, hashes))),
+            TxOutcome::TimedOut { nonce, hashes } => Err(Error::Execution(format!("Transaction not included before deadline (nonce {}, sent as {:?})", nonce, hashes))),
+        }
+    }
+}
+
+/// Submits transactions with managed nonces and follows them to a final status
+#[derive(Debug, Default)]
+pub struct TxTracker {
+    pub nonces: NonceManager,
+    wallets: Mutex<HashMap<Address, Arc<Mutex<()>>>>,
+}
+
+impl TxTracker {
+    /// Exclusive use of a wallet (balance checks, swaps and post-trade reads) across concurrent targets
+    pub async fn lock_wallet(&self, wallet: Address) -> OwnedMutexGuard<()> {
+        let lock = self.wallets.lock().await.entry(wallet).or_default().clone();
+        lock.lock_owned().await
+    }
+
+    /// Sign and send `request` from `wallet` (the provider must hold its signer), then watch it until
+    /// inclusion, replacing it with higher fees while pending and giving up at the deadline
+    pub async fn send<P: Provider + Clone + 'static>(&self, provider: &P, wallet: Address, mut request: TransactionRequest, policy: TxPolicy) -> Result<TxOutcome> {
+        if policy.simulate {
+            let simulation = Simulator::latest(provider.clone()).await?.simulate(request.clone().with_from(wallet)).await?;
+            if !simulation.success {
+                return Err(Error::Execution(format!(
+                    "Transaction reverts in simulation: {}",
+                    simulation.revert_reason.as_deref().unwrap_or("unknown reason")
+                )));
+            }
+            tracing::debug!("🧪 Simulated from {}: {} gas", wallet, simulation.gas_used);
+        }
+
+        let nonce = self.nonces.next(provider, wallet).await?;
+        // Type-2 unless the caller set a legacy gas price
+        let mut fees = match (request.max_fee_per_gas, request.max_priority_fee_per_gas, request.gas_price) {
+            (Some(max_fee), Some(priority_fee), _) => TxFees::Eip1559 { max_fee, priority_fee },
+            (_, _, Some(gas_price)) => TxFees::Legacy(gas_price),
+            _ => {
+                let estimate = provider.estimate_eip1559_fees().await?;
+                TxFees::Eip1559 {
+                    max_fee: estimate.max_fee_per_gas,
+                    priority_fee: estimate.max_priority_fee_per_gas,
+                }
+            }
+        };
+        request = fees.apply(request.with_from(wallet).with_nonce(nonce));
+
+        let mut hashes = match provider.send_transaction(request.clone()).await {
+            Ok(pending) => vec![*pending.tx_hash()],
+            Err(e) => {
+                // The nonce was not used, re-sync before the next transaction
+                self.nonces.reset(wallet).await;
+                return Err(Error::Execution(format!("Failed to send transaction: {}", e)));
+            }
+        };
+        tracing::info!("📤 Sent 0x{:x} (nonce {}, {})", hashes[0], nonce, fees);
+
+        let started = Instant::now();
+        let mut last_sent = Instant::now();
+        let mut bumps = 0;
+        loop {
+            tokio::time::sleep(TX_POLL_INTERVAL).await;
+
+            if let Some(outcome) = Self::find_receipt(provider, &hashes).await {
+                return Ok(outcome);
+            }
+
+            // Nonce consumed without any of our hashes being included: replaced by someone else
+            if let Ok(mined) = provider.get_transaction_count(wallet).latest().await
+                && mined > nonce
+            {
+                if let Some(outcome) = Self::await_receipt(provider, &hashes).await {
+                    return Ok(outcome);
+                }
+                tracing::warn!("🗑️ Nonce {} of {} consumed by another transaction, 0x{:x} dropped", nonce, wallet, hashes[0]);
+                self.nonces.reset(wallet).await;
+                return Ok(TxOutcome::Dropped { nonce, hashes });
+            }
+
+            if started.elapsed() >= policy.deadline {
+                tracing::warn!("⌛ 0x{:x} not included after {}s, cancelling nonce {}", hashes[0], policy.deadline.as_secs(), nonce);
+                return Ok(self.cancel(provider, wallet, nonce, fees, hashes, policy).await);
+            }
+
+            if last_sent.elapsed() >= policy.bump_after && bumps < policy.max_bumps {
+                let Some(bumped) = fees.bumped_within(policy.bump_percent, policy.max_priority_fee_per_gas) else {
+                    // A replacement with a capped tip would be rejected as underpriced, wait for inclusion or the deadline
+                    tracing::info!("⛽ Priority fee of nonce {} at the target's cap, no further replacement", nonce);
+                    bumps = policy.max_bumps;
+                    continue;
+                };
+                fees = bumped;
+                request = fees.apply(request);
+                match provider.send_transaction(request.clone()).await {
+                    Ok(pending) => {
+                        bumps += 1;
+                        hashes.push(*pending.tx_hash());
+                        tracing::info!("⛽ Replaced nonce {} with 0x{:x} ({}, bump {}/{})", nonce, pending.tx_hash(), fees, bumps, policy.max_bumps);
+                    }
+                    Err(e) => tracing::warn!("Failed to replace nonce {}: {}", nonce, e),
+                }
+                last_sent = Instant::now();
+            }
+        }
+    }
+
+    /// Replace a pending nonce with a 0-value self-transfer and wait up to another deadline for the nonce
+    /// to be consumed, so the signed transactions cannot execute later or collide with the next send.
+    /// One of the original transactions may still win the race, its outcome is returned then
+    async fn cancel<P: Provider + Clone>(&self, provider: &P, wallet: Address, nonce: u64, fees: TxFees, hashes: Vec<TxHash>, policy: TxPolicy) -> TxOutcome {
+        // Uncapped: the cancellation must outbid every pending replacement to be accepted
+        let fees = fees.bumped(policy.bump_percent);
+        let request = fees.apply(
+            TransactionRequest::default()
+                .with_from(wallet)
+                .with_to(wallet)
+                .with_value(U256::ZERO)
+                .with_nonce(nonce)
+                .with_gas_limit(CANCEL_GAS_LIMIT),
+        );
+        match provider.send_transaction(request).await {
+            Ok(pending) => tracing::info!("🚫 Cancelling nonce {} with 0x{:x} ({})", nonce, pending.tx_hash(), fees),
+            Err(e) => tracing::warn!("Failed to cancel nonce {}: {}", nonce, e),
+        }
+
+        let started = Instant::now();
+        while started.elapsed() < policy.deadline {
+            tokio::time::sleep(TX_POLL_INTERVAL).await;
+            if let Ok(mined) = provider.get_transaction_count(wallet).latest().await
+                && mined > nonce
+            {
+                if let Some(outcome) = Self::await_receipt(provider, &hashes).await {
+                    return outcome;
+                }
+                tracing::info!("🚫 Nonce {} of {} cancelled", nonce, wallet);
+                return TxOutcome::TimedOut { nonce, hashes };
+            }
+        }
+        // Still pending: keep the nonce reserved, the next send queues behind it rather than replacing it
+        tracing::error!("⌛ Nonce {} of {} still pending after cancellation, left reserved", nonce, wallet);
+        TxOutcome::TimedOut { nonce, hashes }
+    }
+
+    /// Receipt of any of the hashes sent for a consumed nonce, polled for up to `RECEIPT_GRACE`
+    async fn await_receipt<P: Provider>(provider: &P, hashes: &[TxHash]) -> Option<TxOutcome> {
+        let started = Instant::now();
+        loop {
+            if let Some(outcome) = Self::find_receipt(provider, hashes).await {
+                return Some(outcome);
+            }
+            if started.elapsed() >= RECEIPT_GRACE {
+                return None;
+            }
+            tokio::time::sleep(TX_POLL_INTERVAL).await;
+        }
+    }
+
+    /// Receipt of any of the hashes sent for a nonce
+    async fn find_receipt<P: Provider>(provider: &P, hashes: &[TxHash]) -> Option<TxOutcome> {
+        for hash in hashes {
+            match provider.get_transaction_receipt(*hash).await {
+                Ok(Some(receipt)) if receipt.status() => return Some(TxOutcome::Confirmed(receipt)),
+                Ok(Some(receipt)) => return Some(TxOutcome::Reverted(receipt)),
+                Ok(None) => {}
+                Err(e) => tracing::debug!("Failed to fetch receipt of 0x{:x}: {}", hash, e),
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bumped_fees() {
+        let fees = TxFees::Eip1559 { max_fee: 1_000, priority_fee: 100 }.bumped(15);
+        assert!(matches!(fees, TxFees::Eip1559 { max_fee: 1_150, priority_fee: 115 }));
+        // Zero tips still increase
+        let fees = TxFees::Eip1559 { max_fee: 1_000, priority_fee: 0 }.bumped(15);
+        assert!(matches!(fees, TxFees::Eip1559 { max_fee: 1_150, priority_fee: 1 }));
+        assert!(matches!(TxFees::Legacy(1_000).bumped_within(10, Some(1)), Some(TxFees::Legacy(1_100))));
+    }
+
+    #[test]
+    fn test_bumped_tip_is_capped() {
+        let fees = TxFees::Eip1559 { max_fee: 1_000, priority_fee: 100 }.bumped_within(15, Some(115));
+        assert!(matches!(fees, Some(TxFees::Eip1559 { max_fee: 1_150, priority_fee: 115 })));
+        // Above the cap: the tip cannot rise by the bump percent, a replacement would be rejected
+        assert!(TxFees::Eip1559 { max_fee: 1_000, priority_fee: 100 }.bumped_within(15, Some(110)).is_none());
+        assert!(TxFees::Eip1559 { max_fee: 1_000, priority_fee: 110 }.bumped_within(15, Some(110)).is_none());
+        // Cancellations are not capped
+        assert!(matches!(
+            TxFees::Eip1559 { max_fee: 1_000, priority_fee: 110 }.bumped(15),
+            TxFees::Eip1559 { max_fee: 1_150, priority_fee: 126 }
+        ));
+    }
+}
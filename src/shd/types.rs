@@ -3,6 +3,10 @@
 use alloy::primitives::Address;
 use alloy::signers::local::PrivateKeySigner;
 use serde::{Deserialize, Serialize};
+
+use crate::control::redact_url;
+use crate::error::{Error, Result};
+use crate::stats::MIN_VOL_SAMPLES;
 use std::str::FromStr;
 use std::{env, fs};
 
@@ -17,55 +21,43 @@ pub struct EnvConfig {
     pub wallet_pub_keys: Vec<String>,
     /// List of wallet private keys (must match pub_keys order)
     pub wallet_private_keys: Vec<String>,
-}
-
-impl Default for EnvConfig {
-    fn default() -> Self {
-        Self::new()
-    }
+    /// Bearer token for the control API write endpoints (optional, read-only API without it)
+    pub control_api_token: Option<String>,
 }
 
 impl EnvConfig {
-    pub fn new() -> Self {
-        let testing = env::var("TESTING").unwrap_or_else(|_| {
-            tracing::error!("Missing TESTING in environment");
-            std::process::exit(1);
-        });
+    /// Load from the process environment (call dotenv first)
+    pub fn new() -> Result<Self> {
+        // Testing mode (optional, the --dry-run and --live flags take precedence)
+        let testing = env::var("TESTING").map(|t| t == "true").unwrap_or(false);
 
         // Load database configuration
-        let database_url = env::var("DATABASE_URL").unwrap_or_else(|_| {
-            tracing::error!("Missing DATABASE_URL in environment");
-            std::process::exit(1);
-        });
+        let database_url = env::var("DATABASE_URL").map_err(|_| Error::Config("Missing DATABASE_URL in environment".to_string()))?;
 
         // Load multi-wallet support
-        let wallet_pub_keys_str = env::var("WALLET_PUB_KEYS").unwrap_or_else(|_| {
-            tracing::error!("Missing WALLET_PUB_KEYS in environment");
-            std::process::exit(1);
-        });
+        let wallet_pub_keys_str = env::var("WALLET_PUB_KEYS").map_err(|_| Error::Config("Missing WALLET_PUB_KEYS in environment".to_string()))?;
 
-        let wallet_private_keys_str = env::var("WALLET_PRIVATE_KEYS").unwrap_or_else(|_| {
-            tracing::error!("Missing WALLET_PRIVATE_KEYS in environment");
-            std::process::exit(1);
-        });
+        let wallet_private_keys_str = env::var("WALLET_PRIVATE_KEYS").map_err(|_| Error::Config("Missing WALLET_PRIVATE_KEYS in environment".to_string()))?;
 
         // Parse comma-separated values
         let wallet_pub_keys: Vec<String> = wallet_pub_keys_str.split(',').map(|s| s.trim().to_lowercase()).collect();
 
         let wallet_private_keys: Vec<String> = wallet_private_keys_str.split(',').map(|s| s.trim().to_string()).collect();
 
-        // Load hyperdrive webhook (required)
+        // Load control API token (optional)
+        let control_api_token = env::var("CONTROL_API_TOKEN").ok().filter(|t| !t.is_empty());
 
         let output = Self {
-            testing: testing == "true",
+            testing,
             database_url,
             wallet_pub_keys,
             wallet_private_keys,
+            control_api_token,
         };
 
-        output.validate_wallets().expect("Invalid wallet configuration");
+        output.validate_wallets()?;
         output.print();
-        output
+        Ok(output)
     }
 
     pub fn print(&self) {
@@ -74,36 +66,37 @@ impl EnvConfig {
 
         tracing::info!("   Database URL = 🗄️ (size: {})", self.database_url.len());
         tracing::info!("   Multi-wallet: {} wallets configured", self.wallet_pub_keys.len());
+        tracing::info!("   Control API token = {}", if self.control_api_token.is_some() { "🔐 set" } else { "not set (read-only)" });
     }
 
     /// Validates that public keys and private keys match by count and that each private key
     /// derives to the corresponding public key address
-    pub fn validate_wallets(&self) -> Result<(), String> {
+    pub fn validate_wallets(&self) -> Result<()> {
         // Check that we have the same number of public and private keys
         if self.wallet_pub_keys.len() != self.wallet_private_keys.len() {
-            return Err(format!(
+            return Err(Error::Config(format!(
                 "Mismatch between public keys count ({}) and private keys count ({})",
                 self.wallet_pub_keys.len(),
                 self.wallet_private_keys.len()
-            ));
+            )));
         }
 
         // Check that we have at least one wallet
         if self.wallet_pub_keys.is_empty() {
-            return Err("No wallets configured - WALLET_PUB_KEYS and WALLET_PRIVATE_KEYS cannot be empty".to_string());
+            return Err(Error::Config("No wallets configured - WALLET_PUB_KEYS and WALLET_PRIVATE_KEYS cannot be empty".to_string()));
         }
 
         // Validate each wallet pair
         for (i, (pub_key, priv_key)) in self.wallet_pub_keys.iter().zip(self.wallet_private_keys.iter()).enumerate() {
             // Validate public key format (0x + 40 hex chars)
             if !pub_key.starts_with("0x") || pub_key.len() != 42 {
-                return Err(format!("Wallet {}: public key '{}' must be a valid Ethereum address", i, pub_key));
+                return Err(Error::Config(format!("Wallet {}: public key '{}' must be a valid Ethereum address", i, pub_key)));
             }
 
             // Try to create a signer from the private key
             let signer = match PrivateKeySigner::from_str(priv_key) {
                 Ok(s) => s,
-                Err(e) => return Err(format!("Wallet {}: invalid private key format: {}", i, e)),
+                Err(e) => return Err(Error::Config(format!("Wallet {}: invalid private key format: {}", i, e))),
             };
 
             // Get the address derived from the private key
@@ -111,7 +104,7 @@ impl EnvConfig {
 
             // Check if the derived address matches the provided public key
             if derived_address != pub_key.to_lowercase() {
-                return Err(format!("Wallet {}: private key derives to address '{}' but public key is '{}'", i, derived_address, pub_key));
+                return Err(Error::Config(format!("Wallet {}: private key derives to address '{}' but public key is '{}'", i, derived_address, pub_key)));
             }
         }
 
@@ -144,26 +137,82 @@ impl EnvConfig {
     }
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct BotConfig {
     pub global: GlobalConfig,
     pub hyperevm: HyperEvmConfig,
     pub gas: GasConfig,
     pub dex: Vec<DexConfig>,
     pub targets: Vec<ArbTarget>,
+    #[serde(default)]
+    pub events: Option<EventsConfig>,
+    #[serde(default)]
+    pub discovery: Option<DiscoveryConfig>,
+    #[serde(default)]
+    pub recorder: Option<RecorderConfig>,
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct GlobalConfig {
     pub network_name: String,             // Network identifier
     pub rpc_endpoint: String,             // RPC endpoint for blockchain transactions
     pub broadcast_rpc_endpoint: Option<String>, // Optional RPC endpoint specifically for broadcasting swap transactions
+    #[serde(default)]
+    pub rpc_endpoints: Vec<String>,       // Additional read endpoints, used by health and latency when rpc_endpoint fails
+    #[serde(default)]
+    pub broadcast_rpc_endpoints: Vec<String>, // Additional broadcast endpoints, signed transactions are sent to all in parallel
+    #[serde(default = "default_rpc_failure_threshold")]
+    pub rpc_failure_threshold: u32,       // Consecutive failures before an endpoint is taken out of rotation
+    #[serde(default = "default_rpc_cooldown")]
+    pub rpc_cooldown_secs: u64,           // Time out of rotation before an endpoint is retried
+    #[serde(default = "default_rpc_timeout")]
+    pub rpc_timeout_secs: u64,            // Per-request timeout, a hung endpoint counts as a failure
     pub websocket_endpoint: String,       // WebSocket endpoint for real-time events
     pub hyperliquid_api_endpoint: String, // Hyperliquid API endpoint
     pub explorer_base_url: String,        // Blockchain explorer URL
+    #[serde(default)]
+    pub metrics_listen_address: Option<String>, // Optional Prometheus /metrics listen address (e.g. "0.0.0.0:9100")
+    #[serde(default)]
+    pub control_listen_address: Option<String>, // Optional control/status API listen address (e.g. "127.0.0.1:8080")
 }
 
-#[derive(Debug, Deserialize, Clone)]
+fn default_rpc_failure_threshold() -> u32 {
+    3
+}
+
+fn default_rpc_cooldown() -> u64 {
+    30
+}
+
+fn default_rpc_timeout() -> u64 {
+    10
+}
+
+impl GlobalConfig {
+    /// Read endpoints, rpc_endpoint first
+    pub fn read_endpoints(&self) -> Vec<String> {
+        let mut endpoints = vec![self.rpc_endpoint.clone()];
+        for endpoint in &self.rpc_endpoints {
+            if !endpoints.contains(endpoint) {
+                endpoints.push(endpoint.clone());
+            }
+        }
+        endpoints
+    }
+
+    /// Broadcast endpoints, falling back to the read endpoints when none is configured
+    pub fn broadcast_endpoints(&self) -> Vec<String> {
+        let mut endpoints: Vec<String> = self.broadcast_rpc_endpoint.iter().cloned().collect();
+        for endpoint in &self.broadcast_rpc_endpoints {
+            if !endpoints.contains(endpoint) {
+                endpoints.push(endpoint.clone());
+            }
+        }
+        if endpoints.is_empty() { self.read_endpoints() } else { endpoints }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct HyperEvmConfig {
     pub core_bridge_contract: String,          // CoreWriter contract for cross-chain transfers
     pub wrapped_hype_token_address: String,    // Wrapped HYPE token address on HyperEVM (like WETH)
@@ -172,16 +221,58 @@ pub struct HyperEvmConfig {
     pub liquidswap_api_endpoint: String,       // Liquid Labs API endpoint (required in config)
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct GasConfig {
     pub gas_estimate_multiplier: f64,    // Multiplier for gas estimates (e.g., 1.5 = 50% buffer)
     pub slippage_tolerance_percent: f64, // Slippage tolerance in percent (e.g., 5.0 = 5%)
     pub native_hype_reserve_amount: f64, // Native HYPE reserve amount to keep when wrapping (e.g., 0.1 HYPE)
     pub max_gas_price_gwei: f64,         // Maximum gas price in gwei above which rebalancing is skipped (e.g., 3.0 = 3 gwei)
-    pub gas_price_multiplier: f64,       // Gas price multiplier for transactions (e.g., 1.5 = 50% increase)
+    pub gas_price_multiplier: f64,       // Base fee headroom in the max fee of transactions (e.g., 1.5 = 50% increase)
+    #[serde(default = "default_priority_fee_blocks")]
+    pub priority_fee_blocks: u64,        // Recent blocks sampled with eth_feeHistory for the priority fee
+    #[serde(default = "default_priority_fee_percentile")]
+    pub priority_fee_percentile: f64,    // Reward percentile paid within each sampled block (e.g., 50.0 = median tip)
+    #[serde(default)]
+    pub min_priority_fee_gwei: f64,      // Priority fee floor in gwei, used when recent blocks paid no tips
+    #[serde(default)]
+    pub priority_fee_scale_bps: f64,     // Priority fee doubles every this many bps of spread above the executable threshold (0 = no scaling)
+    #[serde(default = "default_tx_deadline")]
+    pub tx_deadline_secs: u64,           // Give up on a transaction not included after this long
+    #[serde(default = "default_tx_bump_after")]
+    pub tx_bump_after_secs: u64,         // Replace a pending transaction with a higher gas price after this long
+    #[serde(default = "default_tx_gas_bump")]
+    pub tx_gas_bump_percent: u64,        // Gas price increase of each replacement (nodes require >= 10%)
+    #[serde(default = "default_tx_max_bumps")]
+    pub tx_max_bumps: u32,               // Maximum number of replacements per transaction
+    #[serde(default)]
+    pub simulate_before_send: bool,      // Simulate each transaction on the latest block first and skip it if it reverts
 }
 
-#[derive(Debug, Deserialize, Clone)]
+fn default_priority_fee_blocks() -> u64 {
+    20
+}
+
+fn default_priority_fee_percentile() -> f64 {
+    50.0
+}
+
+fn default_tx_deadline() -> u64 {
+    60
+}
+
+fn default_tx_bump_after() -> u64 {
+    10
+}
+
+fn default_tx_gas_bump() -> u64 {
+    15
+}
+
+fn default_tx_max_bumps() -> u32 {
+    3
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct DexConfig {
     pub name: String,
     pub version: String,
@@ -191,7 +282,7 @@ pub struct DexConfig {
     pub position_manager: String, // Position manager address (required)
 }
 
-#[derive(Debug, Deserialize, Clone, PartialEq)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 #[serde(rename_all = "lowercase")]
 pub enum PriceReference {
     Pyth,
@@ -209,16 +300,22 @@ impl std::fmt::Display for PriceReference {
     }
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct ArbTarget {
     pub vault_name: String,
     pub address: String,
     pub base_token: String,
     #[serde(default)]
     pub base_token_address: String,
+    /// ERC20 symbol() of the base token when it differs from the oracle symbol (e.g. "UBTC" for "BTC")
+    #[serde(default)]
+    pub base_token_symbol: Option<String>,
     pub quote_token: String,
     #[serde(default)]
     pub quote_token_address: String,
+    /// ERC20 symbol() of the quote token when it differs from the oracle symbol
+    #[serde(default)]
+    pub quote_token_symbol: Option<String>,
     pub hyperswap_pools: Vec<String>,
     pub prjx_pools: Vec<String>,
     pub min_watch_spread_bps: f64,
@@ -226,6 +323,7 @@ pub struct ArbTarget {
     pub max_slippage_pct: f64,
     pub max_inventory_ratio: f64,
     pub tx_gas_limit: u64,
+    /// Cadence of the target task in ms (runs at most once per new block)
     pub poll_interval_ms: u64,
     pub publish_events: bool,
     pub skip_simulation: bool,
@@ -234,20 +332,354 @@ pub struct ArbTarget {
     pub statistical_arb: bool,
     #[serde(default = "default_min_trade_value")]
     pub min_trade_value_usd: f64,
+    /// Bps added to (or removed from) the executable threshold per unit of inventory skew.
+    /// 0.0 keeps the threshold symmetric for buys and sells.
+    #[serde(default)]
+    pub inventory_skew_coefficient: f64,
+    /// Priority fee cap (gwei) of this target's swaps
+    #[serde(default = "default_max_priority_fee")]
+    pub max_priority_fee_gwei: f64,
+    /// Pools with a lower TVL (USD) are skipped, 0.0 disables the filter
+    #[serde(default)]
+    pub min_pool_tvl_usd: f64,
+    /// Pools with less in-range liquidity (raw V3 L) are skipped, 0.0 disables the filter
+    #[serde(default)]
+    pub min_active_liquidity: f64,
+    /// Optional perp hedge of the base inventory on HyperCore
+    #[serde(default)]
+    pub hedge: Option<HedgeConfig>,
+    /// Optional z-score mean-reversion mode replacing the fixed spread thresholds (statistical arb only)
+    #[serde(default)]
+    pub mean_reversion: Option<MeanReversionConfig>,
+    /// Optional executable threshold driven by realised volatility and fill slippage
+    #[serde(default)]
+    pub adaptive_threshold: Option<AdaptiveThresholdConfig>,
+    /// Optional pool-to-pool arbitrage between the target's Hyperswap and ProjectX pools
+    #[serde(default)]
+    pub cross_pool: Option<CrossPoolConfig>,
 }
 
 fn default_min_trade_value() -> f64 {
     10.0
 }
 
+fn default_max_priority_fee() -> f64 {
+    1.0
+}
+
+/// Perp hedge settings for a target ([targets.hedge])
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct HedgeConfig {
+    pub perp_coin: String,          // HyperCore perp to short (e.g. "HYPE")
+    pub spot_coin: Option<String>,  // HyperCore spot coin holding base inventory (defaults to perp_coin)
+    #[serde(default = "default_hedge_ratio")]
+    pub hedge_ratio: f64,           // Fraction of the base exposure to short (1.0 = fully hedged)
+    #[serde(default = "default_hedge_tolerance")]
+    pub tolerance_ratio: f64,       // Rebalance only when the hedge drifts more than this fraction from target
+    #[serde(default = "default_hedge_min_order")]
+    pub min_order_usd: f64,         // Minimum hedge order notional in USD
+    #[serde(default = "default_hedge_slippage")]
+    pub max_slippage_bps: f64,      // IOC limit price offset from mid in bps
+    #[serde(default = "default_hedge_interval")]
+    pub poll_interval_ms: u64,      // Hedge loop cadence
+}
+
+fn default_hedge_ratio() -> f64 {
+    1.0
+}
+
+fn default_hedge_tolerance() -> f64 {
+    0.1
+}
+
+fn default_hedge_min_order() -> f64 {
+    10.0
+}
+
+fn default_hedge_slippage() -> f64 {
+    30.0
+}
+
+fn default_hedge_interval() -> u64 {
+    5000
+}
+
+/// Rolling spread z-score settings for a target ([targets.mean_reversion])
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct MeanReversionConfig {
+    #[serde(default = "default_mr_window")]
+    pub window: usize,              // Spread samples kept per pool (one per block)
+    #[serde(default = "default_mr_min_samples")]
+    pub min_samples: usize,         // No signal until the window holds this many samples
+    #[serde(default = "default_mr_entry_z")]
+    pub entry_z: f64,               // Open a position when |z| reaches this
+    #[serde(default = "default_mr_exit_z")]
+    pub exit_z: f64,                // Close it once |z| falls back to this
+    #[serde(default)]
+    pub max_half_life: f64,         // Skip entries when the spread reverts slower than this (samples), 0.0 disables
+    #[serde(default = "default_mr_max_position")]
+    pub max_position_usd: f64,      // Cap on the total open position of the target in USD
+    #[serde(default = "default_mr_trade_size")]
+    pub trade_size_usd: f64,        // Size of each entry in USD
+}
+
+fn default_mr_window() -> usize {
+    300
+}
+
+fn default_mr_min_samples() -> usize {
+    60
+}
+
+fn default_mr_entry_z() -> f64 {
+    2.0
+}
+
+fn default_mr_exit_z() -> f64 {
+    0.5
+}
+
+fn default_mr_max_position() -> f64 {
+    1000.0
+}
+
+fn default_mr_trade_size() -> f64 {
+    100.0
+}
+
+/// Adaptive executable threshold for a target ([targets.adaptive_threshold])
+/// threshold = min_executable_spread_bps + vol_coefficient * (realised vol - target_vol_bps)
+///           + slippage_coefficient * mean fill slippage, clamped to [floor_bps, ceiling_bps]
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AdaptiveThresholdConfig {
+    pub floor_bps: f64,             // Hard lower bound of the executable threshold
+    pub ceiling_bps: f64,           // Hard upper bound of the executable threshold
+    #[serde(default = "default_at_vol_window")]
+    pub vol_window: usize,          // Reference price samples (one per block) for realised volatility
+    #[serde(default = "default_at_min_samples")]
+    pub min_samples: usize,         // Static threshold until the window holds this many samples
+    #[serde(default = "default_at_target_vol")]
+    pub target_vol_bps: f64,        // Per-block volatility at which the volatility term is zero
+    #[serde(default = "default_at_coefficient")]
+    pub vol_coefficient: f64,       // Threshold bps per bps of volatility above (or below) target
+    #[serde(default = "default_at_slippage_window")]
+    pub slippage_window: usize,     // Recent fills averaged for the slippage term
+    #[serde(default = "default_at_coefficient")]
+    pub slippage_coefficient: f64,  // Threshold bps per bps of mean fill slippage (positive = worse than expected)
+}
+
+fn default_at_vol_window() -> usize {
+    120
+}
+
+fn default_at_min_samples() -> usize {
+    30
+}
+
+fn default_at_target_vol() -> f64 {
+    5.0
+}
+
+fn default_at_coefficient() -> f64 {
+    1.0
+}
+
+fn default_at_slippage_window() -> usize {
+    20
+}
+
+/// How the two legs of a cross-pool arbitrage are sent
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum CrossPoolMode {
+    /// Two swaps from the wallet inventory, the sell leg once the buy leg is confirmed
+    #[default]
+    Sequential,
+    /// Both swaps in one transaction through the HyperArb contract (reverts unless profitable)
+    Atomic,
+    /// Flash swap through the HyperArb contract: the buy pool lends the base, repaid from the sell pool
+    /// output, so the size is not bounded by the wallet inventory
+    Flash,
+}
+
+impl std::fmt::Display for CrossPoolMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CrossPoolMode::Sequential => write!(f, "sequential"),
+            CrossPoolMode::Atomic => write!(f, "atomic"),
+            CrossPoolMode::Flash => write!(f, "flash"),
+        }
+    }
+}
+
+/// Pool-to-pool arbitrage settings for a target ([targets.cross_pool])
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CrossPoolConfig {
+    #[serde(default)]
+    pub mode: CrossPoolMode,
+    #[serde(default)]
+    pub contract_address: Option<String>, // HyperArb contract (atomic and flash modes), owned by the target wallet
+    #[serde(default = "default_cp_min_profit")]
+    pub min_profit_usd: f64,        // Minimum profit after both pool fees, price impact and gas
+    #[serde(default = "default_cp_max_trade")]
+    pub max_trade_usd: f64,         // Cap on the quote spent on the buy leg
+    #[serde(default = "default_cp_flash_sizes")]
+    pub flash_sizes: usize,         // Flash mode: sizes from 0.5x to 1.5x the V3-math optimum simulated in parallel, the best is sent
+}
+
+fn default_cp_min_profit() -> f64 {
+    1.0
+}
+
+fn default_cp_max_trade() -> f64 {
+    1000.0
+}
+
+fn default_cp_flash_sizes() -> usize {
+    5
+}
+
+/// Event sinks shared by targets with publish_events = true ([events])
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct EventsConfig {
+    #[serde(default)]
+    pub sinks: Vec<EventSinkConfig>,
+    #[serde(default = "default_events_per_minute")]
+    pub max_events_per_minute: u32, // Events above this rate are dropped
+    #[serde(default = "default_events_dedup_window")]
+    pub dedup_window_secs: u64,     // Identical events within this window are published once
+    #[serde(default = "default_oracle_divergence")]
+    pub oracle_divergence_bps: f64, // Reference vs HyperCore mid divergence that raises an OracleDivergence event
+}
+
+/// Event sink type
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum EventSinkKind {
+    Discord,
+    Slack,
+    Http,
+    Stdout,
+}
+
+/// A single event sink ([[events.sinks]])
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct EventSinkConfig {
+    pub kind: EventSinkKind,
+    #[serde(default)]
+    pub url: String, // Webhook or HTTP endpoint (unused for stdout)
+}
+
+/// Automatic pool discovery from the [[dex]] factories ([discovery])
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DiscoveryConfig {
+    #[serde(default = "default_discovery_fee_tiers")]
+    pub fee_tiers: Vec<u32>,  // Fee tiers queried with getPool (hundredths of a bip, 500 = 0.05%)
+    pub min_tvl_usd: f64,     // Pools below this TVL are ignored
+    #[serde(default = "default_discovery_interval")]
+    pub interval_secs: u64,   // Discovery re-run interval
+}
+
+fn default_discovery_fee_tiers() -> Vec<u32> {
+    vec![100, 500, 3000, 10000]
+}
+
+fn default_discovery_interval() -> u64 {
+    1800
+}
+
+/// File format of the spread recordings
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum RecordFormat {
+    #[default]
+    Csv,
+    Parquet,
+}
+
+impl RecordFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            RecordFormat::Csv => "csv",
+            RecordFormat::Parquet => "parquet",
+        }
+    }
+}
+
+/// Historical spread recorder, one row per block, target and pool ([recorder])
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RecorderConfig {
+    #[serde(default = "default_recorder_directory")]
+    pub directory: String,           // Output directory, created if missing
+    #[serde(default)]
+    pub format: RecordFormat,        // "csv" (appended every block) or "parquet" (written every flush_rows rows or flush_secs)
+    #[serde(default = "default_recorder_roll")]
+    pub roll_secs: u64,              // A new file is started every roll_secs (UTC aligned)
+    #[serde(default = "default_recorder_flush_rows")]
+    pub flush_rows: usize,           // Parquet rows buffered before a part file is written
+    #[serde(default = "default_recorder_flush_secs")]
+    pub flush_secs: u64,             // Parquet buffered rows are written at least this often
+    #[serde(default)]
+    pub oracles: Vec<PriceReference>, // Reference prices recorded besides each target's own reference
+}
+
+fn default_recorder_directory() -> String {
+    "data/spreads".to_string()
+}
+
+fn default_recorder_roll() -> u64 {
+    86400
+}
+
+fn default_recorder_flush_rows() -> usize {
+    5000
+}
+
+fn default_recorder_flush_secs() -> u64 {
+    300
+}
+
+fn default_events_per_minute() -> u32 {
+    30
+}
+
+fn default_events_dedup_window() -> u64 {
+    60
+}
+
+fn default_oracle_divergence() -> f64 {
+    50.0
+}
+
+impl HedgeConfig {
+    /// HyperCore spot coin holding the base inventory
+    pub fn spot_coin(&self) -> &str {
+        self.spot_coin.as_deref().unwrap_or(&self.perp_coin)
+    }
+}
+
 impl BotConfig {
     pub fn print(&self) {
         tracing::debug!(" >>> Config <<<");
         tracing::debug!("  Network:                {}", self.global.network_name);
         tracing::debug!("  RPC Endpoint:           {}", self.global.rpc_endpoint);
+        tracing::debug!(
+            "  RPC Failover:           {} read, {} broadcast endpoints (circuit open after {} failures for {}s, {}s timeout)",
+            self.global.read_endpoints().len(),
+            self.global.broadcast_endpoints().len(),
+            self.global.rpc_failure_threshold,
+            self.global.rpc_cooldown_secs,
+            self.global.rpc_timeout_secs
+        );
         tracing::debug!("  WebSocket Endpoint:     {}", self.global.websocket_endpoint);
         tracing::debug!("  Hyperliquid API:        {}", self.global.hyperliquid_api_endpoint);
         tracing::debug!("  Explorer URL:           {}", self.global.explorer_base_url);
+        if let Some(metrics) = &self.global.metrics_listen_address {
+            tracing::debug!("  Metrics Address:        {}", metrics);
+        }
+        if let Some(control) = &self.global.control_listen_address {
+            tracing::debug!("  Control API Address:    {}", control);
+        }
         tracing::debug!("  Core Bridge Contract:   {}", self.hyperevm.core_bridge_contract);
         tracing::debug!("  Wrapped HYPE Address:   {}", self.hyperevm.wrapped_hype_token_address);
         tracing::debug!("  Bridge HYPE Address:    {}", self.hyperevm.bridge_hype_token_address);
@@ -257,6 +689,38 @@ impl BotConfig {
         tracing::debug!("  Gas Estimate Multiplier: {}x", self.gas.gas_estimate_multiplier);
         tracing::debug!("  Slippage Tolerance:     {}%", self.gas.slippage_tolerance_percent);
         tracing::debug!("  Native HYPE Reserve:    {} HYPE", self.gas.native_hype_reserve_amount);
+        tracing::debug!(
+            "  Priority Fee:           p{} of last {} blocks, floor {} gwei, scale {} bps",
+            self.gas.priority_fee_percentile,
+            self.gas.priority_fee_blocks,
+            self.gas.min_priority_fee_gwei,
+            self.gas.priority_fee_scale_bps
+        );
+        tracing::debug!(
+            "  Tx Lifecycle:           deadline {}s, bump +{}% after {}s (max {})",
+            self.gas.tx_deadline_secs,
+            self.gas.tx_gas_bump_percent,
+            self.gas.tx_bump_after_secs,
+            self.gas.tx_max_bumps
+        );
+        tracing::debug!(
+            "  Simulation:             {}",
+            match (self.gas.simulate_before_send, cfg!(feature = "simulation")) {
+                (false, _) => "off",
+                (true, true) => "local revm before send",
+                (true, false) => "eth_call before send",
+            }
+        );
+
+        if let Some(recorder) = &self.recorder {
+            tracing::debug!(
+                "  Spread Recorder:        {} files under {}, rolled every {}s, extra oracles {:?}",
+                recorder.format.extension(),
+                recorder.directory,
+                recorder.roll_secs,
+                recorder.oracles
+            );
+        }
 
         if !self.dex.is_empty() {
             tracing::debug!("  DEX Configurations:");
@@ -265,6 +729,15 @@ impl BotConfig {
             }
         }
 
+        if let Some(events) = &self.events {
+            let kinds: Vec<String> = events.sinks.iter().map(|s| format!("{:?}", s.kind)).collect();
+            tracing::debug!("  Event Sinks:            {} ({}/min, dedup {}s)", kinds.join(", "), events.max_events_per_minute, events.dedup_window_secs);
+        }
+
+        if let Some(discovery) = &self.discovery {
+            tracing::debug!("  Pool Discovery:         fee tiers {:?}, min TVL ${}, every {}s", discovery.fee_tiers, discovery.min_tvl_usd, discovery.interval_secs);
+        }
+
         if !self.targets.is_empty() {
             tracing::debug!("  Targets Configurations:");
             for track in &self.targets {
@@ -284,105 +757,289 @@ impl BotConfig {
                 tracing::debug!("   ║ Infinite Approval: {}", track.infinite_approval);
                 tracing::debug!("   ║ Price Reference: {}", track.reference);
                 tracing::debug!("   ║ Statistical Arb: {}", track.statistical_arb);
+                tracing::debug!("   ║ Inventory Skew Coef: {} bps", track.inventory_skew_coefficient);
+                tracing::debug!("   ║ Min Pool TVL: ${} | Min Active Liquidity: {}", track.min_pool_tvl_usd, track.min_active_liquidity);
+                tracing::debug!("   ║ Max Priority Fee: {} gwei", track.max_priority_fee_gwei);
+                if let Some(hedge) = &track.hedge {
+                    tracing::debug!("   ║ Hedge: short {} perp x{} (band {}%)", hedge.perp_coin, hedge.hedge_ratio, hedge.tolerance_ratio * 100.0);
+                }
+                if let Some(mr) = &track.mean_reversion {
+                    tracing::debug!(
+                        "   ║ Mean Reversion: window {} (min {}), entry z {} / exit z {}, ${} per trade up to ${}",
+                        mr.window,
+                        mr.min_samples,
+                        mr.entry_z,
+                        mr.exit_z,
+                        mr.trade_size_usd,
+                        mr.max_position_usd
+                    );
+                }
+                if let Some(cp) = &track.cross_pool {
+                    tracing::debug!("   ║ Cross Pool: {} mode, min profit ${}, max ${} per trade", cp.mode, cp.min_profit_usd, cp.max_trade_usd);
+                }
+                if let Some(at) = &track.adaptive_threshold {
+                    tracing::debug!(
+                        "   ║ Adaptive Threshold: [{}, {}] bps, vol x{} vs {} bps over {} blocks, slippage x{} over {} fills",
+                        at.floor_bps,
+                        at.ceiling_bps,
+                        at.vol_coefficient,
+                        at.target_vol_bps,
+                        at.vol_window,
+                        at.slippage_coefficient,
+                        at.slippage_window
+                    );
+                }
                 tracing::debug!("   ╚════════════════════╝");
             }
         }
         tracing::debug!(" >>> End of Config <<<");
     }
 
-    pub fn validate(&self, env_config: Option<&EnvConfig>) -> Result<(), String> {
+    pub fn validate(&self, env_config: Option<&EnvConfig>) -> Result<()> {
         if self.global.network_name.is_empty() {
-            return Err("Network name cannot be empty".to_string());
+            return Err(Error::Config("Network name cannot be empty".to_string()));
         }
         if self.global.rpc_endpoint.is_empty() {
-            return Err("RPC endpoint cannot be empty".to_string());
+            return Err(Error::Config("RPC endpoint cannot be empty".to_string()));
+        }
+        for endpoint in self.global.read_endpoints().iter().chain(self.global.broadcast_endpoints().iter()) {
+            if let Err(e) = url::Url::parse(endpoint) {
+                return Err(Error::Config(format!("Invalid RPC endpoint URL {}: {}", redact_url(endpoint), e)));
+            }
+        }
+        if self.global.rpc_failure_threshold == 0 || self.global.rpc_cooldown_secs == 0 || self.global.rpc_timeout_secs == 0 {
+            return Err(Error::Config("rpc_failure_threshold, rpc_cooldown_secs and rpc_timeout_secs must be positive".to_string()));
         }
         if self.global.websocket_endpoint.is_empty() {
-            return Err("WebSocket endpoint cannot be empty".to_string());
+            return Err(Error::Config("WebSocket endpoint cannot be empty".to_string()));
         }
         if self.global.hyperliquid_api_endpoint.is_empty() {
-            return Err("Hyperliquid API endpoint cannot be empty".to_string());
+            return Err(Error::Config("Hyperliquid API endpoint cannot be empty".to_string()));
         }
         if self.hyperevm.core_bridge_contract.is_empty() {
-            return Err("Core bridge contract address cannot be empty".to_string());
+            return Err(Error::Config("Core bridge contract address cannot be empty".to_string()));
         }
         if self.hyperevm.wrapped_hype_token_address.is_empty() {
-            return Err("Wrapped HYPE token address cannot be empty".to_string());
+            return Err(Error::Config("Wrapped HYPE token address cannot be empty".to_string()));
         }
         if self.hyperevm.bridge_hype_token_address.is_empty() {
-            return Err("Bridge HYPE token address cannot be empty".to_string());
+            return Err(Error::Config("Bridge HYPE token address cannot be empty".to_string()));
         }
 
         if self.hyperevm.liqd_multi_hop_router_address.is_empty() {
-            return Err("Liquid Labs multi-hop router address cannot be empty".to_string());
+            return Err(Error::Config("Liquid Labs multi-hop router address cannot be empty".to_string()));
         }
 
         // Validate HyperEVM addresses are properly formatted (0x + 40 hex chars)
         if !self.hyperevm.core_bridge_contract.starts_with("0x") || self.hyperevm.core_bridge_contract.len() != 42 {
-            return Err("Core bridge contract must be a valid Ethereum address".to_string());
+            return Err(Error::Config("Core bridge contract must be a valid Ethereum address".to_string()));
         }
         if !self.hyperevm.wrapped_hype_token_address.starts_with("0x") || self.hyperevm.wrapped_hype_token_address.len() != 42 {
-            return Err("Wrapped HYPE token address must be a valid Ethereum address".to_string());
+            return Err(Error::Config("Wrapped HYPE token address must be a valid Ethereum address".to_string()));
         }
         if !self.hyperevm.bridge_hype_token_address.starts_with("0x") || self.hyperevm.bridge_hype_token_address.len() != 42 {
-            return Err("Bridge HYPE token address must be a valid Ethereum address".to_string());
+            return Err(Error::Config("Bridge HYPE token address must be a valid Ethereum address".to_string()));
         }
 
         if !self.hyperevm.liqd_multi_hop_router_address.starts_with("0x") || self.hyperevm.liqd_multi_hop_router_address.len() != 42 {
-            return Err("Liquid Labs multi-hop router address must be a valid Ethereum address".to_string());
+            return Err(Error::Config("Liquid Labs multi-hop router address must be a valid Ethereum address".to_string()));
         }
 
         // Validate Gas configuration
         if self.gas.gas_estimate_multiplier <= 0.0 {
-            return Err("Gas estimate multiplier must be positive (recommended: 1.5-3.0)".to_string());
+            return Err(Error::Config("Gas estimate multiplier must be positive (recommended: 1.5-3.0)".to_string()));
         }
         if self.gas.gas_estimate_multiplier > 10.0 {
-            return Err("Gas estimate multiplier is too high (recommended: 1.5-3.0)".to_string());
+            return Err(Error::Config("Gas estimate multiplier is too high (recommended: 1.5-3.0)".to_string()));
         }
 
         if self.gas.slippage_tolerance_percent <= 0.0 || self.gas.slippage_tolerance_percent > 50.0 {
-            return Err("Slippage tolerance must be between 0.1% and 50%".to_string());
+            return Err(Error::Config("Slippage tolerance must be between 0.1% and 50%".to_string()));
         }
         if self.gas.native_hype_reserve_amount < 0.0 || self.gas.native_hype_reserve_amount > 10.0 {
-            return Err("Native HYPE reserve amount must be between 0.0 and 10.0 HYPE".to_string());
+            return Err(Error::Config("Native HYPE reserve amount must be between 0.0 and 10.0 HYPE".to_string()));
         }
         if self.gas.gas_price_multiplier < 1.0 || self.gas.gas_price_multiplier > 5.0 {
-            return Err("Gas price multiplier must be between 1.0 and 5.0".to_string());
+            return Err(Error::Config("Gas price multiplier must be between 1.0 and 5.0".to_string()));
+        }
+        if self.gas.priority_fee_blocks == 0 || self.gas.priority_fee_blocks > 1024 {
+            return Err(Error::Config("Gas priority_fee_blocks must be between 1 and 1024".to_string()));
+        }
+        if !(0.0..=100.0).contains(&self.gas.priority_fee_percentile) {
+            return Err(Error::Config("Gas priority_fee_percentile must be between 0 and 100".to_string()));
+        }
+        if self.gas.min_priority_fee_gwei < 0.0 || self.gas.priority_fee_scale_bps < 0.0 {
+            return Err(Error::Config("Gas min_priority_fee_gwei and priority_fee_scale_bps cannot be negative".to_string()));
+        }
+        if self.gas.tx_deadline_secs == 0 || self.gas.tx_bump_after_secs == 0 {
+            return Err(Error::Config("Gas tx_deadline_secs and tx_bump_after_secs must be positive".to_string()));
+        }
+        if self.gas.tx_gas_bump_percent < 10 {
+            return Err(Error::Config("Gas tx_gas_bump_percent must be at least 10 (replacement minimum)".to_string()));
         }
 
         // Validate DEX configurations
         for dex in &self.dex {
             if dex.name.is_empty() {
-                return Err("DEX name cannot be empty".to_string());
+                return Err(Error::Config("DEX name cannot be empty".to_string()));
             }
             if dex.version.is_empty() {
-                return Err(format!("DEX {} version cannot be empty", dex.name));
+                return Err(Error::Config(format!("DEX {} version cannot be empty", dex.name)));
             }
 
             // Only validate non-empty addresses (empty addresses indicate TODO/not configured)
             if !dex.factory.is_empty() && (!dex.factory.starts_with("0x") || dex.factory.len() != 42) {
-                return Err(format!("DEX {} factory address must be a valid Ethereum address", dex.name));
+                return Err(Error::Config(format!("DEX {} factory address must be a valid Ethereum address", dex.name)));
             }
             if !dex.router.is_empty() && (!dex.router.starts_with("0x") || dex.router.len() != 42) {
-                return Err(format!("DEX {} router address must be a valid Ethereum address", dex.name));
+                return Err(Error::Config(format!("DEX {} router address must be a valid Ethereum address", dex.name)));
             }
             if !dex.quoter.is_empty() && (!dex.quoter.starts_with("0x") || dex.quoter.len() != 42) {
-                return Err(format!("DEX {} quoter address must be a valid Ethereum address", dex.name));
+                return Err(Error::Config(format!("DEX {} quoter address must be a valid Ethereum address", dex.name)));
             }
 
             // Validate optional fields if present
             if !dex.position_manager.is_empty() && (!dex.position_manager.starts_with("0x") || dex.position_manager.len() != 42) {
-                return Err(format!("DEX {} position manager address must be a valid Ethereum address", dex.name));
+                return Err(Error::Config(format!("DEX {} position manager address must be a valid Ethereum address", dex.name)));
+            }
+        }
+
+        if let Some(metrics) = &self.global.metrics_listen_address
+            && metrics.parse::<std::net::SocketAddr>().is_err()
+        {
+            return Err(Error::Config(format!("Metrics listen address '{}' must be a valid socket address (e.g. 0.0.0.0:9100)", metrics)));
+        }
+
+        if let Some(control) = &self.global.control_listen_address
+            && control.parse::<std::net::SocketAddr>().is_err()
+        {
+            return Err(Error::Config(format!("Control listen address '{}' must be a valid socket address (e.g. 127.0.0.1:8080)", control)));
+        }
+
+        // Validate event sinks
+        if let Some(events) = &self.events {
+            for sink in &events.sinks {
+                if sink.kind != EventSinkKind::Stdout && !sink.url.starts_with("http") {
+                    return Err(Error::Config(format!("events sink {:?} requires an http(s) url", sink.kind)));
+                }
+            }
+            if events.max_events_per_minute == 0 {
+                return Err(Error::Config("events max_events_per_minute must be positive".to_string()));
+            }
+            if events.oracle_divergence_bps <= 0.0 {
+                return Err(Error::Config("events oracle_divergence_bps must be positive".to_string()));
+            }
+        }
+
+        // Validate pool discovery
+        if let Some(discovery) = &self.discovery {
+            if discovery.fee_tiers.is_empty() {
+                return Err(Error::Config("discovery fee_tiers cannot be empty".to_string()));
+            }
+            if let Some(fee) = discovery.fee_tiers.iter().find(|fee| **fee >= 1 << 24) {
+                return Err(Error::Config(format!("discovery fee tier {} does not fit in a uint24", fee)));
+            }
+            if discovery.min_tvl_usd < 0.0 {
+                return Err(Error::Config("discovery min_tvl_usd cannot be negative".to_string()));
+            }
+            if discovery.interval_secs == 0 {
+                return Err(Error::Config("discovery interval_secs must be positive".to_string()));
+            }
+        }
+
+        // Validate spread recorder
+        if let Some(recorder) = &self.recorder {
+            if recorder.directory.is_empty() {
+                return Err(Error::Config("recorder directory cannot be empty".to_string()));
+            }
+            if recorder.roll_secs == 0 || recorder.flush_rows == 0 || recorder.flush_secs == 0 {
+                return Err(Error::Config("recorder roll_secs, flush_rows and flush_secs must be positive".to_string()));
             }
         }
 
         // Validate targets configurations
         for track in &self.targets {
             if track.vault_name.is_empty() {
-                return Err("targets vault name cannot be empty".to_string());
+                return Err(Error::Config("targets vault name cannot be empty".to_string()));
             }
             if !track.address.starts_with("0x") || track.address.len() != 42 {
-                return Err(format!("targets address for {} must be a valid Ethereum address", track.vault_name));
+                return Err(Error::Config(format!("targets address for {} must be a valid Ethereum address", track.vault_name)));
+            }
+
+            if track.inventory_skew_coefficient < 0.0 {
+                return Err(Error::Config(format!("targets inventory_skew_coefficient for {} cannot be negative", track.vault_name)));
+            }
+            if track.poll_interval_ms == 0 {
+                return Err(Error::Config(format!("targets poll_interval_ms for {} must be positive", track.vault_name)));
+            }
+            if track.max_priority_fee_gwei < 0.0 {
+                return Err(Error::Config(format!("targets max_priority_fee_gwei for {} cannot be negative", track.vault_name)));
+            }
+            if track.min_pool_tvl_usd < 0.0 || track.min_active_liquidity < 0.0 {
+                return Err(Error::Config(format!("targets min_pool_tvl_usd and min_active_liquidity for {} cannot be negative", track.vault_name)));
+            }
+
+            if let Some(hedge) = &track.hedge {
+                if hedge.perp_coin.is_empty() {
+                    return Err(Error::Config(format!("targets hedge perp_coin for {} cannot be empty", track.vault_name)));
+                }
+                if hedge.hedge_ratio < 0.0 || hedge.hedge_ratio > 2.0 {
+                    return Err(Error::Config(format!("targets hedge_ratio for {} must be between 0.0 and 2.0", track.vault_name)));
+                }
+                if hedge.tolerance_ratio <= 0.0 || hedge.tolerance_ratio >= 1.0 {
+                    return Err(Error::Config(format!("targets hedge tolerance_ratio for {} must be between 0.0 and 1.0", track.vault_name)));
+                }
+            }
+
+            if let Some(mr) = &track.mean_reversion {
+                if !track.statistical_arb {
+                    return Err(Error::Config(format!("targets mean_reversion for {} requires statistical_arb = true", track.vault_name)));
+                }
+                if mr.min_samples < 10 || mr.window < mr.min_samples {
+                    return Err(Error::Config(format!("targets mean_reversion for {} needs window >= min_samples >= 10", track.vault_name)));
+                }
+                if mr.exit_z < 0.0 || mr.entry_z <= mr.exit_z {
+                    return Err(Error::Config(format!("targets mean_reversion for {} needs entry_z > exit_z >= 0", track.vault_name)));
+                }
+                if mr.max_half_life < 0.0 || mr.trade_size_usd <= 0.0 || mr.max_position_usd < mr.trade_size_usd {
+                    return Err(Error::Config(format!(
+                        "targets mean_reversion for {} needs max_half_life >= 0 and 0 < trade_size_usd <= max_position_usd",
+                        track.vault_name
+                    )));
+                }
+            }
+
+            if let Some(at) = &track.adaptive_threshold {
+                if at.floor_bps > at.ceiling_bps {
+                    return Err(Error::Config(format!("targets adaptive_threshold floor_bps for {} must not exceed ceiling_bps", track.vault_name)));
+                }
+                if at.min_samples < MIN_VOL_SAMPLES || at.vol_window < at.min_samples || at.slippage_window == 0 {
+                    return Err(Error::Config(format!(
+                        "targets adaptive_threshold for {} needs vol_window >= min_samples >= {} and slippage_window > 0",
+                        track.vault_name, MIN_VOL_SAMPLES
+                    )));
+                }
+                if at.target_vol_bps < 0.0 || at.vol_coefficient < 0.0 || at.slippage_coefficient < 0.0 {
+                    return Err(Error::Config(format!(
+                        "targets adaptive_threshold target_vol_bps and coefficients for {} cannot be negative",
+                        track.vault_name
+                    )));
+                }
+            }
+
+            if let Some(cp) = &track.cross_pool {
+                if track.mean_reversion.is_some() {
+                    return Err(Error::Config(format!("targets cross_pool and mean_reversion for {} cannot be combined", track.vault_name)));
+                }
+                if cp.mode != CrossPoolMode::Sequential {
+                    match &cp.contract_address {
+                        Some(address) if Address::from_str(address).is_ok() => {}
+                        _ => return Err(Error::Config(format!("targets cross_pool {} mode for {} needs a valid contract_address", cp.mode, track.vault_name))),
+                    }
+                }
+                if cp.min_profit_usd < 0.0 || cp.max_trade_usd <= 0.0 {
+                    return Err(Error::Config(format!("targets cross_pool for {} needs min_profit_usd >= 0 and max_trade_usd > 0", track.vault_name)));
+                }
             }
 
             // Validate statistical arbitrage configuration
@@ -400,10 +1057,10 @@ impl BotConfig {
         for track in &self.targets {
             let normalized_address = track.address.to_lowercase();
             if !seen_addresses.insert(normalized_address.clone()) {
-                return Err(format!(
+                return Err(Error::Config(format!(
                     "Duplicate targets address found: '{}' ({}). Each address can only be tracked once.",
                     track.address, track.vault_name
-                ));
+                )));
             }
         }
 
@@ -412,10 +1069,10 @@ impl BotConfig {
         for track in &self.targets {
             let normalized_name = track.vault_name.to_lowercase();
             if !seen_names.insert(normalized_name.clone()) {
-                return Err(format!(
+                return Err(Error::Config(format!(
                     "Duplicate vault name found: '{}' (address: {}). Each vault name must be unique.",
                     track.vault_name, track.address
-                ));
+                )));
             }
         }
 
@@ -424,10 +1081,10 @@ impl BotConfig {
             for track in &self.targets {
                 // Check if there's a matching wallet for this vault address
                 if env.get_private_key_for_address(&track.address).is_none() {
-                    return Err(format!(
+                    return Err(Error::Config(format!(
                         "No matching wallet found for vault address '{}' ({}). Please ensure this address is included in WALLET_PUB_KEYS",
                         track.address, track.vault_name
-                    ));
+                    )));
                 }
             }
             tracing::info!("✅ All vault addresses have matching wallets configured");
@@ -461,62 +1118,61 @@ impl ArbTarget {
 
         format!("{}-{}", self.vault_name, address_short)
     }
+
+    /// On-chain ERC20 symbols of the base and quote tokens, the oracle symbols unless overridden
+    pub fn onchain_symbols(&self) -> (&str, &str) {
+        (self.base_token_symbol.as_deref().unwrap_or(&self.base_token), self.quote_token_symbol.as_deref().unwrap_or(&self.quote_token))
+    }
+
+    /// Quote token pegged to the dollar (priced at 1.0 instead of through the oracle)
+    pub fn quote_is_usd(&self) -> bool {
+        matches!(self.quote_token.to_uppercase().as_str(), "USDT0" | "USDC0")
+    }
+
+    /// Executable threshold (bps) for a trade direction given the current inventory skew
+    /// Skew is in [-1, 1]: -1 = all quote, 0 = 50/50, 1 = all base
+    /// Buys add base so they get a higher bar when already long base, sells the opposite
+    pub fn executable_threshold_bps(&self, is_buy: bool, inventory_skew: f64) -> f64 {
+        let skew = inventory_skew.clamp(-1.0, 1.0);
+        let adjustment = self.inventory_skew_coefficient * skew;
+        if is_buy { self.min_executable_spread_bps + adjustment } else { self.min_executable_spread_bps - adjustment }
+    }
+
+    /// Whether a pool is too thin to trade, with the reason
+    /// TVL is None when it was not computed or could not be fetched
+    pub fn thin_pool_reason(&self, active_liquidity: f64, tvl_usd: Option<f64>) -> Option<String> {
+        if active_liquidity < self.min_active_liquidity {
+            return Some(format!("in-range liquidity {:.3e} < {:.3e}", active_liquidity, self.min_active_liquidity));
+        }
+        if self.min_pool_tvl_usd > 0.0 {
+            match tvl_usd {
+                Some(tvl) if tvl < self.min_pool_tvl_usd => return Some(format!("TVL ${:.0} < ${:.0}", tvl, self.min_pool_tvl_usd)),
+                None => return Some("TVL unavailable".to_string()),
+                Some(_) => {}
+            }
+        }
+        None
+    }
 }
 
-pub fn load_bot_config(path: &str) -> BotConfig {
-    let contents = fs::read_to_string(path)
-        .map_err(|e| {
-            tracing::error!("Failed to read config file '{}': {}", path, e);
-            std::process::exit(1);
-        })
-        .unwrap();
-
-    let config: BotConfig = toml::from_str(&contents)
-        .map_err(|e| {
-            tracing::error!("Failed to parse TOML configuration in '{}': {}", path, e);
-            tracing::error!("Please ensure all required sections are present: [global], [hyperevm], [hypercore], [gas], [monitoring]");
-            std::process::exit(1);
-        })
-        .unwrap();
-
-    config
-        .validate(None)
-        .map_err(|e| {
-            tracing::error!("Configuration validation failed: {}", e);
-            std::process::exit(1);
-        })
-        .unwrap();
+/// Read, parse and validate a config file (env is used to check every target has a wallet)
+pub fn try_load_bot_config(path: &str, env_config: Option<&EnvConfig>) -> Result<BotConfig> {
+    let contents = fs::read_to_string(path).map_err(|e| Error::Config(format!("Failed to read config file '{}': {}", path, e)))?;
+    let config: BotConfig = toml::from_str(&contents).map_err(|e| Error::Config(format!("Failed to parse TOML configuration in '{}': {}", path, e)))?;
+    config.validate(env_config)?;
+    Ok(config)
+}
 
+pub fn load_bot_config(path: &str) -> Result<BotConfig> {
+    let config = try_load_bot_config(path, None)?;
     config.print();
-    config
-}
-
-pub fn load_bot_config_with_env(path: &str, env_config: &EnvConfig) -> BotConfig {
-    let contents = fs::read_to_string(path)
-        .map_err(|e| {
-            tracing::error!("Failed to read config file '{}': {}", path, e);
-            std::process::exit(1);
-        })
-        .unwrap();
-
-    let config: BotConfig = toml::from_str(&contents)
-        .map_err(|e| {
-            tracing::error!("Failed to parse TOML configuration in '{}': {}", path, e);
-            tracing::error!("Please ensure all required sections are present: [global], [hyperevm], [hypercore], [gas], [monitoring]");
-            std::process::exit(1);
-        })
-        .unwrap();
-
-    config
-        .validate(Some(env_config))
-        .map_err(|e| {
-            tracing::error!("Configuration validation failed: {}", e);
-            std::process::exit(1);
-        })
-        .unwrap();
+    Ok(config)
+}
 
+pub fn load_bot_config_with_env(path: &str, env_config: &EnvConfig) -> Result<BotConfig> {
+    let config = try_load_bot_config(path, Some(env_config))?;
     config.print();
-    config
+    Ok(config)
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -526,3 +1182,111 @@ pub struct TokenMetadata {
     pub precision: u8,
     pub token: Address,
 }
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// Minimal HYPE/USDT0 target, with `overrides` (TOML key/values) replacing or adding fields
+    pub(crate) fn target(overrides: &str) -> ArbTarget {
+        let mut table: toml::Table = toml::from_str(
+            r#"
+            vault_name = "test"
+            address = "0x0000000000000000000000000000000000000001"
+            base_token = "HYPE"
+            quote_token = "USDT0"
+            hyperswap_pools = []
+            prjx_pools = []
+            min_watch_spread_bps = 1.0
+            min_executable_spread_bps = 5.0
+            max_slippage_pct = 0.0005
+            max_inventory_ratio = 0.5
+            tx_gas_limit = 300000
+            poll_interval_ms = 500
+            publish_events = false
+            skip_simulation = true
+            infinite_approval = true
+            reference = "hypercore"
+            statistical_arb = false
+            "#,
+        )
+        .expect("valid base target");
+        table.extend(toml::from_str::<toml::Table>(overrides).expect("valid overrides"));
+        toml::Value::Table(table).try_into().expect("valid target")
+    }
+
+    /// Config without DEXes or targets, on local endpoints
+    pub(crate) fn config() -> BotConfig {
+        toml::from_str(
+            r#"
+            dex = []
+            targets = []
+            [global]
+            network_name = "test"
+            rpc_endpoint = "http://localhost:8545"
+            websocket_endpoint = "ws://localhost:8546"
+            hyperliquid_api_endpoint = "http://localhost"
+            explorer_base_url = "http://localhost"
+            [hyperevm]
+            core_bridge_contract = ""
+            wrapped_hype_token_address = ""
+            bridge_hype_token_address = ""
+            liqd_multi_hop_router_address = ""
+            liquidswap_api_endpoint = ""
+            [gas]
+            gas_estimate_multiplier = 1.5
+            slippage_tolerance_percent = 5.0
+            native_hype_reserve_amount = 0.1
+            max_gas_price_gwei = 3.0
+            gas_price_multiplier = 1.5
+            "#,
+        )
+        .expect("valid config")
+    }
+
+    #[test]
+    fn test_executable_threshold_symmetric_without_skew() {
+        let target = target("");
+        assert_eq!(target.executable_threshold_bps(true, 0.8), 5.0);
+        assert_eq!(target.executable_threshold_bps(false, -0.8), 5.0);
+    }
+
+    #[test]
+    fn test_executable_threshold_skew() {
+        let target = target("inventory_skew_coefficient = 10.0");
+        // Long base: buying gets harder, selling easier
+        assert_eq!(target.executable_threshold_bps(true, 0.5), 10.0);
+        assert_eq!(target.executable_threshold_bps(false, 0.5), 0.0);
+        // Short base: the opposite
+        assert_eq!(target.executable_threshold_bps(true, -0.5), 0.0);
+        assert_eq!(target.executable_threshold_bps(false, -0.5), 10.0);
+    }
+
+    #[test]
+    fn test_executable_threshold_skew_is_clamped() {
+        let target = target("inventory_skew_coefficient = 10.0");
+        assert_eq!(target.executable_threshold_bps(true, 3.0), 15.0);
+        assert_eq!(target.executable_threshold_bps(false, -3.0), 15.0);
+    }
+
+    #[test]
+    fn test_thin_pool_reason_disabled_filters() {
+        let target = target("");
+        assert_eq!(target.thin_pool_reason(0.0, None), None);
+    }
+
+    #[test]
+    fn test_thin_pool_reason_liquidity() {
+        let target = target("min_active_liquidity = 1e12");
+        assert!(target.thin_pool_reason(1e11, Some(1e9)).unwrap().starts_with("in-range liquidity"));
+        assert_eq!(target.thin_pool_reason(1e12, None), None);
+    }
+
+    #[test]
+    fn test_thin_pool_reason_tvl() {
+        let target = target("min_pool_tvl_usd = 50000.0");
+        assert_eq!(target.thin_pool_reason(1e12, Some(10_000.0)).as_deref(), Some("TVL $10000 < $50000"));
+        assert_eq!(target.thin_pool_reason(1e12, None).as_deref(), Some("TVL unavailable"));
+        assert_eq!(target.thin_pool_reason(1e12, Some(50_000.0)), None);
+    }
+}
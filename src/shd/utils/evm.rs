@@ -1,17 +1,17 @@
-use std::sync::Arc;
-
 use alloy::{
-    eips::eip1559::Eip1559Estimation,
-    network::{Ethereum, EthereumWallet},
+    network::{Ethereum, EthereumWallet, Network},
     primitives::{Address, U256},
     providers::{Provider, ProviderBuilder, RootProvider},
-    rpc::types::TransactionReceipt,
+    rpc::{client::RpcClient, types::TransactionReceipt},
     signers::local::PrivateKeySigner,
 };
 
 use crate::{
+    error::{Error, Result},
+    rpc,
     sol::IERC20,
-    types::{BotConfig, EnvConfig},
+    tx::{TX_TRACKER, TxPolicy, quote_fees},
+    types::{BotConfig, EnvConfig, GasConfig},
 };
 
 /// =============================================================================
@@ -20,16 +20,15 @@ use crate::{
 /// @param provider: Alloy provider instance
 /// @param owner: Owner address as string
 /// @param tokens: Vector of token contract addresses
-/// @return Result<Vec<u128>, String>: Vector of token balances in wei or error
+/// @return Result<Vec<u128>>: Vector of token balances in wei or error
 /// =============================================================================
-pub async fn balances(provider: String, owner: String, tokens: Vec<String>) -> Result<Vec<u128>, String> {
-    let provider = RootProvider::<Ethereum>::new_http(provider.parse().unwrap());
+pub async fn balances<P: Provider<N>, N: Network>(provider: &P, owner: String, tokens: Vec<String>) -> Result<Vec<u128>> {
     let mut balances = vec![];
-    let client = Arc::new(provider);
+    let owner: Address = owner.parse()?;
 
     for token in tokens {
-        let contract = IERC20::new(token.parse().unwrap(), client.clone());
-        match contract.balanceOf(owner.parse().unwrap()).call().await {
+        let contract = IERC20::new(token.parse()?, provider);
+        match contract.balanceOf(owner).call().await {
             Ok(res) => {
                 let balance = res.to_string().parse::<u128>().unwrap_or_default();
                 balances.push(balance);
@@ -51,93 +50,64 @@ pub async fn balances(provider: String, owner: String, tokens: Vec<String>) -> R
 /// @param owner: Token owner address
 /// @param spender: Spender address
 /// @param token: Token contract address
-/// @return Result<u128, String>: Allowance amount in wei or error
+/// @return Result<u128>: Allowance amount in wei or error
 /// =============================================================================
-pub async fn allowance(rpc: String, owner: String, spender: String, token: String) -> Result<u128, String> {
-    let provider = RootProvider::<Ethereum>::new_http(rpc.parse().unwrap());
-    let client = Arc::new(provider);
-    let contract = IERC20::new(token.parse().unwrap(), client.clone());
-    match contract.allowance(owner.parse().unwrap(), spender.parse().unwrap()).call().await {
+pub async fn allowance<P: Provider<N>, N: Network>(provider: &P, owner: String, spender: String, token: String) -> Result<u128> {
+    let contract = IERC20::new(token.parse()?, provider);
+    match contract.allowance(owner.parse()?, spender.parse()?).call().await {
         Ok(allowance) => Ok(allowance.to_string().parse::<u128>().unwrap_or_default()),
         Err(e) => {
             tracing::error!("Failed to get allowance for {}: {:?}", token, e);
-            Err(format!("Failed to get allowance for {}: {:?}", token, e))
-        }
-    }
-}
-
-/// =============================================================================
-/// @function: eip1559_fees
-/// @description: Estimate EIP-1559 gas fees (max fee and priority fee) for the network
-/// @param provider: RPC endpoint URL as string
-/// @return Result<Eip1559Estimation, String>: EIP-1559 fee estimation or error
-/// =============================================================================
-pub async fn eip1559_fees(provider: String) -> Result<Eip1559Estimation, String> {
-    let provider = RootProvider::<Ethereum>::new_http(provider.parse().unwrap());
-    match provider.estimate_eip1559_fees().await {
-        Ok(fees) => Ok(fees),
-        Err(e) => {
-            tracing::error!("Failed to estimate EIP-1559 fees: {:?}", e);
-            Err(format!("Failed to call estimate_eip1559_fees: {:?}", e))
+            Err(Error::Rpc(format!("Failed to get allowance for {}: {:?}", token, e)))
         }
     }
 }
 
-/// Approve token spending
-pub async fn approve(rpc: &str, signer: &PrivateKeySigner, spender: &str, token: &str, amount: u128) -> Option<TransactionReceipt> {
+/// Approve token spending over the given RPC client, with the fees of a swap at the executable threshold (tip capped by the target)
+pub async fn approve(client: RpcClient, signer: &PrivateKeySigner, spender: &str, token: &str, amount: u128, gas: &GasConfig, max_priority_fee_gwei: f64) -> Result<TransactionReceipt> {
     let wallet = EthereumWallet::from(signer.clone());
-    let provider = ProviderBuilder::new().wallet(wallet).connect_http(rpc.parse().unwrap());
-
-    let token_addr: Address = token.parse().unwrap();
-    let spender_addr: Address = spender.parse().unwrap();
-    let contract = IERC20::new(token_addr, Arc::new(provider.clone()));
-
-    match contract.approve(spender_addr, U256::from(amount)).send().await {
-        Ok(pending) => match pending.get_receipt().await {
-            Ok(receipt) => {
-                tracing::info!("Approval tx: 0x{:x} | https://hyperevmscan.io/tx/0x{:x}", receipt.transaction_hash, receipt.transaction_hash);
-                Some(receipt)
-            }
-            Err(e) => {
-                tracing::error!("Failed to get receipt: {:?}", e);
-                None
-            }
-        },
-        Err(e) => {
-            tracing::error!("Failed to approve: {:?}", e);
-            None
-        }
-    }
+    let provider = ProviderBuilder::new().wallet(wallet).connect_client(client);
+
+    let token_addr: Address = token.parse()?;
+    let spender_addr: Address = spender.parse()?;
+    let contract = IERC20::new(token_addr, &provider);
+
+    let fees = quote_fees(&provider, gas, 0.0, max_priority_fee_gwei).await?;
+    let request = contract
+        .approve(spender_addr, U256::from(amount))
+        .max_fee_per_gas(fees.max_fee_per_gas)
+        .max_priority_fee_per_gas(fees.max_priority_fee_per_gas)
+        .into_transaction_request();
+    let receipt = TX_TRACKER.send(&provider, signer.address(), request, TxPolicy::from(gas).capped(&fees)).await?.into_receipt()?;
+    tracing::info!("Approval tx: 0x{:x} | https://hyperevmscan.io/tx/0x{:x}", receipt.transaction_hash, receipt.transaction_hash);
+    Ok(receipt)
 }
 
 /// Get token decimals and balances for two tokens
-pub async fn get_token_info_and_balances(
-    rpc: &str,
+pub async fn get_token_info_and_balances<P: Provider<N>, N: Network>(
+    provider: &P,
     owner: &str,
     base_token: &str,
     quote_token: &str,
-) -> Result<(u8, u8, u128, u128), String> {
-    let provider = RootProvider::<Ethereum>::new_http(rpc.parse().unwrap());
-    let client = Arc::new(provider);
-    
+) -> Result<(u8, u8, u128, u128)> {
     // Parse addresses
-    let base_addr: Address = base_token.parse().map_err(|e| format!("Invalid base token address: {}", e))?;
-    let quote_addr: Address = quote_token.parse().map_err(|e| format!("Invalid quote token address: {}", e))?;
-    let owner_addr: Address = owner.parse().map_err(|e| format!("Invalid owner address: {}", e))?;
+    let base_addr: Address = base_token.parse().map_err(|e| Error::Config(format!("Invalid base token address: {}", e)))?;
+    let quote_addr: Address = quote_token.parse().map_err(|e| Error::Config(format!("Invalid quote token address: {}", e)))?;
+    let owner_addr: Address = owner.parse().map_err(|e| Error::Config(format!("Invalid owner address: {}", e)))?;
     
     // Get base token info
-    let base_contract = IERC20::new(base_addr, client.clone());
+    let base_contract = IERC20::new(base_addr, provider);
     let base_decimals = base_contract.decimals().call().await
-        .map_err(|e| format!("Failed to get base decimals: {:?}", e))?;
+        .map_err(|e| Error::Rpc(format!("Failed to get base decimals: {:?}", e)))?;
     let base_balance = base_contract.balanceOf(owner_addr).call().await
-        .map_err(|e| format!("Failed to get base balance: {:?}", e))?;
+        .map_err(|e| Error::Rpc(format!("Failed to get base balance: {:?}", e)))?;
     
     // Get quote token info
-    let quote_contract = IERC20::new(quote_addr, client.clone());
+    let quote_contract = IERC20::new(quote_addr, provider);
     let quote_decimals = quote_contract.decimals().call().await
-        .map_err(|e| format!("Failed to get quote decimals: {:?}", e))?;
+        .map_err(|e| Error::Rpc(format!("Failed to get quote decimals: {:?}", e)))?;
     let quote_balance = quote_contract.balanceOf(owner_addr).call().await
-        .map_err(|e| format!("Failed to get quote balance: {:?}", e))?;
+        .map_err(|e| Error::Rpc(format!("Failed to get quote balance: {:?}", e)))?;
     
     Ok((
         base_decimals,
@@ -147,10 +117,21 @@ pub async fn get_token_info_and_balances(
     ))
 }
 
+/// Approve the routers for every target's tokens below half the max allowance, only logging them in testing mode
 pub async fn init_allowance(config: &BotConfig, env: &EnvConfig) {
     let target_allowance = u128::MAX / 2;
     let approve_amount = u128::MAX;
 
+    // Reads and approvals go through the configured endpoints (failover on health and latency)
+    let client = match rpc::client(&config.global) {
+        Ok(client) => client,
+        Err(e) => {
+            tracing::error!("Failed to build RPC client for approvals: {}", e);
+            return;
+        }
+    };
+    let provider = RootProvider::<Ethereum>::new(client.clone());
+
     // Get DEX routers
     let hyperswap = config.get_dex("hyperswap");
     let projectx = config.get_dex("projectx");
@@ -178,23 +159,35 @@ pub async fn init_allowance(config: &BotConfig, env: &EnvConfig) {
         for (dex_name, router) in routers {
             if let Some(router_addr) = router {
                 // Check base token allowance
-                let base_allowance = allowance(config.global.rpc_endpoint.clone(), target.address.clone(), router_addr.clone(), target.base_token_address.clone())
+                let base_allowance = allowance(&provider, target.address.clone(), router_addr.clone(), target.base_token_address.clone())
                     .await
                     .unwrap_or(0);
 
                 if base_allowance < target_allowance {
-                    tracing::info!("{} {} allowance insufficient, approving...", dex_name, target.base_token);
-                    approve(&config.global.rpc_endpoint, &signer, router_addr, &target.base_token_address, approve_amount).await;
+                    if env.testing {
+                        tracing::info!("🧪 Dry run: {} {} allowance insufficient, would approve {}", dex_name, target.base_token, router_addr);
+                    } else {
+                        tracing::info!("{} {} allowance insufficient, approving...", dex_name, target.base_token);
+                        if let Err(e) = approve(client.clone(), &signer, router_addr, &target.base_token_address, approve_amount, &config.gas, target.max_priority_fee_gwei).await {
+                            tracing::error!("{} {} approval failed: {}", dex_name, target.base_token, e);
+                        }
+                    }
                 }
 
                 // Check quote token allowance
-                let quote_allowance = allowance(config.global.rpc_endpoint.clone(), target.address.clone(), router_addr.clone(), target.quote_token_address.clone())
+                let quote_allowance = allowance(&provider, target.address.clone(), router_addr.clone(), target.quote_token_address.clone())
                     .await
                     .unwrap_or(0);
 
                 if quote_allowance < target_allowance {
-                    tracing::info!("{} {} allowance insufficient, approving...", dex_name, target.quote_token);
-                    approve(&config.global.rpc_endpoint, &signer, router_addr, &target.quote_token_address, approve_amount).await;
+                    if env.testing {
+                        tracing::info!("🧪 Dry run: {} {} allowance insufficient, would approve {}", dex_name, target.quote_token, router_addr);
+                    } else {
+                        tracing::info!("{} {} allowance insufficient, approving...", dex_name, target.quote_token);
+                        if let Err(e) = approve(client.clone(), &signer, router_addr, &target.quote_token_address, approve_amount, &config.gas, target.max_priority_fee_gwei).await {
+                            tracing::error!("{} {} approval failed: {}", dex_name, target.quote_token, e);
+                        }
+                    }
                 }
             }
         }
@@ -12,29 +12,95 @@ use alloy::{
     primitives::Address,
     providers::{Provider, RootProvider},
 };
+use arrow_array::RecordBatch;
+use parquet::{
+    arrow::{ArrowWriter, arrow_reader::ParquetRecordBatchReaderBuilder},
+    basic::Compression,
+    file::properties::WriterProperties,
+};
 use serde::{Serialize, de::DeserializeOwned};
 
-use crate::{sol::IERC20, types::TokenMetadata};
+use crate::{
+    error::{Error, Result},
+    sol::IERC20,
+    types::TokenMetadata,
+};
 
 /// Constants
 pub const BASIS_POINT_DENOMINATOR: f64 = 10000.0;
 pub const TICK_BASE: f64 = 1.0001;
 
-pub fn read<T: DeserializeOwned>(file: &str) -> Vec<T> {
-    let mut f = File::open(file).unwrap();
+/// Read a JSON array file
+pub fn read<T: DeserializeOwned>(file: &str) -> Result<Vec<T>> {
+    let mut f = File::open(file).map_err(|e| Error::Io(format!("Failed to open {}: {}", file, e)))?;
     let mut buffer = String::new();
-    f.read_to_string(&mut buffer).unwrap();
-    let db: Vec<T> = serde_json::from_str(&buffer).unwrap();
-    db
+    f.read_to_string(&mut buffer)?;
+    serde_json::from_str(&buffer).map_err(|e| Error::Io(format!("Failed to parse {}: {}", file, e)))
+}
+
+/// Write rows to a JSON array file, replacing it
+pub fn save<T: Serialize>(output: Vec<T>, file: &str) -> Result<()> {
+    let mut file = OpenOptions::new()
+        .create(true)
+        .write(true)
+        .truncate(true)
+        .open(file)
+        .map_err(|e| Error::Io(format!("Failed to create {}: {}", file, e)))?;
+    let json = serde_json::to_string(&output).map_err(|e| Error::Io(format!("Failed to serialize JSON: {}", e)))?;
+    file.write_all(json.as_bytes())?;
+    file.write_all(b"\n")?;
+    file.flush()?;
+    Ok(())
+}
+
+/// Append rows to a CSV file, writing the header when the file is new
+pub fn append_csv<T: Serialize>(output: &[T], file: &str) -> Result<()> {
+    let exists = std::path::Path::new(file).exists();
+    let handle = OpenOptions::new().create(true).append(true).open(file)?;
+    let mut writer = csv::WriterBuilder::new().has_headers(!exists).from_writer(handle);
+    for row in output {
+        writer.serialize(row).map_err(|e| Error::Io(format!("Failed to write {}: {}", file, e)))?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+/// Write rows to a CSV file with a header, replacing it
+pub fn save_csv<T: Serialize>(output: &[T], file: &str) -> Result<()> {
+    let mut writer = csv::Writer::from_path(file).map_err(|e| Error::Io(format!("Failed to create {}: {}", file, e)))?;
+    for row in output {
+        writer.serialize(row).map_err(|e| Error::Io(format!("Failed to write {}: {}", file, e)))?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+/// Read every row of a CSV file with a header
+pub fn read_csv<T: DeserializeOwned>(file: &str) -> Result<Vec<T>> {
+    let mut reader = csv::Reader::from_path(file).map_err(|e| Error::Io(format!("Failed to open {}: {}", file, e)))?;
+    reader
+        .deserialize()
+        .collect::<std::result::Result<Vec<T>, _>>()
+        .map_err(|e| Error::Io(format!("Failed to read {}: {}", file, e)))
 }
 
-pub fn save<T: Serialize>(output: Vec<T>, file: &str) {
-    // log::info!("Saving to file: {}", file);
-    let mut file = OpenOptions::new().create(true).write(true).truncate(true).open(file).expect("Failed to open or create file");
-    let json = serde_json::to_string(&output).expect("Failed to serialize JSON");
-    file.write_all(json.as_bytes()).expect("Failed to write to file");
-    file.write_all(b"\n").expect("Failed to write newline to file");
-    file.flush().expect("Failed to flush file");
+/// Read every record batch of a Parquet file
+pub fn read_parquet(file: &str) -> Result<Vec<RecordBatch>> {
+    let handle = File::open(file)?;
+    let reader = ParquetRecordBatchReaderBuilder::try_new(handle)
+        .and_then(|builder| builder.build())
+        .map_err(|e| Error::Io(format!("Failed to open {}: {}", file, e)))?;
+    reader.collect::<std::result::Result<Vec<_>, _>>().map_err(|e| Error::Io(format!("Failed to read {}: {}", file, e)))
+}
+
+/// Write a record batch to a new Snappy-compressed Parquet file
+pub fn save_parquet(batch: &RecordBatch, file: &str) -> Result<()> {
+    let handle = OpenOptions::new().create(true).write(true).truncate(true).open(file)?;
+    let properties = WriterProperties::builder().set_compression(Compression::SNAPPY).build();
+    let mut writer = ArrowWriter::try_new(handle, batch.schema(), Some(properties)).map_err(|e| Error::Io(format!("Failed to create {}: {}", file, e)))?;
+    writer.write(batch).map_err(|e| Error::Io(format!("Failed to write {}: {}", file, e)))?;
+    writer.close().map_err(|e| Error::Io(format!("Failed to close {}: {}", file, e)))?;
+    Ok(())
 }
 
 // === EVM UTILITIES ===
@@ -42,30 +108,21 @@ pub fn save<T: Serialize>(output: Vec<T>, file: &str) {
 /**
  * Get current block number from provider
  */
-pub async fn block<T: Network>(provider: RootProvider<T>) -> Result<u64, String> {
-    match provider.get_block_number().await {
-        Ok(current) => Ok(current),
-        Err(e) => Err(e.to_string()),
-    }
+pub async fn block<T: Network>(provider: RootProvider<T>) -> Result<u64> {
+    Ok(provider.get_block_number().await?)
 }
 
 /**
  * Fetch the metadata of an ERC20 token
  */
-pub async fn token_metadata<T: Network>(provider: &RootProvider<T>, token: String) -> TokenMetadata {
+pub async fn token_metadata<T: Network>(provider: &RootProvider<T>, token: String) -> Result<TokenMetadata> {
     let client = Arc::new(provider);
-    let token = Address::from_str(&token).unwrap();
+    let token = Address::from_str(&token)?;
     let contract = IERC20::new(token, client);
-    let name = contract.name().call().await;
-    let precision = contract.decimals().call().await;
-    let sym = contract.symbol().call().await;
-    match (name, precision, sym) {
-        (Ok(name), Ok(precision), Ok(sym)) => TokenMetadata { name, precision, token, sym },
-        _ => {
-            tracing::warn!("🔺 Erc20::metadata: unknown name|precision|symbol");
-            TokenMetadata::default()
-        }
-    }
+    let name = contract.name().call().await?;
+    let precision = contract.decimals().call().await?;
+    let sym = contract.symbol().call().await?;
+    Ok(TokenMetadata { name, precision, token, sym })
 }
 
 /**
@@ -95,9 +152,9 @@ static GAS_FULL_REBALANCE: u64 = GAS_DECREASE_LIQUIDITY + GAS_COLLECT + (2 * GAS
  * Get and log current network gas prices in native token and USD
  * Returns (gas_price_wei, gas_price_gwei, gas_price_usd_per_transfer)
  */
-pub async fn log_gas_prices<T: Network>(provider: RootProvider<T>, gas_token_usd_price: f64) -> Result<(u128, f64, f64), String> {
+pub async fn log_gas_prices<T: Network>(provider: RootProvider<T>, gas_token_usd_price: f64) -> Result<(u128, f64, f64)> {
     // Get current gas price from the network
-    let gas_price_wei = provider.get_gas_price().await.map_err(|e| format!("Failed to get gas price: {}", e))?;
+    let gas_price_wei = provider.get_gas_price().await.map_err(|e| Error::Rpc(format!("Failed to get gas price: {}", e)))?;
 
     // Convert to Gwei (1 Gwei = 10^9 Wei)
     let gas_price_gwei = gas_price_wei as f64 / 1e9;